@@ -0,0 +1,255 @@
+//! Construction of a [`Book`] in code, one marker at a time.
+//!
+//! ```
+//! use usfm::{BookBuilder, BookIdentifier, Caller, Footnote, FootnoteElementStyle};
+//!
+//! let book = BookBuilder::new(BookIdentifier::Genesis)
+//!     .h("Genesis")
+//!     .chapter(1)
+//!     .p()
+//!     .verse(1)
+//!     .text("In the beginning, God")
+//!     .footnote(
+//!         Footnote::new(Caller::Auto)
+//!             .element(FootnoteElementStyle::Reference, "1:1 ")
+//!             .element(FootnoteElementStyle::Text, "Or, Elohim"),
+//!     )
+//!     .text(" created the heavens and the earth.")
+//!     .build();
+//! assert_eq!(book.verses().next().unwrap().text, "In the beginning, God created the heavens and the earth.");
+//! ```
+
+use crate::parser::ParseError;
+use crate::usfm::{
+    Book, BookContents, BookIdentifier, Caller, Character, CharacterContents, CharacterType,
+    CrossRef, CrossRefElement, CrossRefElementStyle, CrossRefStyle, Element, ElementContents,
+    ElementType, Footnote, FootnoteElement, FootnoteElementStyle, FootnoteStyle, Paragraph,
+    ParagraphContents, ParagraphStyle, Poetry, PoetryStyle, VerseNumber,
+};
+
+/// Builds a book from its markers in the order they would be written.
+///
+/// Verses and inline content go into the paragraph, poetry line or heading that was started
+/// last. Verses, and text with no paragraph or heading to go into, start a `\p` paragraph.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BookBuilder {
+    contents: Vec<BookContents>,
+}
+
+impl BookBuilder {
+    /// Starts a book with `\id` and its book code.
+    pub fn new(code: BookIdentifier) -> Self {
+        Self::default().push(BookContents::Id { code, text: None })
+    }
+
+    /// Adds any block, such as `\usfm`, `\b` or a table row.
+    pub fn push(mut self, content: BookContents) -> Self {
+        self.contents.push(content);
+        self
+    }
+
+    pub fn chapter(self, chapter: u16) -> Self {
+        self.push(BookContents::Chapter(chapter))
+    }
+
+    /// Starts a heading or other element holding `text`.
+    pub fn element(self, ty: ElementType, text: impl Into<String>) -> Self {
+        self.push(BookContents::Element(Element {
+            ty,
+            contents: vec![ElementContents::Line(text.into())],
+        }))
+    }
+
+    /// The running header, `\h`.
+    pub fn h(self, text: impl Into<String>) -> Self {
+        self.element(ElementType::Header, text)
+    }
+
+    /// A major title, `\mt1`.
+    pub fn mt(self, text: impl Into<String>) -> Self {
+        self.element(ElementType::MajorTitle(1), text)
+    }
+
+    /// A section heading, `\s1`.
+    pub fn s(self, text: impl Into<String>) -> Self {
+        self.element(ElementType::Section(1), text)
+    }
+
+    pub fn paragraph(self, style: ParagraphStyle) -> Self {
+        self.push(BookContents::Paragraph(Paragraph {
+            style,
+            contents: Vec::new(),
+        }))
+    }
+
+    /// A normal paragraph, `\p`.
+    pub fn p(self) -> Self {
+        self.paragraph(ParagraphStyle::Normal)
+    }
+
+    /// A paragraph without a first-line indent, `\m`.
+    pub fn m(self) -> Self {
+        self.paragraph(ParagraphStyle::Margin)
+    }
+
+    pub fn poetry(self, style: PoetryStyle) -> Self {
+        self.push(BookContents::Poetry(Poetry {
+            style,
+            contents: Vec::new(),
+        }))
+    }
+
+    /// A poetic line indented to `level`, `\q1`–`\q4`.
+    pub fn q(self, level: u8) -> Self {
+        self.poetry(PoetryStyle::Normal(level))
+    }
+
+    /// A verse, given by its number or a parsed [`VerseNumber`]. Use [`verse_str`] for
+    /// spellings such as `4a` or `1-3`.
+    ///
+    /// [`verse_str`]: Self::verse_str
+    pub fn verse(mut self, verse: impl Into<VerseNumber>) -> Self {
+        self.paragraph_contents()
+            .push(ParagraphContents::Verse(verse.into()));
+        self
+    }
+
+    /// A verse spelled as it would be after `\v`, such as `4a` or `1-3`.
+    pub fn verse_str(self, verse: &str) -> Result<Self, ParseError> {
+        Ok(self.verse(VerseNumber::try_from(verse)?))
+    }
+
+    pub fn text(self, text: impl Into<String>) -> Self {
+        self.inline(CharacterContents::Line(text.into()))
+    }
+
+    /// A character style holding `text`, such as `\nd` or `\wj`.
+    pub fn character(self, ty: CharacterType, text: impl Into<String>) -> Self {
+        self.inline(CharacterContents::Character(Character::new(ty, text)))
+    }
+
+    pub fn footnote(self, footnote: Footnote) -> Self {
+        self.inline(CharacterContents::Footnote(footnote))
+    }
+
+    pub fn cross_ref(self, cross_ref: CrossRef) -> Self {
+        self.inline(CharacterContents::CrossRef(cross_ref))
+    }
+
+    /// Adds any inline content, such as a figure or milestone.
+    pub fn inline(mut self, content: CharacterContents) -> Self {
+        if let Some(BookContents::Element(e)) = self.contents.last_mut() {
+            e.contents.push(to_element_contents(content));
+        } else {
+            self.paragraph_contents()
+                .push(to_paragraph_contents(content));
+        }
+        self
+    }
+
+    pub fn build(self) -> Book {
+        Book {
+            contents: self.contents,
+        }
+    }
+
+    /// The contents of the paragraph started last, starting a `\p` if there is none.
+    fn paragraph_contents(&mut self) -> &mut Vec<ParagraphContents> {
+        if !matches!(
+            self.contents.last(),
+            Some(BookContents::Paragraph(_) | BookContents::Poetry(_) | BookContents::Custom(_))
+        ) {
+            self.contents.push(BookContents::Paragraph(Paragraph {
+                style: ParagraphStyle::Normal,
+                contents: Vec::new(),
+            }));
+        }
+        match self.contents.last_mut() {
+            Some(BookContents::Paragraph(p)) => &mut p.contents,
+            Some(BookContents::Poetry(p)) => &mut p.contents,
+            Some(BookContents::Custom(p)) => &mut p.contents,
+            _ => unreachable!(),
+        }
+    }
+}
+
+impl Character {
+    pub fn new(ty: CharacterType, text: impl Into<String>) -> Self {
+        Self {
+            ty,
+            contents: vec![CharacterContents::Line(text.into())],
+            attributes: Vec::new(),
+        }
+    }
+
+    pub fn attribute(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.attributes.push((key.into(), value.into()));
+        self
+    }
+}
+
+impl Footnote {
+    /// A `\f` footnote without elements.
+    pub fn new(caller: Caller) -> Self {
+        Self {
+            style: FootnoteStyle::Footnote,
+            caller,
+            elements: Vec::new(),
+        }
+    }
+
+    pub fn element(mut self, style: FootnoteElementStyle, text: impl Into<String>) -> Self {
+        self.elements.push(FootnoteElement {
+            style,
+            contents: vec![CharacterContents::Line(text.into())],
+        });
+        self
+    }
+}
+
+impl CrossRef {
+    /// A `\x` cross-reference without elements.
+    pub fn new(caller: Caller) -> Self {
+        Self {
+            style: CrossRefStyle::CrossRef,
+            caller,
+            elements: Vec::new(),
+        }
+    }
+
+    pub fn element(mut self, style: CrossRefElementStyle, text: impl Into<String>) -> Self {
+        self.elements.push(CrossRefElement {
+            style,
+            contents: vec![CharacterContents::Line(text.into())],
+        });
+        self
+    }
+}
+
+fn to_paragraph_contents(content: CharacterContents) -> ParagraphContents {
+    use CharacterContents as C;
+    match content {
+        C::Line(text) => ParagraphContents::Line(text),
+        C::Character(c) => ParagraphContents::Character(c),
+        C::Footnote(f) => ParagraphContents::Footnote(f),
+        C::CrossRef(x) => ParagraphContents::CrossRef(x),
+        C::Figure(fig) => ParagraphContents::Figure(fig),
+        C::Milestone(ms) => ParagraphContents::Milestone(ms),
+        C::OptionalBreak => ParagraphContents::OptionalBreak,
+        C::Custom(c) => ParagraphContents::Custom(c),
+    }
+}
+
+fn to_element_contents(content: CharacterContents) -> ElementContents {
+    use CharacterContents as C;
+    match content {
+        C::Line(text) => ElementContents::Line(text),
+        C::Character(c) => ElementContents::Character(c),
+        C::Footnote(f) => ElementContents::Footnote(f),
+        C::CrossRef(x) => ElementContents::CrossRef(x),
+        C::Figure(fig) => ElementContents::Figure(fig),
+        C::Milestone(ms) => ElementContents::Milestone(ms),
+        C::OptionalBreak => ElementContents::OptionalBreak,
+        C::Custom(c) => ElementContents::Custom(c),
+    }
+}
@@ -0,0 +1,285 @@
+//! Changing the verses of a [`Book`] in place, by reference.
+//!
+//! Verses are found through the [`BookIndex`](crate::BookIndex), so a verse that continues across
+//! paragraph or poetry breaks is edited in each of its parts, and a reference that names a book
+//! other than the one of `\id` finds no verse. Offsets into a verse count the characters of its
+//! text as written, including the text of character styles but not that of notes.
+
+use std::ops::Range;
+
+use crate::index::VersePart;
+use crate::reference::Reference;
+use crate::usfm::{
+    Book, BookContents, Character, CharacterContents, CharacterType, Footnote, ParagraphContents,
+};
+
+impl Book {
+    /// Replaces everything in a verse after its `\v` marker, including its notes, with `text`.
+    ///
+    /// A verse that continues into later paragraphs or poetry lines is cut back to the first,
+    /// and the paragraphs this leaves empty are removed. Returns `false` if there is no such
+    /// verse.
+    pub fn set_verse_text(&mut self, reference: &Reference, text: impl Into<String>) -> bool {
+        let Some(entry) = self.index().get(reference).cloned() else {
+            return false;
+        };
+        let Some((first, rest)) = entry.parts.split_first() else {
+            return false;
+        };
+        self.remove_parts(rest);
+        let range = text_range(first, true);
+        contents_mut(self, &first.path).splice(range, [ParagraphContents::Line(text.into())]);
+        true
+    }
+
+    /// Removes a verse, its `\v` marker and everything in it, along with the paragraphs this
+    /// leaves empty. Returns `false` if there is no such verse.
+    pub fn delete_verse(&mut self, reference: &Reference) -> bool {
+        let Some(entry) = self.index().get(reference).cloned() else {
+            return false;
+        };
+        self.remove_parts(&entry.parts);
+        true
+    }
+
+    /// Inserts `footnote` into a verse, `offset` characters into its text.
+    ///
+    /// A character style the offset falls inside is split in two around the footnote. Returns
+    /// `false` if there is no such verse or the offset is past its end.
+    pub fn insert_footnote_at(
+        &mut self,
+        reference: &Reference,
+        offset: usize,
+        footnote: Footnote,
+    ) -> bool {
+        let Some(entry) = self.index().get(reference).cloned() else {
+            return false;
+        };
+        let mut start = 0;
+        for (k, part) in entry.parts.iter().enumerate() {
+            let range = text_range(part, k == 0);
+            let contents = contents_mut(self, &part.path);
+            let len = text_len(&contents[range.clone()]);
+            if offset <= start + len {
+                let i = boundary(contents, range, offset - start);
+                contents.insert(i, ParagraphContents::Footnote(footnote));
+                return true;
+            }
+            start += len;
+        }
+        false
+    }
+
+    /// Wraps the characters of a verse in `range` in a character style of type `ty`.
+    ///
+    /// A range that continues across a paragraph or poetry break is wrapped in one character
+    /// style in each. Text and character styles the range starts or ends inside are split at
+    /// its bounds. Returns `false` if there is no such verse, the range is empty or goes past
+    /// the end of the verse, or a part of the verse it covers holds a `\cat` marker.
+    pub fn wrap_in_character(
+        &mut self,
+        reference: &Reference,
+        range: Range<usize>,
+        ty: CharacterType,
+    ) -> bool {
+        let Some(entry) = self.index().get(reference).cloned() else {
+            return false;
+        };
+        if range.is_empty() {
+            return false;
+        }
+        let mut wraps = Vec::new();
+        let mut start = 0;
+        for (k, part) in entry.parts.iter().enumerate() {
+            let contents = &contents_mut(self, &part.path)[text_range(part, k == 0)];
+            let len = text_len(contents);
+            let (a, b) = (range.start.max(start), range.end.min(start + len));
+            if a < b {
+                if contents
+                    .iter()
+                    .any(|c| matches!(c, ParagraphContents::Category(_)))
+                {
+                    return false;
+                }
+                wraps.push((part, k == 0, a - start..b - start));
+            }
+            start += len;
+        }
+        if range.end > start {
+            return false;
+        }
+
+        for (part, first, wrap) in wraps {
+            let mut range = text_range(part, first);
+            let contents = contents_mut(self, &part.path);
+            let len = contents.len();
+            let i = boundary(contents, range.clone(), wrap.start);
+            range.end += contents.len() - len;
+            let j = boundary(contents, i..range.end, wrap.len());
+            let wrapped = contents
+                .drain(i..j)
+                .filter_map(to_character_contents)
+                .collect();
+            contents.insert(
+                i,
+                ParagraphContents::Character(Character {
+                    ty: ty.clone(),
+                    contents: wrapped,
+                    attributes: Vec::new(),
+                }),
+            );
+        }
+        true
+    }
+
+    /// Removes the contents of `parts`, last first so the paths of earlier parts stay valid.
+    fn remove_parts(&mut self, parts: &[VersePart]) {
+        for part in parts.iter().rev() {
+            let contents = contents_mut(self, &part.path);
+            contents.drain(part.contents.clone());
+            if contents.is_empty() && part.path.len() == 1 {
+                self.contents.remove(part.path[0]);
+            }
+        }
+    }
+}
+
+/// The contents of the paragraph, poetry line or table cell at `path`, which the index
+/// has just pointed to.
+fn contents_mut<'a>(book: &'a mut Book, path: &[usize]) -> &'a mut Vec<ParagraphContents> {
+    match (path, &mut book.contents[path[0]]) {
+        ([_], BookContents::Paragraph(p)) => &mut p.contents,
+        ([_], BookContents::Poetry(p)) => &mut p.contents,
+        ([_], BookContents::Custom(p)) => &mut p.contents,
+        ([_, j], BookContents::TableRow(tr)) => &mut tr.cells[*j].contents,
+        _ => unreachable!("verse parts are in paragraphs, poetry and table cells"),
+    }
+}
+
+/// The contents of a part that hold its text, which in the first part of a verse follow its
+/// `\v` marker.
+fn text_range(part: &VersePart, first: bool) -> Range<usize> {
+    let start = part.contents.start + usize::from(first);
+    start..part.contents.end
+}
+
+/// Content that text offsets count through.
+trait Inline: Sized {
+    fn text_len(&self) -> usize;
+
+    /// Splits this node `at` characters into its text, somewhere strictly inside it.
+    fn split_at(self, at: usize) -> (Self, Self);
+}
+
+impl Inline for ParagraphContents {
+    fn text_len(&self) -> usize {
+        match self {
+            ParagraphContents::Line(line) => line.chars().count(),
+            ParagraphContents::Character(c) => text_len(&c.contents),
+            _ => 0,
+        }
+    }
+
+    fn split_at(self, at: usize) -> (Self, Self) {
+        use ParagraphContents as C;
+        match self {
+            C::Line(line) => {
+                let (head, tail) = split_line(line, at);
+                (C::Line(head), C::Line(tail))
+            }
+            C::Character(c) => {
+                let (head, tail) = split_character(c, at);
+                (C::Character(head), C::Character(tail))
+            }
+            _ => unreachable!("only text is split"),
+        }
+    }
+}
+
+impl Inline for CharacterContents {
+    fn text_len(&self) -> usize {
+        match self {
+            CharacterContents::Line(line) => line.chars().count(),
+            CharacterContents::Character(c) => text_len(&c.contents),
+            _ => 0,
+        }
+    }
+
+    fn split_at(self, at: usize) -> (Self, Self) {
+        use CharacterContents as C;
+        match self {
+            C::Line(line) => {
+                let (head, tail) = split_line(line, at);
+                (C::Line(head), C::Line(tail))
+            }
+            C::Character(c) => {
+                let (head, tail) = split_character(c, at);
+                (C::Character(head), C::Character(tail))
+            }
+            _ => unreachable!("only text is split"),
+        }
+    }
+}
+
+fn text_len<T: Inline>(contents: &[T]) -> usize {
+    contents.iter().map(Inline::text_len).sum()
+}
+
+/// The index in `contents` at which `offset` characters of the text of `range` start,
+/// splitting the node they fall inside. An offset past the text of `range` gives its end.
+fn boundary<T: Inline>(contents: &mut Vec<T>, range: Range<usize>, offset: usize) -> usize {
+    let mut pos = 0;
+    for i in range.clone() {
+        if pos == offset {
+            return i;
+        }
+        let len = contents[i].text_len();
+        if offset < pos + len {
+            let (head, tail) = contents.remove(i).split_at(offset - pos);
+            contents.insert(i, tail);
+            contents.insert(i, head);
+            return i + 1;
+        }
+        pos += len;
+    }
+    range.end
+}
+
+fn split_line(mut line: String, at: usize) -> (String, String) {
+    let i = line.char_indices().nth(at).map_or(line.len(), |(i, _)| i);
+    let tail = line.split_off(i);
+    (line, tail)
+}
+
+fn split_character(c: Character, at: usize) -> (Character, Character) {
+    let mut contents = c.contents;
+    let len = contents.len();
+    let i = boundary(&mut contents, 0..len, at);
+    let tail = contents.split_off(i);
+    let head = Character {
+        ty: c.ty.clone(),
+        contents,
+        attributes: c.attributes.clone(),
+    };
+    let tail = Character {
+        ty: c.ty,
+        contents: tail,
+        attributes: c.attributes,
+    };
+    (head, tail)
+}
+
+pub(crate) fn to_character_contents(content: ParagraphContents) -> Option<CharacterContents> {
+    use ParagraphContents as C;
+    Some(match content {
+        C::Line(text) => CharacterContents::Line(text),
+        C::Character(c) => CharacterContents::Character(c),
+        C::Footnote(f) => CharacterContents::Footnote(f),
+        C::CrossRef(x) => CharacterContents::CrossRef(x),
+        C::Figure(fig) => CharacterContents::Figure(fig),
+        C::Milestone(ms) => CharacterContents::Milestone(ms),
+        C::OptionalBreak => CharacterContents::OptionalBreak,
+        C::Custom(c) => CharacterContents::Custom(c),
+        C::Verse(_) | C::Category(_) => return None,
+    })
+}
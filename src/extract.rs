@@ -0,0 +1,166 @@
+//! Extraction of a passage of a [`Book`] as a book of its own.
+
+use crate::reference::RefRange;
+use crate::usfm::{
+    Book, BookContents, CustomParagraph, ElementType, Paragraph, ParagraphContents, Poetry,
+    TableCell, TableRow,
+};
+
+impl Book {
+    /// A copy of this book holding only the verses in `range`, in this book's part of it if it
+    /// names books.
+    ///
+    /// The `\id` and header markers are kept, along with the chapter markers, headings and other
+    /// blocks that lead into verses in the range. Paragraphs, poetry and table rows that start
+    /// or end outside the range are cut down to the part inside it, keeping their style and any
+    /// notes. A range without verses covers whole chapters.
+    pub fn extract(&self, range: &RefRange) -> Book {
+        // A range that leaves this book out keeps nothing but the header.
+        let (start, end) = range
+            .bounds_in(self.code())
+            .unwrap_or(((u16::MAX, u16::MAX), (0, 0)));
+        let mut extractor = Extractor {
+            start,
+            end,
+            chapter: 0,
+            verse: (0, 0),
+            pending_chapter: None,
+            pending: Vec::new(),
+            contents: Vec::new(),
+        };
+        for content in &self.contents {
+            extractor.push(content);
+        }
+        Book {
+            contents: extractor.contents,
+        }
+    }
+}
+
+struct Extractor {
+    start: (u16, u16),
+    end: (u16, u16),
+    chapter: u16,
+    /// The first and last verse of the current verse number, or zero before the first verse of
+    /// a chapter.
+    verse: (u16, u16),
+    pending_chapter: Option<u16>,
+    /// Blocks that are kept only if the verses that follow them are.
+    pending: Vec<BookContents>,
+    contents: Vec<BookContents>,
+}
+
+impl Extractor {
+    fn push(&mut self, content: &BookContents) {
+        use BookContents as C;
+        match content {
+            C::Id { .. } | C::Usfm(_) | C::Encoding(_) | C::Status(_) => {
+                self.contents.push(content.clone())
+            }
+            C::Element(e)
+                if matches!(
+                    e.ty,
+                    ElementType::Header | ElementType::Contents(_) | ElementType::AltContents(_)
+                ) =>
+            {
+                self.contents.push(content.clone())
+            }
+            C::Chapter(chapter) => {
+                self.chapter = *chapter;
+                self.verse = (0, 0);
+                self.pending.clear();
+                self.pending_chapter = (self.start.0..=self.end.0)
+                    .contains(chapter)
+                    .then_some(*chapter);
+            }
+            C::Paragraph(p) => {
+                if let Some(contents) = self.filter(&p.contents) {
+                    self.contents.push(C::Paragraph(Paragraph {
+                        style: p.style.clone(),
+                        contents,
+                    }));
+                }
+            }
+            C::Poetry(p) => {
+                if let Some(contents) = self.filter(&p.contents) {
+                    self.contents.push(C::Poetry(Poetry {
+                        style: p.style.clone(),
+                        contents,
+                    }));
+                }
+            }
+            C::Custom(p) => {
+                if let Some(contents) = self.filter(&p.contents) {
+                    self.contents.push(C::Custom(CustomParagraph {
+                        marker: p.marker.clone(),
+                        contents,
+                    }));
+                }
+            }
+            C::TableRow(tr) => {
+                let mut kept = false;
+                let cells = tr
+                    .cells
+                    .iter()
+                    .map(|cell| {
+                        let contents = self.filter(&cell.contents);
+                        kept |= contents.is_some();
+                        TableCell {
+                            prefix: cell.prefix.clone(),
+                            column: cell.column,
+                            contents: contents.unwrap_or_default(),
+                        }
+                    })
+                    .collect();
+                if kept {
+                    self.contents.push(C::TableRow(TableRow { cells }));
+                }
+            }
+            _ if self.is_past_end() => {}
+            _ => self.pending.push(content.clone()),
+        }
+    }
+
+    /// Keeps the contents inside the range, and the blocks pending before them.
+    fn filter(&mut self, contents: &[ParagraphContents]) -> Option<Vec<ParagraphContents>> {
+        let mut kept = Vec::new();
+        for (i, content) in contents.iter().enumerate() {
+            if let ParagraphContents::Verse(verse) = content {
+                self.verse = (verse.start, verse.end);
+            }
+            if !self.is_in_range() {
+                continue;
+            }
+            if kept.is_empty() {
+                // Blocks only lead into the range if nothing outside it comes between.
+                if i > 0 {
+                    self.pending.clear();
+                }
+                self.flush();
+            }
+            kept.push(content.clone());
+        }
+        if kept.is_empty() {
+            if !contents.is_empty() {
+                self.pending.clear();
+            }
+            return None;
+        }
+        Some(kept)
+    }
+
+    fn flush(&mut self) {
+        if let Some(chapter) = self.pending_chapter.take() {
+            self.contents.push(BookContents::Chapter(chapter));
+        }
+        self.contents.append(&mut self.pending);
+    }
+
+    fn is_in_range(&self) -> bool {
+        (self.chapter, self.verse.1) >= self.start && !self.is_past_end()
+    }
+
+    fn is_past_end(&self) -> bool {
+        (self.chapter, self.verse.0) > self.end
+    }
+}
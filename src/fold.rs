@@ -0,0 +1,372 @@
+//! Transformation of a [`Book`] by value, rebuilding each node from its folded children.
+//!
+//! This works like [`Visit`](crate::visit::Visit), with each method taking a node and
+//! returning its replacement:
+//!
+//! ```
+//! use usfm::fold::Fold;
+//!
+//! struct Upper;
+//!
+//! impl Fold for Upper {
+//!     fn fold_text(&mut self, text: String) -> String {
+//!         text.to_uppercase()
+//!     }
+//! }
+//!
+//! let book = usfm::parse("\\id GEN\n\\p\n\\v 1 In the beginning.\n").unwrap();
+//! let book = Upper.fold_book(book);
+//! assert!(usfm::format(&book).contains("IN THE BEGINNING."));
+//! ```
+
+use crate::usfm::*;
+
+pub trait Fold {
+    fn fold_book(&mut self, node: Book) -> Book {
+        fold_book(self, node)
+    }
+
+    fn fold_book_contents(&mut self, node: BookContents) -> BookContents {
+        fold_book_contents(self, node)
+    }
+
+    /// Folds an `\id` marker and the text after its book code.
+    fn fold_id(
+        &mut self,
+        code: BookIdentifier,
+        text: Option<String>,
+    ) -> (BookIdentifier, Option<String>) {
+        (code, text)
+    }
+
+    fn fold_usfm(&mut self, version: String) -> String {
+        version
+    }
+
+    fn fold_encoding(&mut self, node: BookEncoding) -> BookEncoding {
+        node
+    }
+
+    fn fold_status(&mut self, status: u16) -> u16 {
+        status
+    }
+
+    fn fold_chapter(&mut self, number: u16) -> u16 {
+        number
+    }
+
+    fn fold_alt_chapter(&mut self, number: u16) -> u16 {
+        number
+    }
+
+    fn fold_empty(&mut self, node: EmptyType) -> EmptyType {
+        node
+    }
+
+    fn fold_peripheral(&mut self, title: String) -> String {
+        title
+    }
+
+    fn fold_paragraph(&mut self, node: Paragraph) -> Paragraph {
+        fold_paragraph(self, node)
+    }
+
+    fn fold_poetry(&mut self, node: Poetry) -> Poetry {
+        fold_poetry(self, node)
+    }
+
+    fn fold_element(&mut self, node: Element) -> Element {
+        fold_element(self, node)
+    }
+
+    fn fold_table_row(&mut self, node: TableRow) -> TableRow {
+        fold_table_row(self, node)
+    }
+
+    fn fold_table_cell(&mut self, node: TableCell) -> TableCell {
+        fold_table_cell(self, node)
+    }
+
+    fn fold_sidebar(&mut self, node: Sidebar) -> Sidebar {
+        fold_sidebar(self, node)
+    }
+
+    fn fold_sidebar_contents(&mut self, node: SidebarContents) -> SidebarContents {
+        fold_sidebar_contents(self, node)
+    }
+
+    fn fold_custom_paragraph(&mut self, node: CustomParagraph) -> CustomParagraph {
+        fold_custom_paragraph(self, node)
+    }
+
+    fn fold_paragraph_contents(&mut self, node: ParagraphContents) -> ParagraphContents {
+        fold_paragraph_contents(self, node)
+    }
+
+    fn fold_element_contents(&mut self, node: ElementContents) -> ElementContents {
+        fold_element_contents(self, node)
+    }
+
+    fn fold_character_contents(&mut self, node: CharacterContents) -> CharacterContents {
+        fold_character_contents(self, node)
+    }
+
+    fn fold_character(&mut self, node: Character) -> Character {
+        fold_character(self, node)
+    }
+
+    fn fold_footnote(&mut self, node: Footnote) -> Footnote {
+        fold_footnote(self, node)
+    }
+
+    fn fold_footnote_element(&mut self, node: FootnoteElement) -> FootnoteElement {
+        fold_footnote_element(self, node)
+    }
+
+    fn fold_cross_ref(&mut self, node: CrossRef) -> CrossRef {
+        fold_cross_ref(self, node)
+    }
+
+    fn fold_cross_ref_element(&mut self, node: CrossRefElement) -> CrossRefElement {
+        fold_cross_ref_element(self, node)
+    }
+
+    fn fold_figure(&mut self, node: Figure) -> Figure {
+        fold_figure(self, node)
+    }
+
+    fn fold_milestone(&mut self, node: Milestone) -> Milestone {
+        node
+    }
+
+    fn fold_custom(&mut self, node: Custom) -> Custom {
+        fold_custom(self, node)
+    }
+
+    fn fold_verse(&mut self, node: VerseNumber) -> VerseNumber {
+        node
+    }
+
+    fn fold_category(&mut self, category: String) -> String {
+        category
+    }
+
+    /// Folds the text of a paragraph, element or character style.
+    fn fold_text(&mut self, text: String) -> String {
+        text
+    }
+}
+
+pub fn fold_book<F: Fold + ?Sized>(f: &mut F, node: Book) -> Book {
+    Book {
+        contents: fold_all(node.contents, |c| f.fold_book_contents(c)),
+    }
+}
+
+pub fn fold_book_contents<F: Fold + ?Sized>(f: &mut F, node: BookContents) -> BookContents {
+    use BookContents as C;
+    match node {
+        C::Id { code, text } => {
+            let (code, text) = f.fold_id(code, text);
+            C::Id { code, text }
+        }
+        C::Usfm(version) => C::Usfm(f.fold_usfm(version)),
+        C::Encoding(encoding) => C::Encoding(f.fold_encoding(encoding)),
+        C::Status(status) => C::Status(f.fold_status(status)),
+        C::Chapter(number) => C::Chapter(f.fold_chapter(number)),
+        C::AltChapter(number) => C::AltChapter(f.fold_alt_chapter(number)),
+        C::Paragraph(p) => C::Paragraph(f.fold_paragraph(p)),
+        C::Poetry(p) => C::Poetry(f.fold_poetry(p)),
+        C::Element(e) => C::Element(f.fold_element(e)),
+        C::Empty(ty) => C::Empty(f.fold_empty(ty)),
+        C::TableRow(tr) => C::TableRow(f.fold_table_row(tr)),
+        C::Sidebar(sb) => C::Sidebar(f.fold_sidebar(sb)),
+        C::Peripheral(title) => C::Peripheral(f.fold_peripheral(title)),
+        C::Figure(fig) => C::Figure(f.fold_figure(fig)),
+        C::Custom(p) => C::Custom(f.fold_custom_paragraph(p)),
+    }
+}
+
+pub fn fold_paragraph<F: Fold + ?Sized>(f: &mut F, node: Paragraph) -> Paragraph {
+    Paragraph {
+        style: node.style,
+        contents: fold_all(node.contents, |c| f.fold_paragraph_contents(c)),
+    }
+}
+
+pub fn fold_poetry<F: Fold + ?Sized>(f: &mut F, node: Poetry) -> Poetry {
+    Poetry {
+        style: node.style,
+        contents: fold_all(node.contents, |c| f.fold_paragraph_contents(c)),
+    }
+}
+
+pub fn fold_element<F: Fold + ?Sized>(f: &mut F, node: Element) -> Element {
+    Element {
+        ty: node.ty,
+        contents: fold_all(node.contents, |c| f.fold_element_contents(c)),
+    }
+}
+
+pub fn fold_table_row<F: Fold + ?Sized>(f: &mut F, node: TableRow) -> TableRow {
+    TableRow {
+        cells: fold_all(node.cells, |cell| f.fold_table_cell(cell)),
+    }
+}
+
+pub fn fold_table_cell<F: Fold + ?Sized>(f: &mut F, node: TableCell) -> TableCell {
+    TableCell {
+        prefix: node.prefix,
+        column: node.column,
+        contents: fold_all(node.contents, |c| f.fold_paragraph_contents(c)),
+    }
+}
+
+pub fn fold_sidebar<F: Fold + ?Sized>(f: &mut F, node: Sidebar) -> Sidebar {
+    Sidebar {
+        contents: fold_all(node.contents, |c| f.fold_sidebar_contents(c)),
+    }
+}
+
+pub fn fold_sidebar_contents<F: Fold + ?Sized>(
+    f: &mut F,
+    node: SidebarContents,
+) -> SidebarContents {
+    use SidebarContents as C;
+    match node {
+        C::Paragraph(p) => C::Paragraph(f.fold_paragraph(p)),
+        C::Poetry(p) => C::Poetry(f.fold_poetry(p)),
+        C::Element(e) => C::Element(f.fold_element(e)),
+        C::TableRow(tr) => C::TableRow(f.fold_table_row(tr)),
+        C::Empty(ty) => C::Empty(f.fold_empty(ty)),
+        C::Category(category) => C::Category(f.fold_category(category)),
+    }
+}
+
+pub fn fold_custom_paragraph<F: Fold + ?Sized>(
+    f: &mut F,
+    node: CustomParagraph,
+) -> CustomParagraph {
+    CustomParagraph {
+        marker: node.marker,
+        contents: fold_all(node.contents, |c| f.fold_paragraph_contents(c)),
+    }
+}
+
+pub fn fold_paragraph_contents<F: Fold + ?Sized>(
+    f: &mut F,
+    node: ParagraphContents,
+) -> ParagraphContents {
+    use ParagraphContents as C;
+    match node {
+        C::Verse(verse) => C::Verse(f.fold_verse(verse)),
+        C::Line(text) => C::Line(f.fold_text(text)),
+        C::Character(c) => C::Character(f.fold_character(c)),
+        C::Footnote(fn_) => C::Footnote(f.fold_footnote(fn_)),
+        C::CrossRef(x) => C::CrossRef(f.fold_cross_ref(x)),
+        C::Figure(fig) => C::Figure(f.fold_figure(fig)),
+        C::Milestone(ms) => C::Milestone(f.fold_milestone(ms)),
+        C::Custom(c) => C::Custom(f.fold_custom(c)),
+        C::Category(category) => C::Category(f.fold_category(category)),
+        C::OptionalBreak => C::OptionalBreak,
+    }
+}
+
+pub fn fold_element_contents<F: Fold + ?Sized>(
+    f: &mut F,
+    node: ElementContents,
+) -> ElementContents {
+    use ElementContents as C;
+    match node {
+        C::Line(text) => C::Line(f.fold_text(text)),
+        C::Character(c) => C::Character(f.fold_character(c)),
+        C::Footnote(fn_) => C::Footnote(f.fold_footnote(fn_)),
+        C::CrossRef(x) => C::CrossRef(f.fold_cross_ref(x)),
+        C::Figure(fig) => C::Figure(f.fold_figure(fig)),
+        C::Milestone(ms) => C::Milestone(f.fold_milestone(ms)),
+        C::Custom(c) => C::Custom(f.fold_custom(c)),
+        C::Category(category) => C::Category(f.fold_category(category)),
+        C::OptionalBreak => C::OptionalBreak,
+    }
+}
+
+pub fn fold_character_contents<F: Fold + ?Sized>(
+    f: &mut F,
+    node: CharacterContents,
+) -> CharacterContents {
+    use CharacterContents as C;
+    match node {
+        C::Line(text) => C::Line(f.fold_text(text)),
+        C::Character(c) => C::Character(f.fold_character(c)),
+        C::Footnote(fn_) => C::Footnote(f.fold_footnote(fn_)),
+        C::CrossRef(x) => C::CrossRef(f.fold_cross_ref(x)),
+        C::Figure(fig) => C::Figure(f.fold_figure(fig)),
+        C::Milestone(ms) => C::Milestone(f.fold_milestone(ms)),
+        C::Custom(c) => C::Custom(f.fold_custom(c)),
+        C::OptionalBreak => C::OptionalBreak,
+    }
+}
+
+pub fn fold_character<F: Fold + ?Sized>(f: &mut F, node: Character) -> Character {
+    Character {
+        ty: node.ty,
+        contents: fold_all(node.contents, |c| f.fold_character_contents(c)),
+        attributes: node.attributes,
+    }
+}
+
+pub fn fold_footnote<F: Fold + ?Sized>(f: &mut F, node: Footnote) -> Footnote {
+    Footnote {
+        style: node.style,
+        caller: node.caller,
+        elements: fold_all(node.elements, |e| f.fold_footnote_element(e)),
+    }
+}
+
+pub fn fold_footnote_element<F: Fold + ?Sized>(
+    f: &mut F,
+    node: FootnoteElement,
+) -> FootnoteElement {
+    FootnoteElement {
+        style: node.style,
+        contents: fold_all(node.contents, |c| f.fold_character_contents(c)),
+    }
+}
+
+pub fn fold_cross_ref<F: Fold + ?Sized>(f: &mut F, node: CrossRef) -> CrossRef {
+    CrossRef {
+        style: node.style,
+        caller: node.caller,
+        elements: fold_all(node.elements, |e| f.fold_cross_ref_element(e)),
+    }
+}
+
+pub fn fold_cross_ref_element<F: Fold + ?Sized>(
+    f: &mut F,
+    node: CrossRefElement,
+) -> CrossRefElement {
+    CrossRefElement {
+        style: node.style,
+        contents: fold_all(node.contents, |c| f.fold_character_contents(c)),
+    }
+}
+
+pub fn fold_figure<F: Fold + ?Sized>(f: &mut F, node: Figure) -> Figure {
+    Figure {
+        contents: fold_all(node.contents, |c| f.fold_character_contents(c)),
+        attributes: node.attributes,
+    }
+}
+
+pub fn fold_custom<F: Fold + ?Sized>(f: &mut F, node: Custom) -> Custom {
+    Custom {
+        marker: node.marker,
+        milestone: node.milestone,
+        contents: fold_all(node.contents, |c| f.fold_character_contents(c)),
+        attributes: node.attributes,
+    }
+}
+
+fn fold_all<T>(nodes: Vec<T>, f: impl FnMut(T) -> T) -> Vec<T> {
+    nodes.into_iter().map(f).collect()
+}
@@ -36,10 +36,11 @@ fn format_book_contents(content: &BookContents) -> String {
         Sidebar(sb) => format_sidebar(sb),
         Peripheral(text) => format!("\\periph {}\n", text),
         Figure(fig) => format_figure(fig),
+        Custom(p) => format_custom_paragraph(p),
     }
 }
 
-fn encoding_to_str(enc: &BookEncoding) -> &'static str {
+pub(crate) fn encoding_to_str(enc: &BookEncoding) -> &'static str {
     use BookEncoding::*;
     match enc {
         CP1252 => "cp1252",
@@ -49,7 +50,7 @@ fn encoding_to_str(enc: &BookEncoding) -> &'static str {
     }
 }
 
-fn empty_type_to_str(ty: &EmptyType) -> &'static str {
+pub(crate) fn empty_type_to_str(ty: &EmptyType) -> &'static str {
     use EmptyType::*;
     match ty {
         Blank => "b",
@@ -58,7 +59,7 @@ fn empty_type_to_str(ty: &EmptyType) -> &'static str {
     }
 }
 
-fn paragraph_style_to_str(style: &ParagraphStyle) -> String {
+pub(crate) fn paragraph_style_to_str(style: &ParagraphStyle) -> String {
     use ParagraphStyle::*;
     match style {
         Normal => "p".into(),
@@ -82,10 +83,11 @@ fn paragraph_style_to_str(style: &ParagraphStyle) -> String {
         Descriptive => "d".into(),
         ListEntry(n) => format!("li{}", n),
         EmbeddedListEntry(n) => format!("lim{}", n),
+        Custom(marker) => marker.clone(),
     }
 }
 
-fn poetry_style_to_str(style: &PoetryStyle) -> String {
+pub(crate) fn poetry_style_to_str(style: &PoetryStyle) -> String {
     use PoetryStyle::*;
     match style {
         Normal(n) => format!("q{}", n),
@@ -97,7 +99,7 @@ fn poetry_style_to_str(style: &PoetryStyle) -> String {
     }
 }
 
-fn element_type_to_str(ty: &ElementType) -> String {
+pub(crate) fn element_type_to_str(ty: &ElementType) -> String {
     use ElementType::*;
     match ty {
         Remark => "rem".into(),
@@ -135,10 +137,11 @@ fn element_type_to_str(ty: &ElementType) -> String {
         Speaker => "sp".into(),
         Division(n) => format!("sd{}", n),
         Restore => "restore".into(),
+        Custom(name, _) => name.clone(),
     }
 }
 
-fn character_type_to_str(ty: &CharacterType) -> &'static str {
+pub(crate) fn character_type_to_str(ty: &CharacterType) -> &str {
     use CharacterType::*;
     match ty {
         IntroOutline => "ior",
@@ -188,28 +191,31 @@ fn character_type_to_str(ty: &CharacterType) -> &'static str {
         TextAlternative => "ta",
         AltVerse => "va",
         CrossRefTarget => "xt",
+        Custom(marker) => marker,
     }
 }
 
-fn footnote_style_to_str(style: &FootnoteStyle) -> &'static str {
+pub(crate) fn footnote_style_to_str(style: &FootnoteStyle) -> &str {
     use FootnoteStyle::*;
     match style {
         Footnote => "f",
         Endnote => "fe",
         ExtendedFootnote => "ef",
         ExtendedEndnote => "efe",
+        Custom(marker) => marker,
     }
 }
 
-fn cross_ref_style_to_str(style: &CrossRefStyle) -> &'static str {
+pub(crate) fn cross_ref_style_to_str(style: &CrossRefStyle) -> &str {
     use CrossRefStyle::*;
     match style {
         CrossRef => "x",
         ExtendedCrossRef => "ex",
+        Custom(marker) => marker,
     }
 }
 
-fn footnote_element_style_to_str(style: &FootnoteElementStyle) -> &'static str {
+pub(crate) fn footnote_element_style_to_str(style: &FootnoteElementStyle) -> &'static str {
     use FootnoteElementStyle::*;
     match style {
         Reference => "fr",
@@ -225,7 +231,7 @@ fn footnote_element_style_to_str(style: &FootnoteElementStyle) -> &'static str {
     }
 }
 
-fn cross_ref_element_style_to_str(style: &CrossRefElementStyle) -> &'static str {
+pub(crate) fn cross_ref_element_style_to_str(style: &CrossRefElementStyle) -> &'static str {
     use CrossRefElementStyle::*;
     match style {
         Keyword => "xk",
@@ -241,7 +247,7 @@ fn cross_ref_element_style_to_str(style: &CrossRefElementStyle) -> &'static str
     }
 }
 
-fn cell_prefix_to_str(prefix: &CellPrefix) -> &'static str {
+pub(crate) fn cell_prefix_to_str(prefix: &CellPrefix) -> &'static str {
     use CellPrefix::*;
     match prefix {
         Header => "th",
@@ -337,6 +343,9 @@ fn format_paragraph_contents(contents: &[ParagraphContents]) -> String {
             OptionalBreak => {
                 result.push_str("//");
             }
+            Custom(c) => {
+                result.push_str(&format_custom(c, false));
+            }
         }
     }
     result
@@ -371,6 +380,9 @@ fn format_element_contents(contents: &[ElementContents]) -> String {
             OptionalBreak => {
                 result.push_str("//");
             }
+            Custom(c) => {
+                result.push_str(&format_custom(c, false));
+            }
         }
     }
     result
@@ -406,40 +418,85 @@ fn format_character_contents_impl(contents: &[CharacterContents], nest: bool) ->
             OptionalBreak => {
                 result.push_str("//");
             }
+            Custom(c) => {
+                result.push_str(&format_custom(c, nest));
+            }
         }
     }
     result
 }
 
 fn format_character(c: &Character, is_nested: bool) -> String {
-    let prefix = if is_nested { "\\+" } else { "\\" };
     let base_ty = character_type_to_str(&c.ty);
     let ty = match &c.ty {
         CharacterType::ListValue(n) if *n > 0 => format!("{}{}", base_ty, n),
         _ => base_ty.to_string(),
     };
+    format_span(&ty, &c.contents, &c.attributes, is_nested)
+}
 
+fn format_span(
+    ty: &str,
+    contents: &[CharacterContents],
+    attributes: &[(String, String)],
+    is_nested: bool,
+) -> String {
+    let prefix = if is_nested { "\\+" } else { "\\" };
     let mut result = format!(
         "{}{}{}",
         prefix,
         ty,
-        if c.contents.is_empty() && c.attributes.is_empty() {
+        if contents.is_empty() && attributes.is_empty() {
             ""
         } else {
             " "
         }
     );
 
-    let contents = format_character_contents(&c.contents);
+    let contents = format_character_contents(contents);
     if !contents.is_empty() {
         result.push_str(&contents);
     }
 
+    if !attributes.is_empty() {
+        result.push_str(&format_attributes(attributes));
+    }
+
+    result.push_str(&format!("{}{}*", prefix, ty));
+    result
+}
+
+fn format_custom(c: &Custom, is_nested: bool) -> String {
+    let Some(bound) = &c.milestone else {
+        return format_span(&c.marker, &c.contents, &c.attributes, is_nested);
+    };
+
+    let mut result = format!("\\{}", c.marker);
+    match bound {
+        MilestoneBound::Start => result.push_str("-s"),
+        MilestoneBound::End => result.push_str("-e"),
+        MilestoneBound::None => {}
+    }
+
     if !c.attributes.is_empty() {
+        result.push(' ');
         result.push_str(&format_attributes(&c.attributes));
     }
 
-    result.push_str(&format!("{}{}*", prefix, ty));
+    result.push_str("\\*");
+    result
+}
+
+fn format_custom_paragraph(p: &CustomParagraph) -> String {
+    let mut result = format!("\\{}", p.marker);
+
+    let contents = format_paragraph_contents(&p.contents);
+    if !contents.is_empty() {
+        result.push(' ');
+        result.push_str(&contents);
+    }
+
+    result.push('\n');
     result
 }
 
@@ -463,8 +520,8 @@ fn format_attributes(attrs: &[(String, String)]) -> String {
     result
 }
 
-fn format_verse(verse: &str) -> String {
-    format!("\\v {} ", verse)
+fn format_verse(verse: &VerseNumber) -> String {
+    format!("\\v {} ", verse.spelling())
 }
 
 fn format_footnote(f: &Footnote) -> String {
@@ -528,15 +585,12 @@ fn format_figure(f: &Figure) -> String {
 fn format_milestone(ms: &Milestone) -> String {
     use MilestoneStyle::*;
 
-    let (style, bound) = match &ms.style {
-        QuotedText(n, b) => (format!("qt{}", n), Some(b)),
-        TextSection(b) => ("ts".to_string(), Some(b)),
-        Text(b) => ("t".to_string(), Some(b)),
-        WordsOfJesus(b) => ("wj".to_string(), Some(b)),
-        VerseId => ("vid".to_string(), None),
+    let bound = match &ms.style {
+        QuotedText(_, b) | TextSection(b) | Text(b) | WordsOfJesus(b) | Custom(_, b) => Some(b),
+        VerseId => None,
     };
 
-    let mut result = format!("\\{}", style);
+    let mut result = format!("\\{}", milestone_style_to_str(&ms.style));
 
     if let Some(bound) = bound {
         match bound {
@@ -547,6 +601,7 @@ fn format_milestone(ms: &Milestone) -> String {
     }
 
     if !ms.attributes.is_empty() {
+        result.push(' ');
         result.push_str(&format_attributes(&ms.attributes));
     }
 
@@ -554,6 +609,19 @@ fn format_milestone(ms: &Milestone) -> String {
     result
 }
 
+/// The marker of a milestone, without its `-s` or `-e` suffix.
+pub(crate) fn milestone_style_to_str(style: &MilestoneStyle) -> String {
+    use MilestoneStyle::*;
+    match style {
+        QuotedText(n, _) => format!("qt{}", n),
+        TextSection(_) => "ts".into(),
+        Text(_) => "t".into(),
+        WordsOfJesus(_) => "wj".into(),
+        VerseId => "vid".into(),
+        Custom(marker, _) => marker.clone(),
+    }
+}
+
 fn format_table_row(tr: &TableRow) -> String {
     let mut result = String::from("\\tr ");
 
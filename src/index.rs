@@ -0,0 +1,229 @@
+//! An index of where each chapter and verse of a [`Book`] is, for looking them up without
+//! walking the tree.
+
+use std::ops::Range;
+
+use crate::parser::SourceMap;
+use crate::reference::{RefRange, Reference};
+use crate::usfm::{Book, BookContents, BookIdentifier, ParagraphContents, VerseNumber};
+
+/// The chapters and verses of a book, in order, built once with [`BookIndex::new`].
+///
+/// Nodes are addressed by the same paths as in a [`SourceMap`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct BookIndex {
+    /// The book of `\id`, which references to other books are not found in.
+    book: Option<BookIdentifier>,
+    chapters: Vec<ChapterEntry>,
+    verses: Vec<VerseEntry>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ChapterEntry {
+    pub chapter: u16,
+    /// The indices into `Book::contents` from the `\c` marker up to the next one.
+    pub contents: Range<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct VerseEntry {
+    pub chapter: u16,
+    pub verse: VerseNumber,
+    /// The runs of contents that make up the verse, which spans several when it continues
+    /// across paragraph or poetry breaks.
+    pub parts: Vec<VersePart>,
+}
+
+/// A run of a paragraph's, poetry line's or table cell's contents that belongs to one verse.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct VersePart {
+    /// The path to the paragraph, poetry line or table cell.
+    pub path: Vec<usize>,
+    /// The indices into its contents, starting at the `\v` marker in the verse's first part.
+    pub contents: Range<usize>,
+}
+
+impl BookIndex {
+    /// Indexes the verses of paragraphs, poetry, table cells and custom paragraphs. Sidebars
+    /// are not part of the text and are left out.
+    pub fn new(book: &Book) -> Self {
+        let mut builder = Builder::default();
+        for (i, content) in book.contents.iter().enumerate() {
+            use BookContents as C;
+            match content {
+                C::Id { code, .. } => builder.index.book = Some(*code),
+                C::Chapter(chapter) => {
+                    builder.close_verse();
+                    if let Some(last) = builder.index.chapters.last_mut() {
+                        last.contents.end = i;
+                    }
+                    builder.index.chapters.push(ChapterEntry {
+                        chapter: *chapter,
+                        contents: i..book.contents.len(),
+                    });
+                }
+                C::Paragraph(p) => builder.add(vec![i], &p.contents),
+                C::Poetry(p) => builder.add(vec![i], &p.contents),
+                C::Custom(p) => builder.add(vec![i], &p.contents),
+                C::TableRow(tr) => {
+                    for (j, cell) in tr.cells.iter().enumerate() {
+                        builder.add(vec![i, j], &cell.contents);
+                    }
+                }
+                _ => {}
+            }
+        }
+        builder.close_verse();
+        let mut index = builder.index;
+        index.chapters.sort_by_key(|c| c.chapter);
+        index
+            .verses
+            .sort_by(|a, b| (a.chapter, &a.verse).cmp(&(b.chapter, &b.verse)));
+        index
+    }
+
+    /// Every chapter, ordered by number rather than by where it is in the book.
+    pub fn chapters(&self) -> &[ChapterEntry] {
+        &self.chapters
+    }
+
+    /// Every verse, ordered by chapter and verse.
+    pub fn verses(&self) -> &[VerseEntry] {
+        &self.verses
+    }
+
+    pub fn chapter(&self, chapter: u16) -> Option<&ChapterEntry> {
+        let i = self
+            .chapters
+            .binary_search_by_key(&chapter, |c| c.chapter)
+            .ok()?;
+        Some(&self.chapters[i])
+    }
+
+    /// The verse whose number covers `verse`, so that verse 2 is found in `\v 1-3`.
+    pub fn verse(&self, chapter: u16, verse: u16) -> Option<&VerseEntry> {
+        let i = self.first_ending_at_or_after(chapter, verse);
+        self.verses
+            .get(i)
+            .filter(|v| v.chapter == chapter && v.verse.contains(verse))
+    }
+
+    /// The verse a reference points to, if it is in this book or names none.
+    pub fn get(&self, reference: &Reference) -> Option<&VerseEntry> {
+        if reference.book.is_some() && reference.book != self.book {
+            return None;
+        }
+        self.verse(reference.chapter?, reference.verse.as_ref()?.start)
+    }
+
+    /// The verses that overlap a range, in this book's part of it if it names books.
+    ///
+    /// A range without verses covers whole chapters.
+    pub fn range(&self, range: &RefRange) -> &[VerseEntry] {
+        let Some(((start_chapter, start_verse), (end_chapter, end_verse))) =
+            range.bounds_in(self.book)
+        else {
+            return &[];
+        };
+        let start = self.first_ending_at_or_after(start_chapter, start_verse);
+        let end = self
+            .verses
+            .partition_point(|v| (v.chapter, v.verse.start) <= (end_chapter, end_verse));
+        &self.verses[start..end.max(start)]
+    }
+
+    fn first_ending_at_or_after(&self, chapter: u16, verse: u16) -> usize {
+        self.verses
+            .partition_point(|v| (v.chapter, v.verse.end) < (chapter, verse))
+    }
+}
+
+impl VerseEntry {
+    /// The byte range of the input the verse was parsed from, from its `\v` marker to the end
+    /// of its last part.
+    pub fn source_range(&self, source_map: &SourceMap) -> Option<Range<usize>> {
+        let start = self.parts.first()?.source_range(source_map)?;
+        let end = self.parts.last()?.source_range(source_map)?;
+        Some(start.start..end.end)
+    }
+}
+
+impl VersePart {
+    /// The contents of the paragraph, poetry line or table cell this part is in, all of which
+    /// make up the verse.
+    pub fn contents<'a>(&self, book: &'a Book) -> &'a [ParagraphContents] {
+        let contents = match self.path[..] {
+            [i] => match book.contents.get(i) {
+                Some(BookContents::Paragraph(p)) => Some(&p.contents),
+                Some(BookContents::Poetry(p)) => Some(&p.contents),
+                Some(BookContents::Custom(p)) => Some(&p.contents),
+                _ => None,
+            },
+            [i, j] => match book.contents.get(i) {
+                Some(BookContents::TableRow(tr)) => tr.cells.get(j).map(|cell| &cell.contents),
+                _ => None,
+            },
+            _ => None,
+        };
+        contents
+            .and_then(|contents| contents.get(self.contents.clone()))
+            .unwrap_or_default()
+    }
+
+    pub fn source_range(&self, source_map: &SourceMap) -> Option<Range<usize>> {
+        let span = |i: usize| {
+            let mut path = self.path.clone();
+            path.push(i);
+            source_map.get(&path).copied()
+        };
+        let start = span(self.contents.start)?;
+        let end = span(self.contents.end.checked_sub(1)?)?;
+        Some(start.start.offset..end.end.offset)
+    }
+}
+
+impl Book {
+    /// Builds an index of this book's chapters and verses.
+    pub fn index(&self) -> BookIndex {
+        BookIndex::new(self)
+    }
+}
+
+#[derive(Default)]
+struct Builder {
+    index: BookIndex,
+    chapter: u16,
+    current: Option<VerseEntry>,
+}
+
+impl Builder {
+    fn add(&mut self, path: Vec<usize>, contents: &[ParagraphContents]) {
+        if let Some(chapter) = self.index.chapters.last() {
+            self.chapter = chapter.chapter;
+        }
+        for (k, content) in contents.iter().enumerate() {
+            if let ParagraphContents::Verse(verse) = content {
+                self.close_verse();
+                self.current = Some(VerseEntry {
+                    chapter: self.chapter,
+                    verse: verse.clone(),
+                    parts: Vec::new(),
+                });
+            }
+            let Some(current) = &mut self.current else {
+                continue;
+            };
+            match current.parts.last_mut() {
+                Some(part) if part.path == path => part.contents.end = k + 1,
+                _ => current.parts.push(VersePart {
+                    path: path.clone(),
+                    contents: k..k + 1,
+                }),
+            }
+        }
+    }
+
+    fn close_verse(&mut self) {
+        self.index.verses.extend(self.current.take());
+    }
+}
@@ -1,7 +1,37 @@
+pub mod builder;
+pub mod edit;
+pub mod extract;
+pub mod fold;
 pub mod format;
+pub mod index;
+pub mod metadata;
+pub mod osis;
 pub mod parser;
+pub mod query;
+pub mod reference;
+pub mod stylesheet;
+pub mod text;
 pub mod usfm;
+#[cfg(feature = "serde")]
+pub mod usj;
+pub mod usx;
+pub mod versification;
+pub mod view;
+pub mod visit;
+pub mod visit_mut;
 
+pub use builder::BookBuilder;
 pub use format::format;
-pub use parser::parse;
+pub use index::{BookIndex, ChapterEntry, VerseEntry, VersePart};
+pub use metadata::BookMetadata;
+pub use parser::{
+    Diagnostic, ParseError, ParseErrorKind, ParserConfig, SourceMap, UnknownMarkers, parse,
+    parse_borrowed, parse_with_recovery, parse_with_spans,
+};
+pub use query::{Match, Node, Query};
+pub use reference::{BookNameTable, BookNames, NameStyle, RefList, RefRange, Reference};
+pub use stylesheet::Stylesheet;
+pub use text::{TextOptions, VerseText, Verses};
 pub use usfm::*;
+pub use versification::Versification;
+pub use view::{BookView, ChapterView, SectionView};
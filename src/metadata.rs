@@ -0,0 +1,253 @@
+//! The identification and header markers of a [`Book`], gathered into one struct.
+
+use crate::reference::BookNames;
+use crate::usfm::{
+    Book, BookContents, BookEncoding, BookIdentifier, CharacterContents, Element, ElementContents,
+    ElementType,
+};
+
+/// What a book says about itself before its introduction and first chapter.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct BookMetadata {
+    /// The book code of `\id`.
+    pub code: Option<BookIdentifier>,
+    /// The text after the book code of `\id`.
+    pub description: Option<String>,
+    /// `\usfm`.
+    pub usfm: Option<String>,
+    /// `\ide`.
+    pub encoding: Option<BookEncoding>,
+    /// `\sts`.
+    pub status: Option<u16>,
+    /// The running header, `\h`.
+    pub header: Option<String>,
+    /// `\toc1`–`\toc3` and `\toca1`–`\toca3`.
+    pub names: BookNames,
+    /// The level and text of each `\mt`.
+    pub titles: Vec<(u8, String)>,
+}
+
+impl Book {
+    /// The book code of `\id`.
+    pub(crate) fn code(&self) -> Option<BookIdentifier> {
+        self.contents.iter().find_map(|content| match content {
+            BookContents::Id { code, .. } => Some(*code),
+            _ => None,
+        })
+    }
+
+    /// Reads the metadata from the markers before the first chapter.
+    pub fn metadata(&self) -> BookMetadata {
+        let mut metadata = BookMetadata {
+            names: BookNames::from_contents(self.front()),
+            ..BookMetadata::default()
+        };
+        for content in self.front() {
+            use BookContents as C;
+            match content {
+                C::Id { code, text } => {
+                    metadata.code = Some(*code);
+                    metadata.description = text.clone();
+                }
+                C::Usfm(version) => metadata.usfm = Some(version.clone()),
+                C::Encoding(encoding) => metadata.encoding = Some(encoding.clone()),
+                C::Status(status) => metadata.status = Some(*status),
+                C::Element(e) => match e.ty {
+                    ElementType::Header => metadata.header = Some(to_text(&e.contents)),
+                    ElementType::MajorTitle(n) => metadata.titles.push((n, to_text(&e.contents))),
+                    _ => {}
+                },
+                _ => {}
+            }
+        }
+        metadata
+    }
+
+    /// Replaces the markers [`metadata`](Book::metadata) reads with those for `metadata`.
+    ///
+    /// The identification markers are written at the start of the book, in the order USFM gives
+    /// them. The running header, the names and the titles each take the place of those already
+    /// in the book, or go where USFM puts them if there are none. A marker whose text is
+    /// unchanged is kept as it is, character styles and all, and the rest are written as plain
+    /// text.
+    pub fn set_metadata(&mut self, metadata: &BookMetadata) {
+        let front = self.front().count();
+        let rest = self.contents.split_off(front);
+        let mut old = Vec::new();
+        let mut slots = Vec::new();
+        for content in std::mem::take(&mut self.contents) {
+            match content {
+                BookContents::Id { .. }
+                | BookContents::Usfm(_)
+                | BookContents::Encoding(_)
+                | BookContents::Status(_) => {}
+                BookContents::Element(e) => match to_group(&e.ty) {
+                    Some(group) => {
+                        if !slots.contains(&Slot::Group(group)) {
+                            slots.push(Slot::Group(group));
+                        }
+                        old.push(e);
+                    }
+                    None => slots.push(Slot::Content(BookContents::Element(e))),
+                },
+                content => slots.push(Slot::Content(content)),
+            }
+        }
+        for group in [Group::Header, Group::Names, Group::Titles] {
+            if !slots.contains(&Slot::Group(group)) && !group.elements(metadata).is_empty() {
+                let index = position(&slots, group);
+                slots.insert(index, Slot::Group(group));
+            }
+        }
+
+        let mut contents = Vec::new();
+        if let Some(code) = metadata.code {
+            contents.push(BookContents::Id {
+                code,
+                text: metadata.description.clone(),
+            });
+        }
+        contents.extend(metadata.usfm.clone().map(BookContents::Usfm));
+        contents.extend(metadata.encoding.clone().map(BookContents::Encoding));
+        contents.extend(metadata.status.map(BookContents::Status));
+        for slot in slots {
+            match slot {
+                Slot::Content(content) => contents.push(content),
+                Slot::Group(group) => {
+                    for (ty, text) in group.elements(metadata) {
+                        let kept = old
+                            .iter()
+                            .position(|e| e.ty == ty && to_text(&e.contents) == text);
+                        let element = match kept {
+                            Some(i) => old.remove(i),
+                            None => to_element(ty, text),
+                        };
+                        contents.push(BookContents::Element(element));
+                    }
+                }
+            }
+        }
+        contents.extend(rest);
+        self.contents = contents;
+    }
+
+    /// The contents before the first chapter.
+    fn front(&self) -> impl Iterator<Item = &BookContents> {
+        self.contents
+            .iter()
+            .take_while(|c| !matches!(c, BookContents::Chapter(_)))
+    }
+}
+
+/// The markers of the metadata that [`Book::set_metadata`] writes together.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Group {
+    Header,
+    Names,
+    Titles,
+}
+
+#[derive(Debug, PartialEq)]
+enum Slot {
+    Content(BookContents),
+    Group(Group),
+}
+
+impl Group {
+    /// The elements of this group that `metadata` calls for, in order.
+    fn elements(self, metadata: &BookMetadata) -> Vec<(ElementType, &str)> {
+        let names = &metadata.names;
+        match self {
+            Group::Header => metadata
+                .header
+                .iter()
+                .map(|text| (ElementType::Header, text.as_str()))
+                .collect(),
+            Group::Names => [
+                (ElementType::Contents(1), &names.long),
+                (ElementType::Contents(2), &names.short),
+                (ElementType::Contents(3), &names.abbreviation),
+            ]
+            .into_iter()
+            .filter_map(|(ty, text)| Some((ty, text.as_deref()?)))
+            .chain(
+                names
+                    .alternates
+                    .iter()
+                    .map(|(n, text)| (ElementType::AltContents(*n), text.as_str())),
+            )
+            .collect(),
+            Group::Titles => metadata
+                .titles
+                .iter()
+                .map(|(n, text)| (ElementType::MajorTitle(*n), text.as_str()))
+                .collect(),
+        }
+    }
+}
+
+fn to_group(ty: &ElementType) -> Option<Group> {
+    match ty {
+        ElementType::Header => Some(Group::Header),
+        ElementType::Contents(_) | ElementType::AltContents(_) => Some(Group::Names),
+        ElementType::MajorTitle(_) => Some(Group::Titles),
+        _ => None,
+    }
+}
+
+/// Where a group the book does not have goes: after the groups before it, or else before
+/// those after it. Titles with neither go after any remarks at the start of the book, and the
+/// header and names go first.
+fn position(slots: &[Slot], group: Group) -> usize {
+    let index = |g: Group| slots.iter().position(|slot| *slot == Slot::Group(g));
+    let (before, after): (&[Group], &[Group]) = match group {
+        Group::Header => (&[], &[Group::Names, Group::Titles]),
+        Group::Names => (&[Group::Header], &[Group::Titles]),
+        Group::Titles => (&[Group::Names, Group::Header], &[]),
+    };
+    if let Some(i) = before.iter().find_map(|g| index(*g)) {
+        return i + 1;
+    }
+    if let Some(i) = after.iter().find_map(|g| index(*g)) {
+        return i;
+    }
+    match group {
+        Group::Titles => slots
+            .iter()
+            .take_while(|slot| {
+                matches!(slot, Slot::Content(BookContents::Element(e)) if e.ty == ElementType::Remark)
+            })
+            .count(),
+        _ => 0,
+    }
+}
+
+fn to_element(ty: ElementType, text: &str) -> Element {
+    Element {
+        ty,
+        contents: vec![ElementContents::Line(text.to_string())],
+    }
+}
+
+/// The plain text of an element, including the text of its character styles.
+pub(crate) fn to_text(contents: &[ElementContents]) -> String {
+    let mut text = String::new();
+    for content in contents {
+        match content {
+            ElementContents::Line(line) => text.push_str(line),
+            ElementContents::Character(c) => push_text(&mut text, &c.contents),
+            _ => {}
+        }
+    }
+    text.trim().to_string()
+}
+
+fn push_text(text: &mut String, contents: &[CharacterContents]) {
+    for content in contents {
+        match content {
+            CharacterContents::Line(line) => text.push_str(line),
+            CharacterContents::Character(c) => push_text(text, &c.contents),
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,635 @@
+//! Export of a [`Book`] to OSIS 2.1, the XML schema of the Open Scripture Information Standard.
+//!
+//! The book becomes a `<div type="book">` named by its OSIS abbreviation, and chapters and
+//! verses are written as milestones with an `sID` where they start and an `eID` where they end,
+//! so that they may cross the paragraphs, line groups and sections around them. Each `\s#`
+//! heading opens a `<div type="section">`, or a `subSection` below the first level, that lasts
+//! until the next heading of its level or above. Poetry lines become `<l level="#">` inside an
+//! `<lg>` that a blank line or any other block ends.
+//!
+//! OSIS has no place for some markers: categories, custom markers, `\cp` and milestones other
+//! than quotations are left out, keeping their text where they have any.
+
+use crate::format::{character_type_to_str, element_type_to_str, paragraph_style_to_str};
+use crate::metadata::to_text;
+use crate::usfm::*;
+use crate::usx::escape;
+
+/// Writes `book` as an OSIS document.
+pub fn to_osis(book: &Book) -> String {
+    let mut writer = Writer::default();
+    writer.out.push_str(concat!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+        "<osis xmlns=\"http://www.bibletechnologies.net/2003/OSIS/namespace\" ",
+        "xmlns:xsi=\"http://www.w3.org/2001/XMLSchema-instance\" ",
+        "xsi:schemaLocation=\"http://www.bibletechnologies.net/2003/OSIS/namespace ",
+        "http://www.bibletechnologies.net/osisCore.2.1.1.xsd\">\n",
+        "<osisText osisIDWork=\"Bible\" osisRefWork=\"Bible\" xml:lang=\"und\">\n",
+        "<header>\n<work osisWork=\"Bible\"/>\n</header>\n",
+    ));
+    for content in &book.contents {
+        writer.book_contents(content);
+    }
+    writer.end_chapter();
+    writer.end_sections(0);
+    if writer.book.is_some() {
+        writer.out.push_str("</div>\n");
+    }
+    writer.out.push_str("</osisText>\n</osis>\n");
+    writer.out
+}
+
+#[derive(Default)]
+struct Writer {
+    out: String,
+    book: Option<BookIdentifier>,
+    chapter: Option<u16>,
+    /// The `sID` of the verse whose end has not been written yet, and its first `osisID`.
+    verse: Option<(String, String)>,
+    /// The closing tag of the last paragraph, line or title, which is still open for a verse to
+    /// end in.
+    para: Option<&'static str>,
+    lg: bool,
+    table: bool,
+    intro: bool,
+    /// The levels of the open sections, `0` being a major section.
+    sections: Vec<u8>,
+    /// The `sID`s of the open quotation milestones.
+    quotes: Vec<String>,
+    quote_count: usize,
+}
+
+impl Writer {
+    fn book_contents(&mut self, content: &BookContents) {
+        use BookContents as C;
+        match content {
+            C::Id { code, .. } => {
+                self.book = Some(*code);
+                self.out.push_str(&format!(
+                    "<div type=\"book\" osisID=\"{}\" canonical=\"true\">\n",
+                    code.to_osis()
+                ));
+            }
+            C::Usfm(_) | C::Encoding(_) | C::Status(_) | C::AltChapter(_) => {}
+            C::Chapter(chapter) => {
+                self.end_chapter();
+                self.end_intro();
+                self.chapter = Some(*chapter);
+                let id = self.osis_id(None);
+                self.out.push_str(&format!(
+                    "<chapter osisID=\"{}\" sID=\"{}\" n=\"{}\"/>\n",
+                    id, id, chapter
+                ));
+            }
+            C::Paragraph(p) => self.paragraph(p),
+            C::Poetry(p) => self.poetry(p),
+            C::Custom(p) => {
+                self.end_intro();
+                self.open(
+                    &format!("<p type=\"x-{}\">", escape(&p.marker)),
+                    "</p>",
+                    &p.contents,
+                );
+            }
+            C::Element(e) => self.element(e),
+            C::Empty(EmptyType::PageBreak) => {
+                self.end_block();
+                self.out.push_str("<milestone type=\"pb\"/>\n");
+            }
+            C::Empty(_) => self.end_block(),
+            C::TableRow(tr) => self.table_row(tr),
+            C::Sidebar(sb) => self.sidebar(sb),
+            C::Peripheral(text) => {
+                self.end_block();
+                self.out.push_str(&format!(
+                    "<milestone type=\"x-periph\" n=\"{}\"/>\n",
+                    escape(text)
+                ));
+            }
+            C::Figure(fig) => {
+                self.end_block();
+                self.figure(fig);
+                self.out.push('\n');
+            }
+        }
+    }
+
+    fn paragraph(&mut self, p: &Paragraph) {
+        self.end_intro();
+        match &p.style {
+            ParagraphStyle::Normal => self.open("<p>", "</p>", &p.contents),
+            ParagraphStyle::Descriptive => self.open(
+                "<title type=\"psalm\" canonical=\"true\">",
+                "</title>",
+                &p.contents,
+            ),
+            style => self.open(
+                &format!("<p type=\"x-{}\">", paragraph_style_to_str(style)),
+                "</p>",
+                &p.contents,
+            ),
+        }
+    }
+
+    fn poetry(&mut self, p: &Poetry) {
+        self.end_intro();
+        let tag = match &p.style {
+            PoetryStyle::Normal(n) => format!("<l level=\"{}\">", n),
+            PoetryStyle::Embedded(n) => format!("<l level=\"{}\" type=\"x-embedded\">", n),
+            PoetryStyle::Right => "<l type=\"x-right\">".to_string(),
+            PoetryStyle::Centered => "<l type=\"x-centered\">".to_string(),
+            PoetryStyle::AcrosticHeading => "<l type=\"x-acrostic\">".to_string(),
+            PoetryStyle::Descriptive => "<l type=\"x-descriptive\">".to_string(),
+        };
+        if let Some(ParagraphContents::Verse(_)) = p.contents.first() {
+            self.end_verse();
+        }
+        self.end_para();
+        self.end_table();
+        if !self.lg {
+            self.out.push_str("<lg>\n");
+            self.lg = true;
+        }
+        self.out.push_str(&tag);
+        self.para = Some("</l>");
+        self.paragraph_contents(&p.contents);
+    }
+
+    /// Opens a paragraph that stays open until the next block, so that a verse ending at the
+    /// end of it is ended inside it.
+    fn open(&mut self, tag: &str, end: &'static str, contents: &[ParagraphContents]) {
+        if let Some(ParagraphContents::Verse(_)) = contents.first() {
+            self.end_verse();
+        }
+        self.end_para();
+        self.end_lg();
+        self.end_table();
+        self.out.push_str(tag);
+        self.para = Some(end);
+        self.paragraph_contents(contents);
+    }
+
+    fn element(&mut self, e: &Element) {
+        use ElementType::*;
+        let (tag, end) = match &e.ty {
+            Remark => {
+                self.end_block();
+                // A comment cannot hold `--`, and splitting one can leave another, as in `---`.
+                let mut text = to_text(&e.contents);
+                while text.contains("--") {
+                    text = text.replace("--", "- -");
+                }
+                self.out.push_str(&format!("<!-- {} -->\n", text));
+                return;
+            }
+            Contents(_) | AltContents(_) => {
+                self.end_block();
+                self.out.push_str(&format!(
+                    "<milestone type=\"x-usfm-{}\" n=\"{}\"/>\n",
+                    element_type_to_str(&e.ty),
+                    escape(&to_text(&e.contents))
+                ));
+                return;
+            }
+            Division(_) => {
+                self.end_block();
+                self.out.push_str(&format!(
+                    "<milestone type=\"x-usfm-{}\"/>\n",
+                    element_type_to_str(&e.ty)
+                ));
+                return;
+            }
+            ChapterPublishedLabel | Restore => return,
+            EndIntro => {
+                self.end_block();
+                self.end_intro();
+                return;
+            }
+            Header => ("<title type=\"runningHead\">".to_string(), "</title>"),
+            MajorSection(_) | Section(_) | Custom(_, ElementKind::Section) => {
+                self.end_block();
+                self.end_intro();
+                let (level, ty) = match e.ty {
+                    MajorSection(_) => (0, "majorSection"),
+                    Section(n) if n <= 1 => (1, "section"),
+                    Custom(..) => (1, "section"),
+                    Section(n) => (n, "subSection"),
+                    _ => unreachable!(),
+                };
+                self.end_sections(level);
+                self.sections.push(level);
+                self.out.push_str(&format!("<div type=\"{}\">\n", ty));
+                ("<title>".to_string(), "</title>")
+            }
+            MajorTitle(n) | MajorTitleEnding(n) => {
+                (format!("<title type=\"main\" level=\"{}\">", n), "</title>")
+            }
+            Custom(_, ElementKind::Title) => ("<title type=\"main\">".to_string(), "</title>"),
+            MajorIntro(n) | MajorTitleEndingIntro(n) => {
+                (format!("<title type=\"main\" level=\"{}\">", n), "</title>")
+            }
+            SectionIntro(n) | EntryIntro(n) => (format!("<title level=\"{}\">", n), "</title>"),
+            OutlineIntro => ("<title>".to_string(), "</title>"),
+            Intro => ("<p>".to_string(), "</p>"),
+            MajorReference | Reference => ("<title type=\"scope\">".to_string(), "</title>"),
+            Parallel => ("<title type=\"parallel\">".to_string(), "</title>"),
+            ChapterLabel => ("<title type=\"chapter\">".to_string(), "</title>"),
+            Speaker => ("<speaker>".to_string(), "</speaker>"),
+            ty => (
+                format!("<p type=\"x-{}\">", element_type_to_str(ty)),
+                "</p>",
+            ),
+        };
+        self.end_block();
+        if is_intro(&e.ty) {
+            if !self.intro {
+                self.out.push_str("<div type=\"introduction\">\n");
+                self.intro = true;
+            }
+        } else if !matches!(e.ty, Header) {
+            self.end_intro();
+        }
+        self.out.push_str(&tag);
+        for content in &e.contents {
+            self.element_contents(content);
+        }
+        self.out.push_str(end);
+        self.out.push('\n');
+    }
+
+    fn table_row(&mut self, tr: &TableRow) {
+        self.end_verse();
+        self.end_para();
+        self.end_lg();
+        if !self.table {
+            self.out.push_str("<table>\n");
+            self.table = true;
+        }
+        self.out.push_str("<row>");
+        for cell in &tr.cells {
+            use CellPrefix::*;
+            let (role, align) = match cell.prefix {
+                Header => ("label", "start"),
+                HeaderRight => ("label", "end"),
+                HeaderCenter => ("label", "center"),
+                Content => ("data", "start"),
+                ContentRight => ("data", "end"),
+                ContentCenter => ("data", "center"),
+            };
+            self.out
+                .push_str(&format!("<cell role=\"{}\" align=\"{}\">", role, align));
+            self.paragraph_contents(&cell.contents);
+            self.end_verse();
+            self.out.push_str("</cell>");
+        }
+        self.out.push_str("</row>\n");
+    }
+
+    fn sidebar(&mut self, sb: &Sidebar) {
+        self.end_block();
+        self.out.push_str("<div type=\"x-sidebar\">\n");
+        // Sections within the sidebar end with it.
+        let sections = std::mem::take(&mut self.sections);
+        for content in &sb.contents {
+            use SidebarContents as C;
+            match content {
+                C::Paragraph(p) => self.paragraph(p),
+                C::Poetry(p) => self.poetry(p),
+                C::Element(e) => self.element(e),
+                C::Empty(_) => self.end_block(),
+                C::TableRow(tr) => self.table_row(tr),
+                C::Category(_) => {}
+            }
+        }
+        self.end_block();
+        self.end_sections(0);
+        self.sections = sections;
+        self.out.push_str("</div>\n");
+    }
+
+    fn paragraph_contents(&mut self, contents: &[ParagraphContents]) {
+        for content in contents {
+            use ParagraphContents as C;
+            match content {
+                C::Verse(verse) => self.verse(verse),
+                C::Line(text) => self.out.push_str(&escape(text)),
+                C::Character(c) => self.character(c),
+                C::Footnote(f) => self.footnote(f),
+                C::CrossRef(x) => self.cross_ref(x),
+                C::Figure(fig) => self.figure(fig),
+                C::Milestone(ms) => self.milestone(ms),
+                C::Category(_) => {}
+                C::OptionalBreak => self.out.push_str("<lb type=\"x-optional\"/>"),
+                C::Custom(c) => self.custom(c),
+            }
+        }
+    }
+
+    fn element_contents(&mut self, content: &ElementContents) {
+        use ElementContents as C;
+        match content {
+            C::Line(text) => self.out.push_str(&escape(text)),
+            C::Character(c) => self.character(c),
+            C::Footnote(f) => self.footnote(f),
+            C::CrossRef(x) => self.cross_ref(x),
+            C::Figure(fig) => self.figure(fig),
+            C::Milestone(ms) => self.milestone(ms),
+            C::Category(_) => {}
+            C::OptionalBreak => self.out.push_str("<lb type=\"x-optional\"/>"),
+            C::Custom(c) => self.custom(c),
+        }
+    }
+
+    fn character_contents(&mut self, contents: &[CharacterContents]) {
+        for content in contents {
+            use CharacterContents as C;
+            match content {
+                C::Line(text) => self.out.push_str(&escape(text)),
+                C::Character(c) => self.character(c),
+                C::Footnote(f) => self.footnote(f),
+                C::CrossRef(x) => self.cross_ref(x),
+                C::Figure(fig) => self.figure(fig),
+                C::Milestone(ms) => self.milestone(ms),
+                C::OptionalBreak => self.out.push_str("<lb type=\"x-optional\"/>"),
+                C::Custom(c) => self.custom(c),
+            }
+        }
+    }
+
+    fn verse(&mut self, verse: &VerseNumber) {
+        self.end_verse();
+        let ids: Vec<_> = (verse.start..=verse.end.max(verse.start))
+            .map(|n| self.osis_id(Some(n)))
+            .collect();
+        let sid = format!("{}.{}", self.osis_id(None), verse);
+        self.out.push_str(&format!(
+            "<verse osisID=\"{}\" sID=\"{}\" n=\"{}\"/>",
+            ids.join(" "),
+            sid,
+            verse
+        ));
+        self.verse = Some((sid, ids[0].clone()));
+    }
+
+    fn character(&mut self, c: &Character) {
+        use CharacterType::*;
+        let (tag, end) = match &c.ty {
+            Jesus => ("<q who=\"Jesus\" marker=\"\">".to_string(), "</q>"),
+            Deity => ("<divineName>".to_string(), "</divineName>"),
+            Addition => ("<transChange type=\"added\">".to_string(), "</transChange>"),
+            Emphasis => ("<hi type=\"emphasis\">".to_string(), "</hi>"),
+            Bold => ("<hi type=\"bold\">".to_string(), "</hi>"),
+            Italic => ("<hi type=\"italic\">".to_string(), "</hi>"),
+            BoldItalic => (
+                "<hi type=\"bold\"><hi type=\"italic\">".to_string(),
+                "</hi></hi>",
+            ),
+            Normal => ("<hi type=\"normal\">".to_string(), "</hi>"),
+            SmallCap => ("<hi type=\"small-caps\">".to_string(), "</hi>"),
+            Superscipt => ("<hi type=\"super\">".to_string(), "</hi>"),
+            Proper => ("<name>".to_string(), "</name>"),
+            Geographic => ("<name type=\"geographic\">".to_string(), "</name>"),
+            Keyword => ("<seg type=\"keyword\">".to_string(), "</seg>"),
+            QuotedText => ("<seg type=\"otPassage\">".to_string(), "</seg>"),
+            Signature => ("<signed>".to_string(), "</signed>"),
+            Transliterated | ForeignWord => ("<foreign>".to_string(), "</foreign>"),
+            Word => (format!("<w{}>", lemma(&c.attributes)), "</w>"),
+            ListValue(n) if *n > 0 => (
+                format!("<seg type=\"x-{}{}\">", character_type_to_str(&c.ty), n),
+                "</seg>",
+            ),
+            ty => (
+                format!("<seg type=\"x-{}\">", escape(character_type_to_str(ty))),
+                "</seg>",
+            ),
+        };
+        self.out.push_str(&tag);
+        self.character_contents(&c.contents);
+        self.out.push_str(end);
+    }
+
+    fn footnote(&mut self, f: &Footnote) {
+        let placement = match f.style {
+            FootnoteStyle::Endnote | FootnoteStyle::ExtendedEndnote => "end",
+            _ => "foot",
+        };
+        self.note(&format!(" placement=\"{}\"", placement), &f.caller);
+        for element in &f.elements {
+            use FootnoteElementStyle::*;
+            let (tag, end) = match element.style {
+                Reference => ("<reference type=\"annotateRef\">", "</reference>"),
+                TranslationQuote | Keyword => ("<catchWord>", "</catchWord>"),
+                AltTranslationQuote => ("<rdg type=\"alternative\">", "</rdg>"),
+                Label => ("<label>", "</label>"),
+                Verse => ("<hi type=\"super\">", "</hi>"),
+                Witness | Paragraph | Text | DeuteroText => ("", ""),
+            };
+            self.out.push_str(tag);
+            self.character_contents(&element.contents);
+            self.out.push_str(end);
+        }
+        self.out.push_str("</note>");
+    }
+
+    fn cross_ref(&mut self, x: &CrossRef) {
+        self.note(" type=\"crossReference\"", &x.caller);
+        for element in &x.elements {
+            use CrossRefElementStyle::*;
+            let (tag, end) = match element.style {
+                Origin | OriginRef => ("<reference type=\"annotateRef\">", "</reference>"),
+                Target | ExtraTarget | OldTarget | NewTarget | DeuteroTarget => {
+                    ("<reference>", "</reference>")
+                }
+                Keyword => ("<catchWord>", "</catchWord>"),
+                Quote | InlineQuote => ("<q>", "</q>"),
+            };
+            self.out.push_str(tag);
+            self.character_contents(&element.contents);
+            self.out.push_str(end);
+        }
+        self.out.push_str("</note>");
+    }
+
+    fn note(&mut self, attributes: &str, caller: &Caller) {
+        self.out.push_str("<note");
+        self.out.push_str(attributes);
+        if let Caller::Some(c) = caller {
+            self.out
+                .push_str(&format!(" n=\"{}\"", escape(&c.to_string())));
+        }
+        if let Some((_, id)) = &self.verse {
+            self.out.push_str(&format!(" osisRef=\"{}\"", id));
+        }
+        self.out.push('>');
+    }
+
+    fn figure(&mut self, fig: &Figure) {
+        self.out.push_str("<figure");
+        for (key, value) in &fig.attributes {
+            if matches!(key.as_str(), "src" | "size") {
+                self.out
+                    .push_str(&format!(" {}=\"{}\"", key, escape(value)));
+            }
+        }
+        self.out.push_str("><caption>");
+        self.character_contents(&fig.contents);
+        self.out.push_str("</caption></figure>");
+    }
+
+    fn milestone(&mut self, ms: &Milestone) {
+        use MilestoneStyle::*;
+        let who = match &ms.style {
+            WordsOfJesus(_) => Some("Jesus"),
+            QuotedText(..) => ms
+                .attributes
+                .iter()
+                .find(|(key, _)| key == "who")
+                .map(|(_, value)| value.as_str()),
+            _ => return,
+        };
+        match &ms.style {
+            QuotedText(_, MilestoneBound::Start) | WordsOfJesus(MilestoneBound::Start) => {
+                self.quote_count += 1;
+                let sid = format!("q.{}", self.quote_count);
+                self.out.push_str("<q");
+                if let Some(who) = who {
+                    self.out.push_str(&format!(" who=\"{}\"", escape(who)));
+                }
+                if let QuotedText(n, _) = &ms.style {
+                    self.out.push_str(&format!(" level=\"{}\"", n));
+                }
+                self.out
+                    .push_str(&format!(" marker=\"\" sID=\"{}\"/>", sid));
+                self.quotes.push(sid);
+            }
+            QuotedText(_, MilestoneBound::End) | WordsOfJesus(MilestoneBound::End) => {
+                if let Some(sid) = self.quotes.pop() {
+                    self.out
+                        .push_str(&format!("<q marker=\"\" eID=\"{}\"/>", sid));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn custom(&mut self, c: &Custom) {
+        if c.milestone.is_none() {
+            self.character_contents(&c.contents);
+        }
+    }
+
+    /// The `osisID` of the current chapter, or of `verse` in it.
+    fn osis_id(&self, verse: Option<u16>) -> String {
+        let book = self.book.map_or("", |book| book.to_osis());
+        let chapter = self.chapter.unwrap_or_default();
+        match verse {
+            Some(verse) => format!("{}.{}.{}", book, chapter, verse),
+            None => format!("{}.{}", book, chapter),
+        }
+    }
+
+    fn end_verse(&mut self) {
+        if let Some((sid, _)) = self.verse.take() {
+            self.out.push_str(&format!("<verse eID=\"{}\"/>", sid));
+        }
+    }
+
+    fn end_para(&mut self) {
+        if let Some(end) = self.para.take() {
+            self.out.push_str(end);
+            self.out.push('\n');
+        }
+    }
+
+    fn end_lg(&mut self) {
+        if self.lg {
+            self.out.push_str("</lg>\n");
+            self.lg = false;
+        }
+    }
+
+    fn end_table(&mut self) {
+        if self.table {
+            self.out.push_str("</table>\n");
+            self.table = false;
+        }
+    }
+
+    fn end_block(&mut self) {
+        self.end_verse();
+        self.end_para();
+        self.end_lg();
+        self.end_table();
+    }
+
+    fn end_intro(&mut self) {
+        if self.intro {
+            self.end_block();
+            self.out.push_str("</div>\n");
+            self.intro = false;
+        }
+    }
+
+    /// Closes the open sections at `level` or below it.
+    fn end_sections(&mut self, level: u8) {
+        while self.sections.last().is_some_and(|open| *open >= level) {
+            self.end_block();
+            self.sections.pop();
+            self.out.push_str("</div>\n");
+        }
+    }
+
+    fn end_chapter(&mut self) {
+        self.end_block();
+        if self.chapter.is_some() {
+            let id = self.osis_id(None);
+            self.out.push_str(&format!("<chapter eID=\"{}\"/>\n", id));
+            self.chapter = None;
+        }
+    }
+}
+
+fn is_intro(ty: &ElementType) -> bool {
+    use ElementType::*;
+    matches!(
+        ty,
+        MajorIntro(_)
+            | SectionIntro(_)
+            | Intro
+            | IndentedIntro
+            | MarginIntro
+            | MarginIndentedIntro
+            | QuotedIntro
+            | MarginQuotedIntro
+            | RightIntro
+            | CenteredIntro
+            | LiturgicalIntro
+            | PoetryIntro(_)
+            | ListIntro(_)
+            | OutlineIntro
+            | EntryIntro(_)
+            | BridgeIntro
+            | MajorTitleEndingIntro(_)
+    )
+}
+
+/// The `lemma` attribute of a `\w` word, with its Strong's numbers as `strong:` references.
+fn lemma(attributes: &[(String, String)]) -> String {
+    let mut lemmas = Vec::new();
+    for (key, value) in attributes {
+        match key.as_str() {
+            "lemma" => lemmas.push(value.clone()),
+            "strong" => lemmas.extend(
+                value
+                    .split(',')
+                    .map(|strong| format!("strong:{}", strong.trim())),
+            ),
+            _ => {}
+        }
+    }
+    if lemmas.is_empty() {
+        String::new()
+    } else {
+        format!(" lemma=\"{}\"", escape(&lemmas.join(" ")))
+    }
+}
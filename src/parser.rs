@@ -1,6 +1,15 @@
+mod config;
+mod error;
 mod pairs;
+mod spans;
 
-use crate::usfm::*;
+pub use config::{ParserConfig, UnknownMarkers};
+#[cfg(feature = "serde")]
+pub(crate) use error::describe;
+pub use error::{Diagnostic, ParseError, ParseErrorKind};
+pub use spans::{Location, SourceMap, Span, SpanNode};
+
+use crate::usfm::{self, borrowed::*};
 use pairs::Unpack;
 use pest::Parser;
 use pest::iterators::{Pair, Pairs};
@@ -10,365 +19,537 @@ use pest_derive::Parser;
 #[grammar = "usfm.pest"]
 pub struct UsfmParser;
 
-pub fn parse(input: &str) -> Result<Book, String> {
-    let parsed = UsfmParser::parse(Rule::book, input).map_err(|e| {
-        e.renamed_rules(|rule| match rule {
-            Rule::ntext | Rule::text => "text".into(),
-            Rule::k => "character style (\\style ...\\style*)".into(),
-            Rule::kn => "numbered character style (\\style1 ...\\style1*)".into(),
-            Rule::f => "footnote (\\f ...\\f*)".into(),
-            Rule::x => "cross-reference (\\x ...\\x*)".into(),
-            Rule::fig => "figure (\\fig ...\\fig*)".into(),
-            Rule::ms => "milestone (\\style\\*)".into(),
-            Rule::mn => "numbered milestone (\\style\\*)".into(),
-            Rule::cat => "category (\\cat ...\\cat*)".into(),
-            Rule::br => "optional break (//)".into(),
-            Rule::k_style => "character style name".into(),
-            Rule::kn_style => "numbered character style name".into(),
-            Rule::mn_style => "numbered milestone style name".into(),
-            Rule::ms_style => "milestone style name".into(),
-            Rule::fe_style => "footnote element style (\\ft, \\fk, \\fq, ...)".into(),
-            Rule::xe_style => "cross-reference element style (\\xt, \\xk, ...)".into(),
-            Rule::f_style => "footnote style (\\f, \\fe, \\ef)".into(),
-            Rule::x_style => "cross-reference style (\\x, \\ex)".into(),
-            Rule::p_style => "paragraph style".into(),
-            Rule::pn_style => "numbered paragraph style".into(),
-            Rule::q_style => "poetry style".into(),
-            Rule::qn_style => "numbered poetry style".into(),
-            Rule::e_type => "element type".into(),
-            Rule::en_type => "numbered element type".into(),
-            Rule::em_type => "empty marker type (\\b, \\pb, \\ib)".into(),
-            Rule::caller => "note caller (+, -, or character)".into(),
-            Rule::num => "number".into(),
-            Rule::verse_num => "verse number".into(),
-            Rule::cell_prefix => "table cell type (\\th, \\tc, ...)".into(),
-            _ => format!("{:?}", rule),
-        })
-        .to_string()
-    })?;
-    Ok(to_book(parsed))
-}
-
-fn to_book(pairs: Pairs<Rule>) -> Book {
-    Book {
-        contents: pairs.map(to_book_contents).collect(),
+pub fn parse(input: &str) -> Result<usfm::Book, ParseError> {
+    ParserConfig::default().parse(input)
+}
+
+/// Parses `input` into a [`Book`](borrowed::Book) whose text borrows from `input`.
+pub fn parse_borrowed(input: &str) -> Result<Book<'_>, ParseError> {
+    ParserConfig::default().parse_borrowed(input)
+}
+
+/// Parses `input` along with a [`SourceMap`] recording where each node came from.
+pub fn parse_with_spans(input: &str) -> Result<(usfm::Book, SourceMap), ParseError> {
+    ParserConfig::default().parse_with_spans(input)
+}
+
+/// Parses `input`, skipping any region that fails to parse instead of rejecting the whole book.
+///
+/// Parsing resynchronises at the next line-initial paragraph, poetry, heading or chapter marker,
+/// so each diagnostic covers everything from the offending block up to that point.
+pub fn parse_with_recovery(input: &str) -> (usfm::Book, Vec<Diagnostic>) {
+    ParserConfig::default().parse_with_recovery(input)
+}
+
+impl ParserConfig {
+    /// Parses `input` into a borrowed book and then copies it into an owned one.
+    pub fn parse(&self, input: &str) -> Result<usfm::Book, ParseError> {
+        self.parse_borrowed(input).map(Book::into_owned)
+    }
+
+    pub fn parse_borrowed<'i>(&self, input: &'i str) -> Result<Book<'i>, ParseError> {
+        let parsed =
+            UsfmParser::parse(Rule::book, input).map_err(|e| ParseError::from_pest(e, input))?;
+        self.to_book(parsed)
+    }
+
+    pub fn parse_with_spans(&self, input: &str) -> Result<(usfm::Book, SourceMap), ParseError> {
+        let parsed =
+            UsfmParser::parse(Rule::book, input).map_err(|e| ParseError::from_pest(e, input))?;
+        let source_map = SourceMap::new(input, parsed.clone(), self);
+        Ok((self.to_book(parsed)?.into_owned(), source_map))
+    }
+
+    pub fn parse_with_recovery(&self, input: &str) -> (usfm::Book, Vec<Diagnostic>) {
+        match UsfmParser::parse(Rule::book_lenient, input) {
+            Ok(parsed) => {
+                let (book, diagnostics) = self.to_book_lenient(parsed);
+                (book.into_owned(), diagnostics)
+            }
+            Err(e) => {
+                let diagnostic = Diagnostic {
+                    error: ParseError::from_pest(e, input),
+                    skipped: 0..input.len(),
+                };
+                (usfm::Book { contents: vec![] }, vec![diagnostic])
+            }
+        }
+    }
+
+    fn to_book<'i>(&self, pairs: Pairs<'i, Rule>) -> Result<Book<'i>, ParseError> {
+        self.check_unknown(pairs.clone())?;
+        let mut contents = pairs.map(to_book_contents).collect::<Result<_, _>>()?;
+        self.resolve(&mut contents);
+        Ok(Book { contents })
+    }
+
+    fn to_book_lenient<'i>(&self, pairs: Pairs<'i, Rule>) -> (Book<'i>, Vec<Diagnostic>) {
+        let mut contents = Vec::new();
+        let mut diagnostics = Vec::new();
+        for pair in pairs {
+            let span = pair.as_span();
+            let result = match pair.as_rule() {
+                Rule::skipped => Err(self.to_skipped_error(pair)),
+                _ => self
+                    .check_unknown(Pairs::single(pair.clone()))
+                    .and_then(|_| to_book_contents(pair)),
+            };
+            match result {
+                Ok(content) => contents.push(content),
+                Err(error) => {
+                    let text = span.as_str();
+                    let start = span.start() + text.len() - text.trim_start().len();
+                    let end = span.start() + text.trim_end().len();
+                    diagnostics.push(Diagnostic {
+                        error,
+                        skipped: start..end,
+                    });
+                }
+            }
+        }
+        self.resolve(&mut contents);
+        (Book { contents }, diagnostics)
+    }
+
+    /// Rejects the first custom marker among `pairs` that is missing its end marker, and under
+    /// [`UnknownMarkers::Error`], the first unknown marker.
+    fn check_unknown(&self, pairs: Pairs<Rule>) -> Result<(), ParseError> {
+        if let Some(pair) = pairs.clone().flatten().find(|p| self.is_unclosed(p)) {
+            let name = Unpack::from(pair).next_pair()?;
+            return Err(ParseError::unrecognized(
+                "marker without an end marker",
+                name.as_span(),
+            ));
+        }
+        if self.unknown_markers != UnknownMarkers::Error {
+            return Ok(());
+        }
+        match pairs.flatten().find(|p| self.is_unknown(p)) {
+            Some(pair) => {
+                let name = Unpack::from(pair).next_pair()?;
+                Err(ParseError::unrecognized("marker", name.as_span()))
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Reparses a skipped region on its own to find out what was wrong with it.
+    fn to_skipped_error(&self, pair: Pair<Rule>) -> ParseError {
+        let span = pair.as_span();
+        let error = match self.parse_borrowed(span.as_str()) {
+            Err(e) => e,
+            Ok(_) => return ParseError::at(ParseErrorKind::Syntax, span),
+        };
+        error.offset_by(span.get_input(), span.start())
     }
 }
 
-pub fn to_book_contents(pair: Pair<Rule>) -> BookContents {
+pub fn to_book_contents<'i>(pair: Pair<'i, Rule>) -> Result<BookContents<'i>, ParseError> {
     use BookContents as C;
     let rule = pair.as_rule();
-    let mut pairs: Unpack<'_, Rule> = pair.into_inner().into();
+    let span = pair.as_span();
+    let mut pairs: Unpack<'i, Rule> = pair.into();
 
-    match rule {
+    Ok(match rule {
         Rule::id => C::Id {
-            code: to_book_identifier(pairs.next_str()),
-            text: pairs.next_str_opt().map(str::to_string),
+            code: pairs.next_with("book identifier", to_book_identifier)?,
+            text: pairs.next_str_opt(),
         },
-        Rule::usfm_ver => C::Usfm(pairs.next_str().to_string()),
-        Rule::ide => C::Encoding(to_book_encoding(pairs.next_str())),
-        Rule::sts => C::Status(pairs.next_value()),
-        Rule::c => C::Chapter(pairs.next_value()),
-        Rule::ca => C::AltChapter(pairs.next_value()),
-        Rule::p => C::Paragraph(to_paragraph(pairs)),
-        Rule::pn => C::Paragraph(to_numbered_paragraph(pairs)),
-        Rule::q => C::Poetry(to_poetry(pairs)),
-        Rule::qn => C::Poetry(to_numbered_poetry(pairs)),
-        Rule::e => C::Element(to_element(pairs)),
-        Rule::en => C::Element(to_numbered_element(pairs)),
-        Rule::em => C::Empty(to_empty_type(pairs.next_str())),
-        Rule::tr => C::TableRow(to_table_row(pairs)),
-        Rule::esb => C::Sidebar(to_sidebar(pairs)),
-        Rule::periph => C::Peripheral(pairs.next_str().to_string()),
-        Rule::fig => C::Figure(to_figure(pairs)),
-        _ => panic!("Unexpected rule {:?} in to_book_contents", rule),
-    }
-}
-
-pub fn to_paragraph_contents(pair: Pair<Rule>) -> ParagraphContents {
+        Rule::usfm_ver => C::Usfm(pairs.next_str()?),
+        Rule::ide => C::Encoding(pairs.next_with("encoding", to_book_encoding)?),
+        Rule::sts => C::Status(pairs.next_value()?),
+        Rule::c => C::Chapter(pairs.next_value()?),
+        Rule::ca => C::AltChapter(pairs.next_value()?),
+        Rule::p => C::Paragraph(to_paragraph(pairs)?),
+        Rule::pn => C::Paragraph(to_numbered_paragraph(pairs)?),
+        Rule::q => C::Poetry(to_poetry(pairs)?),
+        Rule::qn => C::Poetry(to_numbered_poetry(pairs)?),
+        Rule::e => C::Element(to_element(pairs)?),
+        Rule::en => C::Element(to_numbered_element(pairs)?),
+        Rule::em => C::Empty(pairs.next_with("empty marker type", to_empty_type)?),
+        Rule::tr => C::TableRow(to_table_row(pairs)?),
+        Rule::esb => C::Sidebar(to_sidebar(pairs)?),
+        Rule::periph => C::Peripheral(pairs.next_str()?),
+        Rule::fig => C::Figure(to_figure(pairs)?),
+        Rule::zp => C::Custom(to_custom_paragraph(pairs)?),
+        _ => return Err(ParseError::unrecognized("book content", span)),
+    })
+}
+
+pub fn to_paragraph_contents<'i>(
+    pair: Pair<'i, Rule>,
+) -> Result<ParagraphContents<'i>, ParseError> {
     use ParagraphContents as C;
     let rule = pair.as_rule();
     if rule == Rule::ntext || rule == Rule::text {
-        return C::Line(pair.as_str().to_string());
+        return Ok(C::Line(pair.as_str()));
     }
-    let pairs: Unpack<'_, Rule> = pair.into_inner().into();
-    match rule {
+    let span = pair.as_span();
+    let pairs: Unpack<'i, Rule> = pair.into();
+    Ok(match rule {
         Rule::br => C::OptionalBreak,
-        Rule::fig => C::Figure(to_figure(pairs)),
-        Rule::ms => C::Milestone(to_milestone(pairs)),
-        Rule::mn => C::Milestone(to_numbered_milestone(pairs)),
-        Rule::cat => C::Category(to_category(pairs)),
-        Rule::v => C::Verse(to_verse(pairs)),
-        Rule::k => C::Character(to_character(pairs)),
-        Rule::kn => C::Character(to_numbered_character(pairs)),
-        Rule::f => C::Footnote(to_footnote(pairs)),
-        Rule::x => C::CrossRef(to_cross_ref(pairs)),
-        _ => panic!("Unexpected rule {:?} in to_paragraph_contents", rule),
-    }
-}
-
-pub fn to_element_contents(pair: Pair<Rule>) -> ElementContents {
+        Rule::fig => C::Figure(to_figure(pairs)?),
+        Rule::ms => C::Milestone(to_milestone(pairs)?),
+        Rule::mn => C::Milestone(to_numbered_milestone(pairs)?),
+        Rule::cat => C::Category(to_category(pairs)?),
+        Rule::v => C::Verse(to_verse(pairs)?),
+        Rule::k => C::Character(to_character(pairs)?),
+        Rule::kn => C::Character(to_numbered_character(pairs)?),
+        Rule::f => C::Footnote(to_footnote(pairs)?),
+        Rule::x => C::CrossRef(to_cross_ref(pairs)?),
+        Rule::zf | Rule::zfo => C::Footnote(to_custom_footnote(pairs)?),
+        Rule::zx | Rule::zxo => C::CrossRef(to_custom_cross_ref(pairs)?),
+        Rule::zk | Rule::zko => C::Custom(to_custom(pairs)?),
+        Rule::zms => C::Custom(to_custom_milestone(pairs)?),
+        _ => return Err(ParseError::unrecognized("paragraph content", span)),
+    })
+}
+
+pub fn to_element_contents<'i>(pair: Pair<'i, Rule>) -> Result<ElementContents<'i>, ParseError> {
     use ElementContents as C;
     let rule = pair.as_rule();
     if rule == Rule::ntext || rule == Rule::text {
-        return C::Line(pair.as_str().to_string());
+        return Ok(C::Line(pair.as_str()));
     }
-    let pairs: Unpack<'_, Rule> = pair.into_inner().into();
-    match rule {
+    let span = pair.as_span();
+    let pairs: Unpack<'i, Rule> = pair.into();
+    Ok(match rule {
         Rule::br => C::OptionalBreak,
-        Rule::fig => C::Figure(to_figure(pairs)),
-        Rule::ms => C::Milestone(to_milestone(pairs)),
-        Rule::mn => C::Milestone(to_numbered_milestone(pairs)),
-        Rule::cat => C::Category(to_category(pairs)),
-        Rule::k => C::Character(to_character(pairs)),
-        Rule::kn => C::Character(to_numbered_character(pairs)),
-        Rule::f => C::Footnote(to_footnote(pairs)),
-        Rule::x => C::CrossRef(to_cross_ref(pairs)),
-        _ => panic!("Unexpected rule {:?} in to_element_contents", rule),
-    }
-}
-
-pub fn to_character_contents(pair: Pair<Rule>) -> CharacterContents {
+        Rule::fig => C::Figure(to_figure(pairs)?),
+        Rule::ms => C::Milestone(to_milestone(pairs)?),
+        Rule::mn => C::Milestone(to_numbered_milestone(pairs)?),
+        Rule::cat => C::Category(to_category(pairs)?),
+        Rule::k => C::Character(to_character(pairs)?),
+        Rule::kn => C::Character(to_numbered_character(pairs)?),
+        Rule::f => C::Footnote(to_footnote(pairs)?),
+        Rule::x => C::CrossRef(to_cross_ref(pairs)?),
+        Rule::zf | Rule::zfo => C::Footnote(to_custom_footnote(pairs)?),
+        Rule::zx | Rule::zxo => C::CrossRef(to_custom_cross_ref(pairs)?),
+        Rule::zk | Rule::zko => C::Custom(to_custom(pairs)?),
+        Rule::zms => C::Custom(to_custom_milestone(pairs)?),
+        _ => return Err(ParseError::unrecognized("element content", span)),
+    })
+}
+
+pub fn to_character_contents<'i>(
+    pair: Pair<'i, Rule>,
+) -> Result<CharacterContents<'i>, ParseError> {
     use CharacterContents as C;
     let rule = pair.as_rule();
     if rule == Rule::ntext {
-        return C::Line(pair.as_str().to_string());
+        return Ok(C::Line(pair.as_str()));
     }
-    let pairs: Unpack<'_, Rule> = pair.into_inner().into();
-    match rule {
+    let span = pair.as_span();
+    let pairs: Unpack<'i, Rule> = pair.into();
+    Ok(match rule {
         Rule::br => C::OptionalBreak,
-        Rule::fig => C::Figure(to_figure(pairs)),
-        Rule::ms => C::Milestone(to_milestone(pairs)),
-        Rule::mn => C::Milestone(to_numbered_milestone(pairs)),
-        Rule::k => C::Character(to_character(pairs)),
-        Rule::kn => C::Character(to_numbered_character(pairs)),
-        Rule::f => C::Footnote(to_footnote(pairs)),
-        Rule::x => C::CrossRef(to_cross_ref(pairs)),
-        _ => panic!("Unexpected rule {:?} in to_character_contents", rule),
-    }
-}
-
-pub fn to_sidebar_contents(pair: Pair<Rule>) -> SidebarContents {
+        Rule::fig => C::Figure(to_figure(pairs)?),
+        Rule::ms => C::Milestone(to_milestone(pairs)?),
+        Rule::mn => C::Milestone(to_numbered_milestone(pairs)?),
+        Rule::k => C::Character(to_character(pairs)?),
+        Rule::kn => C::Character(to_numbered_character(pairs)?),
+        Rule::f => C::Footnote(to_footnote(pairs)?),
+        Rule::x => C::CrossRef(to_cross_ref(pairs)?),
+        Rule::zf | Rule::zfo => C::Footnote(to_custom_footnote(pairs)?),
+        Rule::zx | Rule::zxo => C::CrossRef(to_custom_cross_ref(pairs)?),
+        Rule::zk | Rule::zko => C::Custom(to_custom(pairs)?),
+        Rule::zms => C::Custom(to_custom_milestone(pairs)?),
+        _ => return Err(ParseError::unrecognized("character content", span)),
+    })
+}
+
+pub fn to_sidebar_contents<'i>(pair: Pair<'i, Rule>) -> Result<SidebarContents<'i>, ParseError> {
     use SidebarContents as C;
     let rule = pair.as_rule();
-    let mut pairs: Unpack<'_, Rule> = pair.into_inner().into();
-    match rule {
-        Rule::p => C::Paragraph(to_paragraph(pairs)),
-        Rule::pn => C::Paragraph(to_numbered_paragraph(pairs)),
-        Rule::q => C::Poetry(to_poetry(pairs)),
-        Rule::qn => C::Poetry(to_numbered_poetry(pairs)),
-        Rule::e => C::Element(to_element(pairs)),
-        Rule::en => C::Element(to_numbered_element(pairs)),
-        Rule::em => C::Empty(to_empty_type(pairs.next_str())),
-        Rule::tr => C::TableRow(to_table_row(pairs)),
-        Rule::cat => C::Category(to_category(pairs)),
-        _ => panic!("Unexpected rule {:?} in to_sidebar_contents", rule),
-    }
-}
-
-fn to_paragraph(mut pairs: Unpack<Rule>) -> Paragraph {
-    Paragraph {
-        style: to_paragraph_style(pairs.next_str()),
-        contents: pairs.map(to_paragraph_contents),
-    }
-}
-
-fn to_numbered_paragraph(mut pairs: Unpack<Rule>) -> Paragraph {
-    Paragraph {
-        style: to_numbered_paragraph_style(pairs.next_str(), pairs.next_value_or(Rule::num, 1)),
-        contents: pairs.map(to_paragraph_contents),
-    }
-}
-
-fn to_poetry(mut pairs: Unpack<Rule>) -> Poetry {
-    Poetry {
-        style: to_poetry_style(pairs.next_str()),
-        contents: pairs.map(to_paragraph_contents),
-    }
-}
-
-fn to_numbered_poetry(mut pairs: Unpack<Rule>) -> Poetry {
-    Poetry {
-        style: to_numbered_poetry_style(pairs.next_str(), pairs.next_value_or(Rule::num, 1)),
-        contents: pairs.map(to_paragraph_contents),
-    }
-}
-
-fn to_element(mut pairs: Unpack<Rule>) -> Element {
-    Element {
-        ty: to_element_type(pairs.next_str()),
-        contents: pairs.map(to_element_contents),
-    }
-}
-
-fn to_numbered_element(mut pairs: Unpack<Rule>) -> Element {
-    Element {
-        ty: to_numbered_element_type(pairs.next_str(), pairs.next_value_or(Rule::num, 1)),
-        contents: pairs.map(to_element_contents),
-    }
-}
-
-fn to_character(mut pairs: Unpack<Rule>) -> Character {
-    Character {
-        ty: to_character_type(pairs.next_str()),
+    let span = pair.as_span();
+    let mut pairs: Unpack<'i, Rule> = pair.into();
+    Ok(match rule {
+        Rule::p => C::Paragraph(to_paragraph(pairs)?),
+        Rule::pn => C::Paragraph(to_numbered_paragraph(pairs)?),
+        Rule::q => C::Poetry(to_poetry(pairs)?),
+        Rule::qn => C::Poetry(to_numbered_poetry(pairs)?),
+        Rule::e => C::Element(to_element(pairs)?),
+        Rule::en => C::Element(to_numbered_element(pairs)?),
+        Rule::em => C::Empty(pairs.next_with("empty marker type", to_empty_type)?),
+        Rule::tr => C::TableRow(to_table_row(pairs)?),
+        Rule::cat => C::Category(to_category(pairs)?),
+        _ => return Err(ParseError::unrecognized("sidebar content", span)),
+    })
+}
+
+fn to_paragraph<'i>(mut pairs: Unpack<'i, Rule>) -> Result<Paragraph<'i>, ParseError> {
+    Ok(Paragraph {
+        style: pairs.next_with("paragraph style", to_paragraph_style)?,
+        contents: pairs.map(to_paragraph_contents)?,
+    })
+}
+
+fn to_numbered_paragraph<'i>(mut pairs: Unpack<'i, Rule>) -> Result<Paragraph<'i>, ParseError> {
+    Ok(Paragraph {
+        style: pairs.next_numbered_with(
+            "numbered paragraph style",
+            Rule::num,
+            to_numbered_paragraph_style,
+        )?,
+        contents: pairs.map(to_paragraph_contents)?,
+    })
+}
+
+fn to_poetry<'i>(mut pairs: Unpack<'i, Rule>) -> Result<Poetry<'i>, ParseError> {
+    Ok(Poetry {
+        style: pairs.next_with("poetry style", to_poetry_style)?,
+        contents: pairs.map(to_paragraph_contents)?,
+    })
+}
+
+fn to_numbered_poetry<'i>(mut pairs: Unpack<'i, Rule>) -> Result<Poetry<'i>, ParseError> {
+    Ok(Poetry {
+        style: pairs.next_numbered_with(
+            "numbered poetry style",
+            Rule::num,
+            to_numbered_poetry_style,
+        )?,
+        contents: pairs.map(to_paragraph_contents)?,
+    })
+}
+
+fn to_element<'i>(mut pairs: Unpack<'i, Rule>) -> Result<Element<'i>, ParseError> {
+    Ok(Element {
+        ty: pairs.next_with("element type", to_element_type)?,
+        contents: pairs.map(to_element_contents)?,
+    })
+}
+
+fn to_numbered_element<'i>(mut pairs: Unpack<'i, Rule>) -> Result<Element<'i>, ParseError> {
+    Ok(Element {
+        ty: pairs.next_numbered_with(
+            "numbered element type",
+            Rule::num,
+            to_numbered_element_type,
+        )?,
+        contents: pairs.map(to_element_contents)?,
+    })
+}
+
+fn to_character<'i>(mut pairs: Unpack<'i, Rule>) -> Result<Character<'i>, ParseError> {
+    Ok(Character {
+        ty: pairs.next_with("character style", to_character_type)?,
         contents: pairs.map_if(
             false,
             &[Rule::attrib, Rule::value, Rule::default_value],
             to_character_contents,
-        ),
+        )?,
         attributes: pairs.map_if(
             true,
             &[Rule::attrib, Rule::value, Rule::default_value],
             to_attribute,
-        ),
-    }
+        )?,
+    })
 }
 
-fn to_numbered_character(mut pairs: Unpack<Rule>) -> Character {
-    let style = pairs.next_str();
-    let num: u8 = pairs.next_value_or(Rule::num, 1);
-    Character {
-        ty: to_numbered_character_type(style, num),
+fn to_numbered_character<'i>(mut pairs: Unpack<'i, Rule>) -> Result<Character<'i>, ParseError> {
+    Ok(Character {
+        ty: pairs.next_numbered_with(
+            "numbered character style",
+            Rule::num,
+            to_numbered_character_type,
+        )?,
         contents: pairs.map_if(
             false,
             &[Rule::attrib, Rule::value, Rule::default_value],
             to_character_contents,
-        ),
+        )?,
         attributes: pairs.map_if(
             true,
             &[Rule::attrib, Rule::value, Rule::default_value],
             to_attribute,
-        ),
-    }
+        )?,
+    })
 }
 
-fn to_footnote(mut pairs: Unpack<Rule>) -> Footnote {
-    Footnote {
-        style: to_footnote_style(pairs.next_str()),
-        caller: to_caller(pairs.next_char()),
-        elements: pairs.map(to_footnote_element),
-    }
+fn to_footnote<'i>(mut pairs: Unpack<'i, Rule>) -> Result<Footnote<'i>, ParseError> {
+    Ok(Footnote {
+        style: pairs.next_with("footnote style", to_footnote_style)?,
+        caller: to_caller(pairs.next_char()?),
+        elements: pairs.map(to_footnote_element)?,
+    })
 }
 
-fn to_cross_ref(mut pairs: Unpack<Rule>) -> CrossRef {
-    CrossRef {
-        style: to_cross_ref_style(pairs.next_str()),
-        caller: to_caller(pairs.next_char()),
-        elements: pairs.map(to_cross_ref_element),
-    }
+fn to_cross_ref<'i>(mut pairs: Unpack<'i, Rule>) -> Result<CrossRef<'i>, ParseError> {
+    Ok(CrossRef {
+        style: pairs.next_with("cross-reference style", to_cross_ref_style)?,
+        caller: to_caller(pairs.next_char()?),
+        elements: pairs.map(to_cross_ref_element)?,
+    })
 }
 
-pub fn to_footnote_element(pair: Pair<Rule>) -> FootnoteElement {
-    let mut pairs: Unpack<'_, Rule> = pair.into_inner().into();
-    FootnoteElement {
-        style: to_footnote_element_style(pairs.next_str()),
-        contents: pairs.map(to_character_contents),
-    }
+pub fn to_footnote_element<'i>(pair: Pair<'i, Rule>) -> Result<FootnoteElement<'i>, ParseError> {
+    let mut pairs: Unpack<'i, Rule> = pair.into();
+    Ok(FootnoteElement {
+        style: pairs.next_with("footnote element style", to_footnote_element_style)?,
+        contents: pairs.map(to_character_contents)?,
+    })
 }
 
-pub fn to_cross_ref_element(pair: Pair<Rule>) -> CrossRefElement {
-    let mut pairs: Unpack<'_, Rule> = pair.into_inner().into();
-    CrossRefElement {
-        style: to_cross_ref_element_style(pairs.next_str()),
-        contents: pairs.map(to_character_contents),
-    }
+pub fn to_cross_ref_element<'i>(pair: Pair<'i, Rule>) -> Result<CrossRefElement<'i>, ParseError> {
+    let mut pairs: Unpack<'i, Rule> = pair.into();
+    Ok(CrossRefElement {
+        style: pairs.next_with("cross-reference element style", to_cross_ref_element_style)?,
+        contents: pairs.map(to_character_contents)?,
+    })
 }
 
-fn to_verse(mut pairs: Unpack<Rule>) -> String {
-    pairs.next_str().to_string()
+fn to_verse<'i>(mut pairs: Unpack<'i, Rule>) -> Result<VerseNumber<'i>, ParseError> {
+    pairs.next_with("verse number", |s| {
+        Some(VerseNumber::try_from(s).unwrap_or_else(|_| VerseNumber::from_spelling(s)))
+    })
 }
 
-fn to_category(mut pairs: Unpack<Rule>) -> String {
-    pairs.next_str().to_string()
+fn to_category<'i>(mut pairs: Unpack<'i, Rule>) -> Result<&'i str, ParseError> {
+    pairs.next_str()
 }
 
-pub fn to_attribute(pair: Pair<Rule>) -> (String, String) {
+pub fn to_attribute<'i>(pair: Pair<'i, Rule>) -> Result<(&'i str, &'i str), ParseError> {
     if pair.as_rule() == Rule::attrib {
-        let mut pairs: Unpack<'_, Rule> = pair.into_inner().into();
-        (pairs.next_str().to_string(), pairs.next_str().to_string())
+        let mut pairs: Unpack<'i, Rule> = pair.into();
+        Ok((pairs.next_str()?, pairs.next_str()?))
     } else {
         // Rule::value or Rule::default_value
-        ("lemma".to_string(), pair.as_str().to_string())
+        Ok(("lemma", pair.as_str()))
     }
 }
 
-pub fn to_table_row(pairs: Unpack<Rule>) -> TableRow {
+pub fn to_table_row<'i>(pairs: Unpack<'i, Rule>) -> Result<TableRow<'i>, ParseError> {
     let mut cells = Vec::new();
     for pair in pairs.0 {
         match pair.as_rule() {
-            Rule::cell => cells.push(to_table_cell(pair)),
+            Rule::cell => cells.push(to_table_cell(pair)?),
             _ => {
                 if let Some(last) = cells.last_mut() {
-                    last.contents.push(to_paragraph_contents(pair));
+                    last.contents.push(to_paragraph_contents(pair)?);
                 }
             }
         }
     }
-    TableRow { cells }
+    Ok(TableRow { cells })
 }
 
-pub fn to_table_cell(pair: Pair<Rule>) -> TableCell {
-    let mut pairs: Unpack<'_, Rule> = pair.into_inner().into();
-    let prefix = to_cell_prefix(pairs.next_str());
-    let column: u8 = pairs.next_value();
-    let contents = pairs.map(to_paragraph_contents);
-    TableCell {
+pub fn to_table_cell<'i>(pair: Pair<'i, Rule>) -> Result<TableCell<'i>, ParseError> {
+    let mut pairs: Unpack<'i, Rule> = pair.into();
+    let prefix = pairs.next_with("table cell type", to_cell_prefix)?;
+    let column: u8 = pairs.next_value()?;
+    let contents = pairs.map(to_paragraph_contents)?;
+    Ok(TableCell {
         prefix,
         column,
         contents,
-    }
+    })
 }
 
-pub fn to_sidebar(pairs: Unpack<Rule>) -> Sidebar {
-    Sidebar {
-        contents: pairs.map(to_sidebar_contents),
-    }
+pub fn to_sidebar<'i>(pairs: Unpack<'i, Rule>) -> Result<Sidebar<'i>, ParseError> {
+    Ok(Sidebar {
+        contents: pairs.map(to_sidebar_contents)?,
+    })
 }
 
-pub fn to_figure(pairs: Unpack<Rule>) -> Figure {
-    Figure {
+pub fn to_figure<'i>(pairs: Unpack<'i, Rule>) -> Result<Figure<'i>, ParseError> {
+    Ok(Figure {
         contents: pairs.map_if(
             false,
             &[Rule::attrib, Rule::value, Rule::default_value],
             to_character_contents,
-        ),
+        )?,
         attributes: pairs.map_if(
             true,
             &[Rule::attrib, Rule::value, Rule::default_value],
             to_attribute,
-        ),
-    }
+        )?,
+    })
 }
 
-pub fn to_milestone(mut pairs: Unpack<Rule>) -> Milestone {
-    Milestone {
-        style: to_milestone_style(pairs.next_str(), pairs.next_str_opt()),
+pub fn to_milestone<'i>(mut pairs: Unpack<'i, Rule>) -> Result<Milestone<'i>, ParseError> {
+    let style = pairs.next_pair()?;
+    let bound = pairs.next_str_opt();
+    Ok(Milestone {
+        style: to_milestone_style(style.as_str(), bound)
+            .ok_or_else(|| ParseError::unrecognized("milestone style", style.as_span()))?,
         attributes: pairs.map_if(
             true,
             &[Rule::attrib, Rule::value, Rule::default_value],
             to_attribute,
-        ),
-    }
+        )?,
+    })
 }
 
-pub fn to_numbered_milestone(mut pairs: Unpack<Rule>) -> Milestone {
-    Milestone {
-        style: to_numbered_milestone_style(
-            pairs.next_str(),
-            pairs.next_value(),
-            pairs.next_str_opt(),
-        ),
+pub fn to_numbered_milestone<'i>(mut pairs: Unpack<'i, Rule>) -> Result<Milestone<'i>, ParseError> {
+    let style = pairs.next_pair()?;
+    let n = pairs.next_value()?;
+    let bound = pairs.next_str_opt();
+    Ok(Milestone {
+        style: to_numbered_milestone_style(style.as_str(), n, bound)
+            .ok_or_else(|| ParseError::unrecognized("numbered milestone style", style.as_span()))?,
         attributes: pairs.map_if(
             true,
             &[Rule::attrib, Rule::value, Rule::default_value],
             to_attribute,
-        ),
-    }
+        )?,
+    })
+}
+
+fn to_custom_paragraph<'i>(mut pairs: Unpack<'i, Rule>) -> Result<CustomParagraph<'i>, ParseError> {
+    Ok(CustomParagraph {
+        marker: pairs.next_str()?,
+        contents: pairs.map(to_paragraph_contents)?,
+    })
+}
+
+fn to_custom_footnote<'i>(mut pairs: Unpack<'i, Rule>) -> Result<Footnote<'i>, ParseError> {
+    Ok(Footnote {
+        style: FootnoteStyle::Custom(pairs.next_str()?),
+        caller: to_caller(pairs.next_char()?),
+        elements: pairs.map(to_footnote_element)?,
+    })
+}
+
+fn to_custom_cross_ref<'i>(mut pairs: Unpack<'i, Rule>) -> Result<CrossRef<'i>, ParseError> {
+    Ok(CrossRef {
+        style: CrossRefStyle::Custom(pairs.next_str()?),
+        caller: to_caller(pairs.next_char()?),
+        elements: pairs.map(to_cross_ref_element)?,
+    })
 }
 
-pub fn to_paragraph_style(s: &str) -> ParagraphStyle {
+fn to_custom<'i>(mut pairs: Unpack<'i, Rule>) -> Result<Custom<'i>, ParseError> {
+    Ok(Custom {
+        marker: pairs.next_str()?,
+        milestone: None,
+        contents: pairs.map_if(
+            false,
+            &[Rule::attrib, Rule::value, Rule::default_value],
+            to_character_contents,
+        )?,
+        attributes: pairs.map_if(
+            true,
+            &[Rule::attrib, Rule::value, Rule::default_value],
+            to_attribute,
+        )?,
+    })
+}
+
+fn to_custom_milestone<'i>(mut pairs: Unpack<'i, Rule>) -> Result<Custom<'i>, ParseError> {
+    let marker = pairs.next_str()?;
+    let bound = pairs.next_str_if(Rule::dir);
+    Ok(Custom {
+        marker,
+        milestone: to_milestone_bound(bound),
+        contents: vec![],
+        attributes: pairs.map(to_attribute)?,
+    })
+}
+
+pub fn to_paragraph_style(s: &str) -> Option<ParagraphStyle<'static>> {
     use ParagraphStyle::*;
-    match s {
+    Some(match s {
         "p" => Normal,
         "m" => Margin,
         "po" => Opening,
@@ -385,48 +566,45 @@ pub fn to_paragraph_style(s: &str) -> ParagraphStyle {
         "lh" => ListHeader,
         "lf" => ListFooter,
         "d" => Descriptive,
-        _ => panic!("Unknown paragraph style: {:?}", s),
-    }
+        _ => return None,
+    })
 }
 
-pub fn to_numbered_paragraph_style(s: &str, n: u8) -> ParagraphStyle {
+pub fn to_numbered_paragraph_style(s: &str, n: u8) -> Option<ParagraphStyle<'static>> {
     use ParagraphStyle::*;
-    match s {
+    Some(match s {
         "pi" => Indented(n),
         "ph" => HangingIndented(n),
         "mi" => MarginIndentedNum(n),
         "lim" => EmbeddedListEntry(n),
         "li" => ListEntry(n),
-        _ => panic!(
-            "Unknown numbered paragraph style: {:?} with number {}",
-            s, n
-        ),
-    }
+        _ => return None,
+    })
 }
 
-pub fn to_poetry_style(s: &str) -> PoetryStyle {
+pub fn to_poetry_style(s: &str) -> Option<PoetryStyle> {
     use PoetryStyle::*;
-    match s {
+    Some(match s {
         "qr" => Right,
         "qc" => Centered,
         "qa" => AcrosticHeading,
         "qd" => Descriptive,
-        _ => panic!("Unknown poetry style: {:?}", s),
-    }
+        _ => return None,
+    })
 }
 
-pub fn to_numbered_poetry_style(s: &str, n: u8) -> PoetryStyle {
+pub fn to_numbered_poetry_style(s: &str, n: u8) -> Option<PoetryStyle> {
     use PoetryStyle::*;
-    match s {
+    Some(match s {
         "q" => Normal(n),
         "qm" => Embedded(n),
-        _ => panic!("Unknown numbered poetry style: {:?} with number {}", s, n),
-    }
+        _ => return None,
+    })
 }
 
-pub fn to_element_type(s: &str) -> ElementType {
+pub fn to_element_type(s: &str) -> Option<ElementType<'static>> {
     use ElementType::*;
-    match s {
+    Some(match s {
         "rem" => Remark,
         "h" => Header,
         "ip" => Intro,
@@ -452,13 +630,13 @@ pub fn to_element_type(s: &str) -> ElementType {
         "ms" => MajorSection(1),
         "mt" => MajorTitle(1),
         "s" => Section(1),
-        _ => panic!("Unknown element type: {:?}", s),
-    }
+        _ => return None,
+    })
 }
 
-pub fn to_numbered_element_type(s: &str, n: u8) -> ElementType {
+pub fn to_numbered_element_type(s: &str, n: u8) -> Option<ElementType<'static>> {
     use ElementType::*;
-    match s {
+    Some(match s {
         "toc" => Contents(n),
         "toca" => AltContents(n),
         "imt" => MajorIntro(n),
@@ -472,23 +650,23 @@ pub fn to_numbered_element_type(s: &str, n: u8) -> ElementType {
         "ms" => MajorSection(n),
         "s" => Section(n),
         "sd" => Division(n),
-        _ => panic!("Unknown numbered element type: {:?} with number {}", s, n),
-    }
+        _ => return None,
+    })
 }
 
-pub fn to_empty_type(s: &str) -> EmptyType {
+pub fn to_empty_type(s: &str) -> Option<EmptyType> {
     use EmptyType::*;
-    match s {
+    Some(match s {
         "b" => Blank,
         "pb" => PageBreak,
         "ib" => IntroBlank,
-        _ => panic!("Unknown empty type: {:?}", s),
-    }
+        _ => return None,
+    })
 }
 
-pub fn to_character_type(s: &str) -> CharacterType {
+pub fn to_character_type(s: &str) -> Option<CharacterType<'static>> {
     use CharacterType::*;
-    match s {
+    Some(match s {
         "ior" => IntroOutline,
         "iqt" => IntroQuote,
         "rq" => InlineQuote,
@@ -536,41 +714,41 @@ pub fn to_character_type(s: &str) -> CharacterType {
         "ta" => TextAlternative,
         "va" => AltVerse,
         "xt" => CrossRefTarget,
-        _ => panic!("Unknown character type: {:?}", s),
-    }
+        _ => return None,
+    })
 }
 
-pub fn to_numbered_character_type(s: &str, n: u8) -> CharacterType {
+pub fn to_numbered_character_type(s: &str, n: u8) -> Option<CharacterType<'static>> {
     use CharacterType::*;
-    match s {
+    Some(match s {
         "liv" => ListValue(n),
-        _ => panic!("Unknown numbered character type: {:?} with number {}", s, n),
-    }
+        _ => return None,
+    })
 }
 
-pub fn to_footnote_style(s: &str) -> FootnoteStyle {
+pub fn to_footnote_style(s: &str) -> Option<FootnoteStyle<'static>> {
     use FootnoteStyle::*;
-    match s {
+    Some(match s {
         "f" => Footnote,
         "fe" => Endnote,
         "ef" => ExtendedFootnote,
         "efe" => ExtendedEndnote,
-        _ => panic!("Unknown footnote style: {:?}", s),
-    }
+        _ => return None,
+    })
 }
 
-pub fn to_cross_ref_style(s: &str) -> CrossRefStyle {
+pub fn to_cross_ref_style(s: &str) -> Option<CrossRefStyle<'static>> {
     use CrossRefStyle::*;
-    match s {
+    Some(match s {
         "x" => CrossRef,
         "ex" => ExtendedCrossRef,
-        _ => panic!("Unknown cross-reference style: {:?}", s),
-    }
+        _ => return None,
+    })
 }
 
-pub fn to_footnote_element_style(s: &str) -> FootnoteElementStyle {
+pub fn to_footnote_element_style(s: &str) -> Option<FootnoteElementStyle> {
     use FootnoteElementStyle::*;
-    match s {
+    Some(match s {
         "fq" => TranslationQuote,
         "fqa" => AltTranslationQuote,
         "fk" => Keyword,
@@ -581,13 +759,13 @@ pub fn to_footnote_element_style(s: &str) -> FootnoteElementStyle {
         "fdc" => DeuteroText,
         "fv" => Verse,
         "fr" => Reference,
-        _ => panic!("Unknown footnote element style: {:?}", s),
-    }
+        _ => return None,
+    })
 }
 
-pub fn to_cross_ref_element_style(s: &str) -> CrossRefElementStyle {
+pub fn to_cross_ref_element_style(s: &str) -> Option<CrossRefElementStyle> {
     use CrossRefElementStyle::*;
-    match s {
+    Some(match s {
         "xk" => Keyword,
         "xq" => Quote,
         "xt" => Target,
@@ -598,8 +776,8 @@ pub fn to_cross_ref_element_style(s: &str) -> CrossRefElementStyle {
         "xdc" => DeuteroTarget,
         "rq" => InlineQuote,
         "xo" => OriginRef,
-        _ => panic!("Unknown cross-reference element style: {:?}", s),
-    }
+        _ => return None,
+    })
 }
 
 pub fn to_caller(c: char) -> Caller {
@@ -611,61 +789,57 @@ pub fn to_caller(c: char) -> Caller {
     }
 }
 
-pub fn to_numbered_milestone_style(s: &str, n: u8, b: Option<&str>) -> MilestoneStyle {
+pub fn to_numbered_milestone_style(
+    s: &str,
+    n: u8,
+    b: Option<&str>,
+) -> Option<MilestoneStyle<'static>> {
     use MilestoneStyle::*;
-    let bound = to_milestone_bound(b);
-    match (s, b) {
+    let bound = to_milestone_bound(b)?;
+    Some(match (s, b) {
         ("qt", _) => QuotedText(n, bound),
-        _ => panic!(
-            "Unknown numbered milestone style: {:?}",
-            b.map(|b| format!("{}-{}", s, b))
-                .unwrap_or(format!("{}", s))
-        ),
-    }
+        _ => return None,
+    })
 }
 
-pub fn to_milestone_style(s: &str, b: Option<&str>) -> MilestoneStyle {
+pub fn to_milestone_style(s: &str, b: Option<&str>) -> Option<MilestoneStyle<'static>> {
     use MilestoneStyle::*;
-    let bound = to_milestone_bound(b);
-    match (s, b) {
+    let bound = to_milestone_bound(b)?;
+    Some(match (s, b) {
         ("qt", _) => QuotedText(1, bound),
         ("ts", _) => TextSection(bound),
         ("t", _) => Text(bound),
         ("wj", _) => WordsOfJesus(bound),
         ("vid", None) => VerseId,
-        _ => panic!(
-            "Unknown milestone style: {:?}",
-            b.map(|b| format!("{}-{}", s, b))
-                .unwrap_or(format!("{}", s))
-        ),
-    }
+        _ => return None,
+    })
 }
 
-pub fn to_milestone_bound(s: Option<&str>) -> MilestoneBound {
-    match s {
+pub fn to_milestone_bound(s: Option<&str>) -> Option<MilestoneBound> {
+    Some(match s {
         Some("s") => MilestoneBound::Start,
         Some("e") => MilestoneBound::End,
         None => MilestoneBound::None,
-        Some(s) => panic!("Unknown milestone direction: {:?}", s),
-    }
+        Some(_) => return None,
+    })
 }
 
-pub fn to_cell_prefix(s: &str) -> CellPrefix {
+pub fn to_cell_prefix(s: &str) -> Option<CellPrefix> {
     use CellPrefix::*;
-    match s {
+    Some(match s {
         "th" => Header,
         "thr" => HeaderRight,
         "thc" => HeaderCenter,
         "tc" => Content,
         "tcr" => ContentRight,
         "tcc" => ContentCenter,
-        _ => panic!("Unknown cell prefix: {:?}", s),
-    }
+        _ => return None,
+    })
 }
 
-pub fn to_book_identifier(s: &str) -> BookIdentifier {
+pub fn to_book_identifier(s: &str) -> Option<BookIdentifier> {
     use BookIdentifier::*;
-    match s {
+    Some(match s {
         "GEN" => Genesis,
         "EXO" => Exodus,
         "LEV" => Leviticus,
@@ -782,17 +956,17 @@ pub fn to_book_identifier(s: &str) -> BookIdentifier {
         "XXE" => ExtraE,
         "XXF" => ExtraF,
         "XXG" => ExtraG,
-        _ => panic!("Unknown book identifier: {:?}", s),
-    }
+        _ => return None,
+    })
 }
 
-fn to_book_encoding(s: &str) -> BookEncoding {
+pub fn to_book_encoding(s: &str) -> Option<BookEncoding> {
     use BookEncoding::*;
-    match s {
+    Some(match s {
         "CP-1252" => CP1252,
         "CP-1251" => CP1251,
         "UTF-8" => UTF8,
         "UTF-16" => UTF16,
-        _ => panic!("Unknown encoding: {:?}", s),
-    }
+        _ => return None,
+    })
 }
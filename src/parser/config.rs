@@ -0,0 +1,278 @@
+use pest::iterators::Pair;
+
+use super::Rule;
+use crate::stylesheet::{StyleType, Stylesheet, TextType};
+use crate::usfm::borrowed::*;
+
+/// Options controlling how input is parsed.
+///
+/// The free functions [`parse`](crate::parse) and friends use the default configuration.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ParserConfig {
+    pub unknown_markers: UnknownMarkers,
+    /// Declares markers beyond those the grammar knows, such as those in a project's
+    /// `custom.sty`. Declared markers are parsed into paragraphs, characters, notes and
+    /// milestones with a `Custom` style rather than into `Custom` nodes.
+    ///
+    /// Markers are classified by their `\StyleType`. A paragraph marker whose `\TextType` is
+    /// `Title`, `Section` or `Other` is an [`ElementType::Custom`](crate::ElementType::Custom)
+    /// instead, and character and note markers declared without an `\Endmarker` may be left
+    /// unclosed. `\OccursUnder` is not checked.
+    pub stylesheet: Stylesheet,
+}
+
+/// What to do with markers the grammar does not know, such as Paratext `\z` markers.
+///
+/// Markers declared in the [`ParserConfig::stylesheet`] are not unknown.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum UnknownMarkers {
+    /// Keep them as `Custom` nodes, which [`format`](crate::format) writes back out.
+    #[default]
+    Preserve,
+    /// Leave them, and anything inside them, out of the book.
+    Strip,
+    /// Reject the input with an [`Unrecognized`](crate::ParseErrorKind::Unrecognized) error.
+    Error,
+}
+
+impl ParserConfig {
+    /// Whether `pair` is a custom marker that the stylesheet does not declare for its position.
+    pub(crate) fn is_unknown(&self, pair: &Pair<Rule>) -> bool {
+        let style_type = match pair.as_rule() {
+            Rule::zp => StyleType::Paragraph,
+            Rule::zk | Rule::zko => StyleType::Character,
+            Rule::zf | Rule::zx | Rule::zfo | Rule::zxo => StyleType::Note,
+            Rule::zms => StyleType::Milestone,
+            _ => return false,
+        };
+        let marker = pair.clone().into_inner().next().map_or("", |p| p.as_str());
+        !self.stylesheet.declares(marker, style_type)
+    }
+
+    /// Whether `pair` is a custom marker left without its end marker, which only markers
+    /// declared without an `\Endmarker` may be.
+    pub(crate) fn is_unclosed(&self, pair: &Pair<Rule>) -> bool {
+        let style_type = match pair.as_rule() {
+            Rule::zko => StyleType::Character,
+            Rule::zfo | Rule::zxo => StyleType::Note,
+            _ => return false,
+        };
+        let marker = pair.clone().into_inner().next().map_or("", |p| p.as_str());
+        !(self.declares(marker, style_type)
+            && self
+                .stylesheet
+                .get(marker)
+                .is_some_and(|s| s.endmarker.is_none()))
+    }
+
+    fn declares(&self, marker: &str, style_type: StyleType) -> bool {
+        self.stylesheet.declares(marker, style_type)
+    }
+
+    /// Turns declared custom markers into styled nodes, and strips unknown ones if asked to.
+    pub(crate) fn resolve(&self, contents: &mut Vec<BookContents>) {
+        for content in contents.iter_mut() {
+            if let BookContents::Custom(p) = content
+                && self.declares(p.marker, StyleType::Paragraph)
+            {
+                *content = self.resolve_paragraph(p);
+            }
+        }
+        if self.unknown_markers == UnknownMarkers::Strip {
+            contents.retain(|c| !matches!(c, BookContents::Custom(_)));
+        }
+        for content in contents {
+            use BookContents as C;
+            match content {
+                C::Paragraph(p) => self.resolve_inline(&mut p.contents),
+                C::Poetry(p) => self.resolve_inline(&mut p.contents),
+                C::Element(e) => self.resolve_inline(&mut e.contents),
+                C::TableRow(tr) => self.resolve_table_row(tr),
+                C::Figure(fig) => self.resolve_inline(&mut fig.contents),
+                C::Custom(p) => self.resolve_inline(&mut p.contents),
+                C::Sidebar(sb) => {
+                    for content in &mut sb.contents {
+                        use SidebarContents as C;
+                        match content {
+                            C::Paragraph(p) => self.resolve_inline(&mut p.contents),
+                            C::Poetry(p) => self.resolve_inline(&mut p.contents),
+                            C::Element(e) => self.resolve_inline(&mut e.contents),
+                            C::TableRow(tr) => self.resolve_table_row(tr),
+                            C::Empty(_) | C::Category(_) => {}
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// A declared paragraph marker is an element if its `\TextType` makes it a title, a
+    /// section heading or other non-verse text, and it holds no verses.
+    fn resolve_paragraph<'i>(&self, p: &mut CustomParagraph<'i>) -> BookContents<'i> {
+        let kind = match self.stylesheet.get(p.marker).and_then(|s| s.text_type) {
+            Some(TextType::Title) => Some(ElementKind::Title),
+            Some(TextType::Section) => Some(ElementKind::Section),
+            Some(TextType::Other) => Some(ElementKind::Other),
+            _ => None,
+        };
+        let contents = std::mem::take(&mut p.contents);
+        match kind {
+            Some(kind)
+                if !contents
+                    .iter()
+                    .any(|c| matches!(c, ParagraphContents::Verse(_))) =>
+            {
+                BookContents::Element(Element {
+                    ty: ElementType::Custom(p.marker, kind),
+                    contents: contents
+                        .into_iter()
+                        .filter_map(to_element_contents)
+                        .collect(),
+                })
+            }
+            _ => BookContents::Paragraph(Paragraph {
+                style: ParagraphStyle::Custom(p.marker),
+                contents,
+            }),
+        }
+    }
+
+    fn resolve_table_row(&self, tr: &mut TableRow) {
+        for cell in &mut tr.cells {
+            self.resolve_inline(&mut cell.contents);
+        }
+    }
+
+    /// Resolves the custom markers in `contents`, and in everything nested inside it.
+    fn resolve_inline<'i, T: Inline<'i>>(&self, contents: &mut Vec<T>) {
+        for content in contents.iter_mut() {
+            if let InlineNode::Custom(c) = content.node()
+                && let Some(resolved) = self.resolve_custom(c)
+            {
+                *content = T::resolved(resolved);
+            }
+        }
+        if self.unknown_markers == UnknownMarkers::Strip {
+            contents.retain_mut(|c| match c.node() {
+                InlineNode::Custom(_) => false,
+                InlineNode::Footnote(f) => !self.is_unknown_footnote(f),
+                InlineNode::CrossRef(x) => !self.is_unknown_cross_ref(x),
+                _ => true,
+            });
+        }
+        for content in contents {
+            match content.node() {
+                InlineNode::Character(c) => self.resolve_inline(&mut c.contents),
+                InlineNode::Footnote(f) => {
+                    self.resolve_notes(f.elements.iter_mut().map(|e| &mut e.contents))
+                }
+                InlineNode::CrossRef(x) => {
+                    self.resolve_notes(x.elements.iter_mut().map(|e| &mut e.contents))
+                }
+                InlineNode::Figure(fig) => self.resolve_inline(&mut fig.contents),
+                InlineNode::Custom(c) => self.resolve_inline(&mut c.contents),
+                InlineNode::Other => {}
+            }
+        }
+    }
+
+    fn resolve_notes<'a, 'i: 'a>(
+        &self,
+        elements: impl Iterator<Item = &'a mut Vec<CharacterContents<'i>>>,
+    ) {
+        elements.for_each(|contents| self.resolve_inline(contents));
+    }
+
+    fn resolve_custom<'i>(&self, c: &mut Custom<'i>) -> Option<Resolved<'i>> {
+        let attributes = &mut c.attributes;
+        match &c.milestone {
+            None if self.declares(c.marker, StyleType::Character) => {
+                Some(Resolved::Character(Character {
+                    ty: CharacterType::Custom(c.marker),
+                    contents: std::mem::take(&mut c.contents),
+                    attributes: std::mem::take(attributes),
+                }))
+            }
+            Some(bound) if self.declares(c.marker, StyleType::Milestone) => {
+                Some(Resolved::Milestone(Milestone {
+                    style: MilestoneStyle::Custom(c.marker, bound.clone()),
+                    attributes: std::mem::take(attributes),
+                }))
+            }
+            _ => None,
+        }
+    }
+
+    fn is_unknown_footnote(&self, f: &Footnote) -> bool {
+        matches!(&f.style, FootnoteStyle::Custom(m) if !self.declares(m, StyleType::Note))
+    }
+
+    fn is_unknown_cross_ref(&self, x: &CrossRef) -> bool {
+        matches!(&x.style, CrossRefStyle::Custom(m) if !self.declares(m, StyleType::Note))
+    }
+}
+
+fn to_element_contents(content: ParagraphContents) -> Option<ElementContents> {
+    use ParagraphContents as C;
+    Some(match content {
+        C::Line(text) => ElementContents::Line(text),
+        C::Character(c) => ElementContents::Character(c),
+        C::Footnote(f) => ElementContents::Footnote(f),
+        C::CrossRef(x) => ElementContents::CrossRef(x),
+        C::Figure(fig) => ElementContents::Figure(fig),
+        C::Milestone(ms) => ElementContents::Milestone(ms),
+        C::Category(cat) => ElementContents::Category(cat),
+        C::OptionalBreak => ElementContents::OptionalBreak,
+        C::Custom(c) => ElementContents::Custom(c),
+        C::Verse(_) => return None,
+    })
+}
+
+enum Resolved<'i> {
+    Character(Character<'i>),
+    Milestone(Milestone<'i>),
+}
+
+/// The nodes of inline content that custom markers are resolved in.
+enum InlineNode<'a, 'i> {
+    Character(&'a mut Character<'i>),
+    Footnote(&'a mut Footnote<'i>),
+    CrossRef(&'a mut CrossRef<'i>),
+    Figure(&'a mut Figure<'i>),
+    Custom(&'a mut Custom<'i>),
+    Other,
+}
+
+/// Paragraph, element and character contents, which hold the same inline nodes.
+trait Inline<'i> {
+    fn node(&mut self) -> InlineNode<'_, 'i>;
+
+    fn resolved(resolved: Resolved<'i>) -> Self;
+}
+
+macro_rules! inline {
+    ($($Contents:ident),*) => {$(
+        impl<'i> Inline<'i> for $Contents<'i> {
+            fn node(&mut self) -> InlineNode<'_, 'i> {
+                match self {
+                    Self::Character(c) => InlineNode::Character(c),
+                    Self::Footnote(f) => InlineNode::Footnote(f),
+                    Self::CrossRef(x) => InlineNode::CrossRef(x),
+                    Self::Figure(fig) => InlineNode::Figure(fig),
+                    Self::Custom(c) => InlineNode::Custom(c),
+                    _ => InlineNode::Other,
+                }
+            }
+
+            fn resolved(resolved: Resolved<'i>) -> Self {
+                match resolved {
+                    Resolved::Character(c) => Self::Character(c),
+                    Resolved::Milestone(ms) => Self::Milestone(ms),
+                }
+            }
+        }
+    )*};
+}
+
+inline!(ParagraphContents, ElementContents, CharacterContents);
@@ -0,0 +1,241 @@
+use std::{error::Error, fmt, ops::Range};
+
+use pest::{
+    Span,
+    error::{ErrorVariant, InputLocation},
+};
+
+use super::Rule;
+
+/// An error produced while parsing USFM, carrying the position it occurred at.
+///
+/// ```
+/// use usfm::{ParseErrorKind, parse};
+///
+/// let err = parse("\\id GEN\n\\c 1\n\\v x In the beginning\n").unwrap_err();
+/// assert!(matches!(*err.kind, ParseErrorKind::Syntax));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ParseError {
+    /// What went wrong, boxed to keep `Result<_, ParseError>` small. Match on `*err.kind`.
+    pub kind: Box<ParseErrorKind>,
+    /// 1-based line number.
+    pub line: usize,
+    /// 1-based column number, counted in characters.
+    pub column: usize,
+    /// Byte offset into the input.
+    pub offset: usize,
+    /// The marker (without its backslash) at or enclosing the error position.
+    pub marker: Option<String>,
+    /// Friendly names of the constructs that would have been accepted.
+    pub expected: Vec<String>,
+    /// The source line the error occurred on.
+    pub snippet: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ParseErrorKind {
+    /// The input does not match the USFM grammar.
+    Syntax,
+    /// A marker or value that is not recognized in its position.
+    Unrecognized { what: &'static str, value: String },
+    /// A number that does not fit in the type it is stored as.
+    NumberOutOfRange { value: String, ty: &'static str },
+}
+
+/// A region of the input that [`parse_with_recovery`](crate::parse_with_recovery) had to skip.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Diagnostic {
+    /// Why the region could not be parsed.
+    pub error: ParseError,
+    /// Byte range of the input that was left out of the book.
+    pub skipped: Range<usize>,
+}
+
+impl ParseError {
+    pub(crate) fn new(kind: ParseErrorKind, input: &str, offset: usize) -> Self {
+        let offset = offset.min(input.len());
+        let line_start = input[..offset].rfind('\n').map_or(0, |i| i + 1);
+        let line_end = input[offset..]
+            .find('\n')
+            .map_or(input.len(), |i| offset + i);
+        Self {
+            kind: Box::new(kind),
+            line: input[..line_start].matches('\n').count() + 1,
+            column: input[line_start..offset].chars().count() + 1,
+            offset,
+            marker: marker_at(input, line_start, offset),
+            expected: Vec::new(),
+            snippet: input[line_start..line_end]
+                .trim_end_matches('\r')
+                .to_string(),
+        }
+    }
+
+    pub(crate) fn at(kind: ParseErrorKind, span: Span) -> Self {
+        Self::new(kind, span.get_input(), span.start())
+    }
+
+    pub(crate) fn unrecognized(what: &'static str, span: Span) -> Self {
+        let value = span.as_str().to_string();
+        Self::at(ParseErrorKind::Unrecognized { what, value }, span)
+    }
+
+    /// Relocates an error reported against `input[base..]` onto `input`.
+    pub(crate) fn offset_by(self, input: &str, base: usize) -> Self {
+        Self {
+            expected: self.expected,
+            ..Self::new(*self.kind, input, base + self.offset)
+        }
+    }
+
+    pub(crate) fn from_pest(error: pest::error::Error<Rule>, input: &str) -> Self {
+        let offset = match error.location {
+            InputLocation::Pos(pos) => pos,
+            InputLocation::Span((start, _)) => start,
+        };
+        let mut expected: Vec<String> = Vec::new();
+        if let ErrorVariant::ParsingError { positives, .. } = &error.variant {
+            for name in positives.iter().map(rule_name) {
+                if !expected.contains(&name) {
+                    expected.push(name);
+                }
+            }
+        }
+        Self {
+            expected,
+            ..Self::new(ParseErrorKind::Syntax, input, offset)
+        }
+    }
+
+    /// A short description of the error, without position information.
+    pub fn message(&self) -> String {
+        describe(&self.kind, &self.expected)
+    }
+
+    /// Renders the error as an annotated, compiler-style diagnostic.
+    ///
+    /// ```text
+    /// error: expected paragraph style
+    ///  --> GEN.usfm:3:2
+    ///   |
+    /// 3 | \zz Some text
+    ///   |  ^ at `\zz`
+    /// ```
+    pub fn render(&self, source_name: &str) -> String {
+        let number = self.line.to_string();
+        let gutter = " ".repeat(number.len());
+        let indent: String = self
+            .snippet
+            .chars()
+            .take(self.column - 1)
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect();
+        let label = self
+            .marker
+            .as_ref()
+            .map(|m| format!(" at `\\{}`", m))
+            .unwrap_or_default();
+        format!(
+            "error: {}\n{gutter}--> {}:{}:{}\n{gutter} |\n{number} | {}\n{gutter} | {indent}^{label}\n",
+            self.message(),
+            source_name,
+            self.line,
+            self.column,
+            self.snippet,
+        )
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}: {}", self.line, self.column, self.message())?;
+        if let Some(marker) = &self.marker {
+            write!(f, " (at \\{})", marker)?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for ParseError {}
+
+/// Describes `kind`, with `expected` the constructs a syntax error would have accepted.
+pub(crate) fn describe(kind: &ParseErrorKind, expected: &[String]) -> String {
+    match kind {
+        ParseErrorKind::Syntax => match expected {
+            [] => "unexpected input".to_string(),
+            [one] => format!("expected {}", one),
+            [first, second] => format!("expected {} or {}", first, second),
+            [init @ .., last] => format!("expected {}, or {}", init.join(", "), last),
+        },
+        ParseErrorKind::Unrecognized { what, value } => {
+            format!("unrecognized {} `{}`", what, value)
+        }
+        ParseErrorKind::NumberOutOfRange { value, ty } => {
+            format!("number `{}` does not fit in {}", value, ty)
+        }
+    }
+}
+
+fn is_marker_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '+' || c == '-' || c == '*'
+}
+
+/// Finds the marker whose name contains `offset`, or which starts at `offset`.
+fn marker_at(input: &str, line_start: usize, offset: usize) -> Option<String> {
+    let before = &input[line_start..offset];
+    let start = match before.rfind('\\') {
+        Some(i) if before[i + 1..].chars().all(is_marker_char) => line_start + i,
+        _ if input[offset..].starts_with('\\') => offset,
+        _ => return None,
+    };
+    let name: String = input[start + 1..]
+        .chars()
+        .take_while(|&c| is_marker_char(c))
+        .collect();
+    (!name.is_empty()).then_some(name)
+}
+
+pub(crate) fn rule_name(rule: &Rule) -> String {
+    match rule {
+        Rule::ntext | Rule::text => "text".into(),
+        Rule::k => "character style (\\style ...\\style*)".into(),
+        Rule::kn => "numbered character style (\\style1 ...\\style1*)".into(),
+        Rule::f => "footnote (\\f ...\\f*)".into(),
+        Rule::x => "cross-reference (\\x ...\\x*)".into(),
+        Rule::fig => "figure (\\fig ...\\fig*)".into(),
+        Rule::ms => "milestone (\\style\\*)".into(),
+        Rule::mn => "numbered milestone (\\style\\*)".into(),
+        Rule::cat => "category (\\cat ...\\cat*)".into(),
+        Rule::br => "optional break (//)".into(),
+        Rule::k_style => "character style name".into(),
+        Rule::kn_style => "numbered character style name".into(),
+        Rule::mn_style => "numbered milestone style name".into(),
+        Rule::ms_style => "milestone style name".into(),
+        Rule::fe_style => "footnote element style (\\ft, \\fk, \\fq, ...)".into(),
+        Rule::xe_style => "cross-reference element style (\\xt, \\xk, ...)".into(),
+        Rule::f_style => "footnote style (\\f, \\fe, \\ef)".into(),
+        Rule::x_style => "cross-reference style (\\x, \\ex)".into(),
+        Rule::p_style => "paragraph style".into(),
+        Rule::pn_style => "numbered paragraph style".into(),
+        Rule::q_style => "poetry style".into(),
+        Rule::qn_style => "numbered poetry style".into(),
+        Rule::e_type => "element type".into(),
+        Rule::en_type => "numbered element type".into(),
+        Rule::em_type => "empty marker type (\\b, \\pb, \\ib)".into(),
+        Rule::caller => "note caller (+, -, or character)".into(),
+        Rule::num => "number".into(),
+        Rule::verse_num => "verse number".into(),
+        Rule::cell_prefix => "table cell type (\\th, \\tc, ...)".into(),
+        Rule::custom_name => "custom marker name".into(),
+        Rule::zk => "custom character style (\\zstyle ...\\zstyle*)".into(),
+        Rule::zms => "custom milestone (\\zstyle\\*)".into(),
+        Rule::zf => "custom footnote (\\zstyle + ...\\zstyle*)".into(),
+        Rule::zx => "custom cross-reference (\\zstyle - ...\\zstyle*)".into(),
+        Rule::zp => "custom paragraph (\\zstyle ...)".into(),
+        Rule::zko => "custom character style without an end marker (\\zstyle ...)".into(),
+        Rule::zfo => "custom footnote without an end marker (\\zstyle + ...)".into(),
+        Rule::zxo => "custom cross-reference without an end marker (\\zstyle - ...)".into(),
+        _ => format!("{:?}", rule),
+    }
+}
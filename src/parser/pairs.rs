@@ -1,15 +1,18 @@
 use std::str::FromStr;
 
 use pest::{
-    RuleType,
+    RuleType, Span,
     iterators::{Pair, Pairs},
 };
 
-pub struct Unpack<'i, R>(pub Pairs<'i, R>);
+use super::{ParseError, ParseErrorKind};
 
-impl<'i, R> From<Pairs<'i, R>> for Unpack<'i, R> {
-    fn from(value: Pairs<'i, R>) -> Self {
-        Self(value)
+pub struct Unpack<'i, R>(pub Pairs<'i, R>, pub Span<'i>);
+
+impl<'i, R: RuleType> From<Pair<'i, R>> for Unpack<'i, R> {
+    fn from(value: Pair<'i, R>) -> Self {
+        let span = value.as_span();
+        Self(value.into_inner(), span)
     }
 }
 
@@ -18,35 +21,90 @@ impl<'i, R: RuleType> Unpack<'i, R> {
         self.0.next()
     }
 
-    pub fn next_str(&mut self) -> &'i str {
-        self.next_str_opt().unwrap()
+    pub fn next_pair(&mut self) -> Result<Pair<'i, R>, ParseError> {
+        self.next().ok_or_else(|| {
+            let end = self.1.end_pos();
+            ParseError::at(ParseErrorKind::Syntax, end.span(&end))
+        })
+    }
+
+    pub fn next_str(&mut self) -> Result<&'i str, ParseError> {
+        Ok(self.next_pair()?.as_str())
     }
 
     pub fn next_str_opt(&mut self) -> Option<&'i str> {
         self.next().as_ref().map(Pair::as_str)
     }
 
-    pub fn next_value<T: FromStr>(&mut self) -> T {
-        self.next_str().parse().unwrap_or_else(|_| panic!())
+    pub fn next_str_if(&mut self, rule: R) -> Option<&'i str> {
+        match self.0.peek() {
+            Some(pair) if pair.as_rule() == rule => self.next_str_opt(),
+            _ => None,
+        }
+    }
+
+    pub fn next_value<T: FromStr>(&mut self) -> Result<T, ParseError> {
+        let pair = self.next_pair()?;
+        pair.as_str().parse().map_err(|_| {
+            let kind = ParseErrorKind::NumberOutOfRange {
+                value: pair.as_str().to_string(),
+                ty: std::any::type_name::<T>(),
+            };
+            ParseError::at(kind, pair.as_span())
+        })
     }
 
-    pub fn next_value_or<T: FromStr>(&mut self, rule: R, default: T) -> T {
+    pub fn next_value_or<T: FromStr>(&mut self, rule: R, default: T) -> Result<T, ParseError> {
         if self.0.peek().map(|p| p.as_rule()) == Some(rule) {
             self.next_value()
         } else {
-            default
+            Ok(default)
         }
     }
 
-    pub fn next_char(&mut self) -> char {
-        self.next_str().chars().next().unwrap()
+    pub fn next_char(&mut self) -> Result<char, ParseError> {
+        let pair = self.next_pair()?;
+        pair.as_str()
+            .chars()
+            .next()
+            .ok_or_else(|| ParseError::at(ParseErrorKind::Syntax, pair.as_span()))
+    }
+
+    /// Converts the next pair with `f`, reporting it as an unrecognized `what` on failure.
+    pub fn next_with<T, F: FnOnce(&'i str) -> Option<T>>(
+        &mut self,
+        what: &'static str,
+        f: F,
+    ) -> Result<T, ParseError> {
+        let pair = self.next_pair()?;
+        f(pair.as_str()).ok_or_else(|| ParseError::unrecognized(what, pair.as_span()))
+    }
+
+    /// Like [`Unpack::next_with`], for a style followed by an optional number (defaulting to 1).
+    pub fn next_numbered_with<T, N: FromStr + From<u8>, F: FnOnce(&'i str, N) -> Option<T>>(
+        &mut self,
+        what: &'static str,
+        rule: R,
+        f: F,
+    ) -> Result<T, ParseError> {
+        let pair = self.next_pair()?;
+        let n = self.next_value_or(rule, N::from(1))?;
+        f(pair.as_str(), n).ok_or_else(|| ParseError::unrecognized(what, pair.as_span()))
     }
 
-    pub fn map<T, F: Fn(Pair<'i, R>) -> T>(self, f: F) -> Vec<T> {
+    pub fn map<T, F: Fn(Pair<'i, R>) -> Result<T, ParseError>>(
+        self,
+        f: F,
+    ) -> Result<Vec<T>, ParseError> {
         self.0.map(f).collect()
     }
 
-    pub fn map_if<T, F: Fn(Pair<'i, R>) -> T>(&self, only: bool, rules: &[R], f: F) -> Vec<T> {
+    pub fn map_if<T, F: Fn(Pair<'i, R>) -> Result<T, ParseError>>(
+        &self,
+        only: bool,
+        rules: &[R],
+        f: F,
+    ) -> Result<Vec<T>, ParseError> {
         self.0
             .clone()
             .filter(|pair| !(rules.contains(&pair.as_rule()) ^ only))
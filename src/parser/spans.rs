@@ -0,0 +1,224 @@
+use pest::iterators::{Pair, Pairs};
+
+use super::{ParserConfig, Rule, UnknownMarkers};
+
+/// A position in the parsed input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Location {
+    /// Byte offset into the input.
+    pub offset: usize,
+    /// 1-based line number.
+    pub line: usize,
+    /// 1-based column number, counted in characters.
+    pub column: usize,
+}
+
+/// The region of the input a node was parsed from, excluding surrounding whitespace.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Span {
+    pub start: Location,
+    pub end: Location,
+}
+
+impl Span {
+    pub fn range(&self) -> std::ops::Range<usize> {
+        self.start.offset..self.end.offset
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SpanNode {
+    pub span: Span,
+    pub children: Vec<SpanNode>,
+}
+
+/// Spans for every node of a [`Book`](crate::Book), kept alongside it rather than inside it.
+///
+/// Nodes are addressed by paths of indices: the first index is into `Book::contents`, and each
+/// following index is into the children of the node before it. The children of a node are its
+/// `contents` (paragraphs, poetry, elements, characters, figures, table cells and sidebars), its
+/// `elements` (footnotes and cross-references) or its `cells` (table rows). Attributes, styles
+/// and callers are not nodes.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SourceMap {
+    nodes: Vec<SpanNode>,
+}
+
+impl SourceMap {
+    pub(crate) fn new(input: &str, pairs: Pairs<Rule>, config: &ParserConfig) -> Self {
+        let walk = Walk { config };
+        let mut nodes: Vec<SpanNode> = pairs
+            .filter(|p| walk.is_node(p))
+            .map(|p| walk.to_span_node(p))
+            .collect();
+        let mut cursor = Cursor::new(input);
+        for node in &mut nodes {
+            cursor.resolve(node);
+        }
+        Self { nodes }
+    }
+
+    /// The top-level nodes, parallel to `Book::contents`.
+    pub fn nodes(&self) -> &[SpanNode] {
+        &self.nodes
+    }
+
+    pub fn node(&self, path: &[usize]) -> Option<&SpanNode> {
+        let (first, rest) = path.split_first()?;
+        rest.iter()
+            .try_fold(self.nodes.get(*first)?, |node, &i| node.children.get(i))
+    }
+
+    pub fn get(&self, path: &[usize]) -> Option<&Span> {
+        self.node(path).map(|node| &node.span)
+    }
+}
+
+fn is_leaf(rule: Rule) -> bool {
+    matches!(
+        rule,
+        Rule::text
+            | Rule::ntext
+            | Rule::br
+            | Rule::ms
+            | Rule::mn
+            | Rule::cat
+            | Rule::v
+            | Rule::em
+            | Rule::periph
+            | Rule::id
+            | Rule::usfm_ver
+            | Rule::ide
+            | Rule::sts
+            | Rule::c
+            | Rule::ca
+            | Rule::zms
+    )
+}
+
+fn is_node(rule: Rule) -> bool {
+    is_leaf(rule)
+        || matches!(
+            rule,
+            Rule::p
+                | Rule::pn
+                | Rule::q
+                | Rule::qn
+                | Rule::e
+                | Rule::en
+                | Rule::k
+                | Rule::kn
+                | Rule::f
+                | Rule::x
+                | Rule::fe
+                | Rule::xe
+                | Rule::fig
+                | Rule::tr
+                | Rule::cell
+                | Rule::esb
+                | Rule::zp
+                | Rule::zf
+                | Rule::zx
+                | Rule::zk
+                | Rule::zfo
+                | Rule::zxo
+                | Rule::zko
+        )
+}
+
+/// Walks the pairs of a parse, skipping unknown markers when they are stripped from the book.
+struct Walk<'c> {
+    config: &'c ParserConfig,
+}
+
+impl Walk<'_> {
+    fn is_node(&self, pair: &Pair<Rule>) -> bool {
+        is_node(pair.as_rule())
+            && !(self.config.unknown_markers == UnknownMarkers::Strip
+                && self.config.is_unknown(pair))
+    }
+
+    fn to_span_node(&self, pair: Pair<Rule>) -> SpanNode {
+        let rule = pair.as_rule();
+        let text = pair.as_str();
+        let (mut start, mut end) = (pair.as_span().start(), pair.as_span().end());
+        if !matches!(rule, Rule::text | Rule::ntext) {
+            start += text.len() - text.trim_start().len();
+            end = start.max(pair.as_span().start() + text.trim_end().len());
+        }
+        let mut children: Vec<SpanNode> = Vec::new();
+        if !is_leaf(rule) {
+            for child in pair.into_inner().filter(|p| self.is_node(p)) {
+                let child_rule = child.as_rule();
+                let node = self.to_span_node(child);
+                match children.last_mut() {
+                    // Contents following a cell belong to that cell, as in `to_table_row`.
+                    Some(cell) if rule == Rule::tr && child_rule != Rule::cell => {
+                        cell.span.end.offset = cell.span.end.offset.max(node.span.end.offset);
+                        cell.children.push(node);
+                    }
+                    None if rule == Rule::tr && child_rule != Rule::cell => {}
+                    _ => children.push(node),
+                }
+            }
+        }
+        if let Some(last) = children.last() {
+            end = end.max(last.span.end.offset);
+        }
+        let location = |offset| Location {
+            offset,
+            line: 0,
+            column: 0,
+        };
+        SpanNode {
+            span: Span {
+                start: location(start),
+                end: location(end),
+            },
+            children,
+        }
+    }
+}
+
+/// Fills in line and column numbers, walking forwards through the input.
+struct Cursor<'i> {
+    input: &'i str,
+    location: Location,
+}
+
+impl<'i> Cursor<'i> {
+    fn new(input: &'i str) -> Self {
+        Self {
+            input,
+            location: Location {
+                offset: 0,
+                line: 1,
+                column: 1,
+            },
+        }
+    }
+
+    fn locate(&mut self, offset: usize) -> Location {
+        if offset < self.location.offset {
+            *self = Self::new(self.input);
+        }
+        for c in self.input[self.location.offset..offset].chars() {
+            if c == '\n' {
+                self.location.line += 1;
+                self.location.column = 1;
+            } else {
+                self.location.column += 1;
+            }
+        }
+        self.location.offset = offset;
+        self.location
+    }
+
+    fn resolve(&mut self, node: &mut SpanNode) {
+        node.span.start = self.locate(node.span.start.offset);
+        for child in &mut node.children {
+            self.resolve(child);
+        }
+        node.span.end = self.locate(node.span.end.offset);
+    }
+}
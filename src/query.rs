@@ -0,0 +1,443 @@
+//! Finding the nodes of a [`Book`] by marker, attribute and reference.
+//!
+//! A [`Query`] is built up in code or parsed from a selector such as `w[strong=H*]`, a marker
+//! followed by any number of attribute tests, with alternatives separated by commas:
+//!
+//! ```
+//! use usfm::{Query, RefRange};
+//!
+//! let book = usfm::parse(
+//!     "\\id GEN\n\\c 1\n\\s1 The creation\n\\p\n\\v 1 In the \\w beginning|strong=\"H7225\"\\w* God...\n",
+//! )
+//! .unwrap();
+//!
+//! let words = book.query(&"w[strong=H*]".parse().unwrap());
+//! assert_eq!(words[0].node.text(), "beginning");
+//! assert_eq!(words[0].reference.to_string(), "GEN 1:1");
+//!
+//! let headings = Query::marker("s").or("ms").within("1".parse::<RefRange>().unwrap());
+//! assert_eq!(book.query(&headings)[0].node.text(), "The creation");
+//! ```
+
+use std::str::FromStr;
+
+use crate::format::{
+    character_type_to_str, cross_ref_element_style_to_str, cross_ref_style_to_str,
+    element_type_to_str, footnote_element_style_to_str, footnote_style_to_str,
+    milestone_style_to_str, paragraph_style_to_str, poetry_style_to_str,
+};
+use crate::parser::{ParseError, ParseErrorKind};
+use crate::reference::{RefRange, Reference};
+use crate::usfm::{
+    Book, BookIdentifier, Character, CrossRef, CrossRefElement, Custom, CustomParagraph, Element,
+    Figure, Footnote, FootnoteElement, Milestone, Paragraph, Poetry, VerseNumber,
+};
+use crate::visit::{self, Visit};
+
+/// Which nodes of a book to find, with [`Book::query`].
+///
+/// A marker written without a level matches every level of it, so `s` matches `\s1` and `\s2`
+/// and `q` matches `\q1`–`\q4`, but not `\qr`. The marker `*` matches every node.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Query {
+    selectors: Vec<Selector>,
+    range: Option<RefRange>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct Selector {
+    marker: String,
+    /// Each attribute key and, if its value is tested, the pattern it must match.
+    attributes: Vec<(String, Option<String>)>,
+}
+
+/// A node found by a [`Query`], with the chapter and verse where it starts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Match<'a> {
+    pub node: Node<'a>,
+    /// The book of `\id` and the chapter and verse in effect at the node, each of which is
+    /// `None` before the first of its markers.
+    pub reference: Reference,
+}
+
+/// A node of a book that has a marker of its own.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Node<'a> {
+    Paragraph(&'a Paragraph),
+    Poetry(&'a Poetry),
+    Element(&'a Element),
+    CustomParagraph(&'a CustomParagraph),
+    Character(&'a Character),
+    Footnote(&'a Footnote),
+    FootnoteElement(&'a FootnoteElement),
+    CrossRef(&'a CrossRef),
+    CrossRefElement(&'a CrossRefElement),
+    Figure(&'a Figure),
+    Milestone(&'a Milestone),
+    Custom(&'a Custom),
+}
+
+impl Query {
+    /// A query for the nodes with `marker`, written with or without its backslash.
+    pub fn marker(marker: impl Into<String>) -> Self {
+        Self::default().or(marker)
+    }
+
+    /// Also finds the nodes with `marker`.
+    pub fn or(mut self, marker: impl Into<String>) -> Self {
+        let marker = marker.into();
+        self.selectors.push(Selector {
+            marker: marker.strip_prefix('\\').unwrap_or(&marker).to_string(),
+            attributes: Vec::new(),
+        });
+        self
+    }
+
+    /// Requires the last marker added to have the attribute `key`, with a value matching
+    /// `pattern`, in which `*` matches any run of characters.
+    pub fn attribute(mut self, key: impl Into<String>, pattern: impl Into<String>) -> Self {
+        if let Some(selector) = self.selectors.last_mut() {
+            selector.attributes.push((key.into(), Some(pattern.into())));
+        }
+        self
+    }
+
+    /// Requires the last marker added to have the attribute `key`, with any value.
+    pub fn has_attribute(mut self, key: impl Into<String>) -> Self {
+        if let Some(selector) = self.selectors.last_mut() {
+            selector.attributes.push((key.into(), None));
+        }
+        self
+    }
+
+    /// Only finds nodes that start within `range`.
+    ///
+    /// A range without verses covers whole chapters, including the headings before their
+    /// first verse. A range that names its books only covers a book whose `\id` is one of them
+    /// or comes between them, and a range without books covers the chapters it names in any.
+    pub fn within(mut self, range: impl Into<RefRange>) -> Self {
+        self.range = Some(range.into());
+        self
+    }
+
+    /// Parses a selector such as `w[strong=H*]`, `f, x` or `fig[src="a b.jpg"][size]`.
+    pub fn parse(input: &str) -> Result<Self, ParseError> {
+        let mut query = Query::default();
+        for part in split_outside_quotes(input, ',') {
+            let part = part.trim();
+            let (marker, mut rest) = split_at_bracket(part);
+            let marker = marker.trim_end();
+            if marker.is_empty() || !marker.trim_start_matches('\\').chars().all(is_name_char) {
+                return Err(selector_error(input, part));
+            }
+            query = query.or(marker);
+            while !rest.is_empty() {
+                let Some(end) = rest
+                    .strip_prefix('[')
+                    .and_then(|r| find_outside_quotes(r, ']'))
+                else {
+                    return Err(selector_error(input, rest));
+                };
+                let attribute = &rest[1..end + 1];
+                query = match attribute.split_once('=') {
+                    Some((key, value)) => {
+                        let value = value.trim();
+                        let value = value
+                            .strip_prefix('"')
+                            .and_then(|v| v.strip_suffix('"'))
+                            .unwrap_or(value);
+                        query.attribute(key.trim(), value)
+                    }
+                    None => query.has_attribute(attribute.trim()),
+                };
+                rest = rest[end + 2..].trim_start();
+            }
+        }
+        Ok(query)
+    }
+
+    /// Whether `node` has a marker and attributes this query finds.
+    pub fn matches(&self, node: &Node) -> bool {
+        let marker = node.marker();
+        self.selectors.iter().any(|selector| {
+            matches_marker(&selector.marker, &marker)
+                && selector.attributes.iter().all(|(key, pattern)| {
+                    node.attributes().iter().any(|(k, value)| {
+                        k == key && pattern.as_ref().is_none_or(|p| matches_pattern(p, value))
+                    })
+                })
+        })
+    }
+}
+
+impl FromStr for Query {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl<'a> Node<'a> {
+    /// The marker of this node, without its backslash or any `-s`/`-e` suffix.
+    pub fn marker(&self) -> String {
+        match self {
+            Node::Paragraph(p) => paragraph_style_to_str(&p.style),
+            Node::Poetry(p) => poetry_style_to_str(&p.style),
+            Node::Element(e) => element_type_to_str(&e.ty),
+            Node::CustomParagraph(p) => p.marker.clone(),
+            Node::Character(c) => character_type_to_str(&c.ty).to_string(),
+            Node::Footnote(f) => footnote_style_to_str(&f.style).to_string(),
+            Node::FootnoteElement(e) => footnote_element_style_to_str(&e.style).to_string(),
+            Node::CrossRef(x) => cross_ref_style_to_str(&x.style).to_string(),
+            Node::CrossRefElement(e) => cross_ref_element_style_to_str(&e.style).to_string(),
+            Node::Figure(_) => "fig".to_string(),
+            Node::Milestone(ms) => milestone_style_to_str(&ms.style),
+            Node::Custom(c) => c.marker.clone(),
+        }
+    }
+
+    pub fn attributes(&self) -> &'a [(String, String)] {
+        match self {
+            Node::Character(c) => &c.attributes,
+            Node::Figure(fig) => &fig.attributes,
+            Node::Milestone(ms) => &ms.attributes,
+            Node::Custom(c) => &c.attributes,
+            _ => &[],
+        }
+    }
+
+    /// The plain text of this node, without verse numbers or the notes inside it, and with
+    /// whitespace collapsed.
+    pub fn text(&self) -> String {
+        let mut text = Text::default();
+        match *self {
+            Node::Paragraph(p) => visit::visit_paragraph(&mut text, p),
+            Node::Poetry(p) => visit::visit_poetry(&mut text, p),
+            Node::Element(e) => visit::visit_element(&mut text, e),
+            Node::CustomParagraph(p) => visit::visit_custom_paragraph(&mut text, p),
+            Node::Character(c) => visit::visit_character(&mut text, c),
+            Node::Footnote(f) => visit::visit_footnote(&mut text, f),
+            Node::FootnoteElement(e) => visit::visit_footnote_element(&mut text, e),
+            Node::CrossRef(x) => visit::visit_cross_ref(&mut text, x),
+            Node::CrossRefElement(e) => visit::visit_cross_ref_element(&mut text, e),
+            Node::Figure(fig) => visit::visit_figure(&mut text, fig),
+            Node::Milestone(_) => {}
+            Node::Custom(c) => visit::visit_custom(&mut text, c),
+        }
+        text.0.split_whitespace().collect::<Vec<_>>().join(" ")
+    }
+}
+
+impl Book {
+    /// The nodes `query` finds, in document order.
+    pub fn query(&self, query: &Query) -> Vec<Match<'_>> {
+        let mut matcher = Matcher {
+            query,
+            range: query.range.as_ref(),
+            book: None,
+            chapter: None,
+            verse: None,
+            matches: Vec::new(),
+        };
+        matcher.visit_book(self);
+        matcher.matches
+    }
+}
+
+struct Matcher<'q, 'a> {
+    query: &'q Query,
+    range: Option<&'q RefRange>,
+    book: Option<BookIdentifier>,
+    chapter: Option<u16>,
+    verse: Option<&'a VerseNumber>,
+    matches: Vec<Match<'a>>,
+}
+
+impl<'a> Matcher<'_, 'a> {
+    fn check(&mut self, node: Node<'a>) {
+        if self.is_in_range() && self.query.matches(&node) {
+            self.matches.push(Match {
+                node,
+                reference: Reference {
+                    book: self.book,
+                    chapter: self.chapter,
+                    verse: self.verse.cloned(),
+                },
+            });
+        }
+    }
+
+    fn is_in_range(&self) -> bool {
+        let Some(range) = self.range else {
+            return true;
+        };
+        let Some((start, end)) = range.bounds_in(self.book) else {
+            return false;
+        };
+        let chapter = self.chapter.unwrap_or(0);
+        let (first, last) = self.verse.map_or((0, 0), |v| (v.start, v.end));
+        (chapter, last) >= start && (chapter, first) <= end
+    }
+}
+
+impl<'a> Visit<'a> for Matcher<'_, 'a> {
+    fn visit_id(&mut self, code: &'a BookIdentifier, _text: Option<&'a str>) {
+        self.book = Some(*code);
+    }
+
+    fn visit_chapter(&mut self, number: u16) {
+        self.chapter = Some(number);
+        self.verse = None;
+    }
+
+    fn visit_paragraph(&mut self, node: &'a Paragraph) {
+        self.check(Node::Paragraph(node));
+        visit::visit_paragraph(self, node);
+    }
+
+    fn visit_poetry(&mut self, node: &'a Poetry) {
+        self.check(Node::Poetry(node));
+        visit::visit_poetry(self, node);
+    }
+
+    fn visit_element(&mut self, node: &'a Element) {
+        self.check(Node::Element(node));
+        visit::visit_element(self, node);
+    }
+
+    fn visit_custom_paragraph(&mut self, node: &'a CustomParagraph) {
+        self.check(Node::CustomParagraph(node));
+        visit::visit_custom_paragraph(self, node);
+    }
+
+    fn visit_character(&mut self, node: &'a Character) {
+        self.check(Node::Character(node));
+        visit::visit_character(self, node);
+    }
+
+    fn visit_footnote(&mut self, node: &'a Footnote) {
+        self.check(Node::Footnote(node));
+        visit::visit_footnote(self, node);
+    }
+
+    fn visit_footnote_element(&mut self, node: &'a FootnoteElement) {
+        self.check(Node::FootnoteElement(node));
+        visit::visit_footnote_element(self, node);
+    }
+
+    fn visit_cross_ref(&mut self, node: &'a CrossRef) {
+        self.check(Node::CrossRef(node));
+        visit::visit_cross_ref(self, node);
+    }
+
+    fn visit_cross_ref_element(&mut self, node: &'a CrossRefElement) {
+        self.check(Node::CrossRefElement(node));
+        visit::visit_cross_ref_element(self, node);
+    }
+
+    fn visit_figure(&mut self, node: &'a Figure) {
+        self.check(Node::Figure(node));
+        visit::visit_figure(self, node);
+    }
+
+    fn visit_milestone(&mut self, node: &'a Milestone) {
+        self.check(Node::Milestone(node));
+    }
+
+    fn visit_custom(&mut self, node: &'a Custom) {
+        self.check(Node::Custom(node));
+        visit::visit_custom(self, node);
+    }
+
+    fn visit_verse(&mut self, node: &'a VerseNumber) {
+        self.verse = Some(node);
+    }
+}
+
+/// Collects the text of a node, skipping the notes inside it.
+#[derive(Default)]
+struct Text(String);
+
+impl<'a> Visit<'a> for Text {
+    fn visit_footnote(&mut self, _node: &'a Footnote) {}
+
+    fn visit_cross_ref(&mut self, _node: &'a CrossRef) {}
+
+    fn visit_text(&mut self, text: &'a str) {
+        self.0.push_str(text);
+    }
+}
+
+fn matches_marker(selector: &str, marker: &str) -> bool {
+    if selector == "*" {
+        return true;
+    }
+    match marker.strip_prefix(selector) {
+        Some(level) => {
+            level.is_empty()
+                || (!selector.ends_with(|c: char| c.is_ascii_digit())
+                    && level.chars().all(|c| c.is_ascii_digit()))
+        }
+        None => false,
+    }
+}
+
+/// Matches `value` against `pattern`, in which `*` matches any run of characters.
+fn matches_pattern(pattern: &str, value: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = value.strip_prefix(first) else {
+        return false;
+    };
+    let parts: Vec<_> = parts.collect();
+    let Some((last, middle)) = parts.split_last() else {
+        return rest.is_empty();
+    };
+    for part in middle {
+        match rest.find(part) {
+            Some(i) => rest = &rest[i + part.len()..],
+            None => return false,
+        }
+    }
+    rest.len() >= last.len() && rest.ends_with(last)
+}
+
+fn is_name_char(c: char) -> bool {
+    c.is_alphanumeric() || matches!(c, '*' | '_' | '-')
+}
+
+fn split_at_bracket(s: &str) -> (&str, &str) {
+    s.split_at(s.find('[').unwrap_or(s.len()))
+}
+
+fn find_outside_quotes(s: &str, needle: char) -> Option<usize> {
+    let mut quoted = false;
+    for (i, c) in s.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            c if c == needle && !quoted => return Some(i),
+            _ => {}
+        }
+    }
+    None
+}
+
+fn split_outside_quotes(mut s: &str, separator: char) -> Vec<&str> {
+    let mut parts = Vec::new();
+    while let Some(i) = find_outside_quotes(s, separator) {
+        parts.push(&s[..i]);
+        s = &s[i + 1..];
+    }
+    parts.push(s);
+    parts
+}
+
+fn selector_error(input: &str, at: &str) -> ParseError {
+    let offset = at.as_ptr() as usize - input.as_ptr() as usize;
+    let kind = ParseErrorKind::Unrecognized {
+        what: "selector",
+        value: at.to_string(),
+    };
+    ParseError::new(kind, input, offset)
+}
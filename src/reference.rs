@@ -0,0 +1,461 @@
+//! Scripture references, as they appear in `\fr`, `\xo`, `\xt`, `\ior`, `\mr`, `\sr` and `\ref`.
+//!
+//! References are written as a book followed by a chapter and verse (`GEN 1:1`), with ranges
+//! (`GEN 1:1-2:3`), verse lists (`GEN 1:1,5`) and groups (`GEN 1:1; 2:4; EXO 3:14`). Parts that
+//! are left out are carried over from the previous reference, so every parsed reference is
+//! complete as far as its text allows.
+
+mod names;
+
+use std::{fmt, str::FromStr};
+
+use crate::parser::{ParseError, ParseErrorKind, to_book_identifier};
+use crate::usfm::{BookIdentifier, VerseNumber};
+
+pub use names::{BookNameTable, BookNames, NameStyle};
+
+/// A single point in the text: a book, a chapter in it, or a verse in that chapter.
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Reference {
+    pub book: Option<BookIdentifier>,
+    pub chapter: Option<u16>,
+    pub verse: Option<VerseNumber>,
+}
+
+/// A contiguous run of text from `start` to `end`, inclusive.
+///
+/// A single reference is a range whose `end` is its `start`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct RefRange {
+    pub start: Reference,
+    pub end: Reference,
+}
+
+/// A list of ranges, such as `GEN 1:1,5; 2:4`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct RefList(pub Vec<RefRange>);
+
+impl Reference {
+    pub fn book(book: BookIdentifier) -> Self {
+        Self {
+            book: Some(book),
+            ..Self::default()
+        }
+    }
+
+    pub fn chapter(book: BookIdentifier, chapter: u16) -> Self {
+        Self {
+            book: Some(book),
+            chapter: Some(chapter),
+            verse: None,
+        }
+    }
+
+    pub fn verse(book: BookIdentifier, chapter: u16, verse: impl Into<VerseNumber>) -> Self {
+        Self {
+            book: Some(book),
+            chapter: Some(chapter),
+            verse: Some(verse.into()),
+        }
+    }
+}
+
+impl RefRange {
+    pub fn new(start: Reference, end: Reference) -> Self {
+        Self { start, end }
+    }
+
+    pub fn is_single(&self) -> bool {
+        self.start == self.end
+    }
+
+    /// The first and last chapter and verse the range covers in `book`, or `None` if it names
+    /// books and `book` is neither of them nor between them. The chapter and verse of a bound
+    /// count only in its own book.
+    pub(crate) fn bounds_in(
+        &self,
+        book: Option<BookIdentifier>,
+    ) -> Option<((u16, u16), (u16, u16))> {
+        let (start, end) = (&self.start, &self.end);
+        if start.book.is_some_and(|b| book.is_none_or(|book| book < b))
+            || end.book.is_some_and(|b| book.is_none_or(|book| book > b))
+        {
+            return None;
+        }
+        let in_book = |bound: &Reference| bound.book.is_none() || bound.book == book;
+        let first = if in_book(start) {
+            (
+                start.chapter.unwrap_or(0),
+                start.verse.as_ref().map_or(0, |v| v.start),
+            )
+        } else {
+            (0, 0)
+        };
+        let last = if in_book(end) {
+            (
+                end.chapter.unwrap_or(u16::MAX),
+                end.verse.as_ref().map_or(u16::MAX, |v| v.end),
+            )
+        } else {
+            (u16::MAX, u16::MAX)
+        };
+        Some((first, last))
+    }
+}
+
+impl From<Reference> for RefRange {
+    fn from(reference: Reference) -> Self {
+        Self::new(reference.clone(), reference)
+    }
+}
+
+impl RefList {
+    /// Parses a reference list, taking any book and chapter it leaves out from `context`.
+    ///
+    /// This is how the `\fr` of a footnote is read: `\fr 3:16` means the book the note is in.
+    pub fn parse_relative(input: &str, context: &Reference) -> Result<Self, ParseError> {
+        RefParser::new(input, context, &to_book).parse()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &RefRange> {
+        self.0.iter()
+    }
+}
+
+impl From<RefRange> for RefList {
+    fn from(range: RefRange) -> Self {
+        Self(vec![range])
+    }
+}
+
+impl From<Reference> for RefList {
+    fn from(reference: Reference) -> Self {
+        RefRange::from(reference).into()
+    }
+}
+
+impl FromIterator<RefRange> for RefList {
+    fn from_iter<T: IntoIterator<Item = RefRange>>(iter: T) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+impl IntoIterator for RefList {
+    type Item = RefRange;
+    type IntoIter = std::vec::IntoIter<RefRange>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl FromStr for Reference {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let range: RefRange = s.parse()?;
+        if !range.is_single() {
+            return Err(ParseError::new(unrecognized("reference", s), s, 0));
+        }
+        Ok(range.start)
+    }
+}
+
+impl FromStr for RefRange {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let RefList(mut ranges) = s.parse()?;
+        if ranges.len() != 1 {
+            return Err(ParseError::new(unrecognized("reference range", s), s, 0));
+        }
+        Ok(ranges.remove(0))
+    }
+}
+
+impl FromStr for RefList {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse_relative(s, &Reference::default())
+    }
+}
+
+/// Whether the text after a separator is a chapter or a verse when it is a bare number.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Bare {
+    Chapter,
+    Verse,
+}
+
+pub(crate) struct RefParser<'a> {
+    input: &'a str,
+    context: Reference,
+    books: BookLookup<'a>,
+}
+
+impl<'a> RefParser<'a> {
+    pub(crate) fn new(input: &'a str, context: &Reference, books: BookLookup<'a>) -> Self {
+        Self {
+            input,
+            context: context.clone(),
+            books,
+        }
+    }
+
+    pub(crate) fn parse(mut self) -> Result<RefList, ParseError> {
+        let trimmed = self.input.trim();
+        let trimmed = trimmed
+            .strip_prefix('(')
+            .and_then(|s| s.strip_suffix(')'))
+            .unwrap_or(trimmed);
+        let trimmed = trimmed.trim_end_matches([':', '.']).trim();
+        if trimmed.is_empty() {
+            return Err(self.error(unrecognized("reference", trimmed), trimmed));
+        }
+        let mut ranges = Vec::new();
+        for group in trimmed.split(';') {
+            for (i, item) in group.split(',').enumerate() {
+                let bare = match i {
+                    0 => Bare::Chapter,
+                    _ if self.context.verse.is_some() => Bare::Verse,
+                    _ => Bare::Chapter,
+                };
+                ranges.push(self.parse_range(item, bare)?);
+            }
+        }
+        Ok(RefList(ranges))
+    }
+
+    fn parse_range(&mut self, item: &'a str, bare: Bare) -> Result<RefRange, ParseError> {
+        let (start, end) = match item.split_once(['-', '\u{2013}']) {
+            Some((start, end)) => (start, Some(end)),
+            None => (item, None),
+        };
+        let start = self.parse_reference(start, bare)?;
+        let end = match end {
+            Some(end) => {
+                let bare = match start.verse {
+                    Some(_) => Bare::Verse,
+                    None => Bare::Chapter,
+                };
+                self.parse_reference(end, bare)?
+            }
+            None => start.clone(),
+        };
+        if end < start {
+            return Err(self.error(unrecognized("reference range", item), item));
+        }
+        Ok(RefRange { start, end })
+    }
+
+    /// Parses one end of a range, filling in what it leaves out from the previous reference.
+    fn parse_reference(&mut self, s: &'a str, bare: Bare) -> Result<Reference, ParseError> {
+        let s = s.trim();
+        let (book, numbers) = match s.rsplit_once(char::is_whitespace) {
+            Some((book, numbers)) if self.is_numbers(numbers) => {
+                (Some(book.trim_end()), Some(numbers))
+            }
+            _ if self.is_numbers(s) => (None, Some(s)),
+            _ => (Some(s), None),
+        };
+        let mut reference = match book {
+            Some(book) => Reference::book(
+                (self.books)(book).ok_or_else(|| self.error(unrecognized("book", book), book))?,
+            ),
+            None => Reference {
+                book: self.context.book,
+                ..Reference::default()
+            },
+        };
+        if let Some(numbers) = numbers {
+            let invalid = || self.error(unrecognized("reference", s), s);
+            match numbers.split_once([':', '.']) {
+                Some((chapter, verse)) => {
+                    reference.chapter = Some(chapter.parse().map_err(|_| invalid())?);
+                    reference.verse = Some(verse.parse().map_err(|_| invalid())?);
+                }
+                None if book.is_none() && bare == Bare::Verse => {
+                    reference.chapter = self.context.chapter;
+                    reference.verse = Some(numbers.parse().map_err(|_| invalid())?);
+                }
+                None => reference.chapter = Some(numbers.parse().map_err(|_| invalid())?),
+            }
+        }
+        self.context = reference.clone();
+        Ok(reference)
+    }
+
+    /// Whether `s` is the chapter and verse part of a reference, such as `3`, `3:16` or `3.16a`,
+    /// rather than a book such as `1SA`.
+    fn is_numbers(&self, s: &str) -> bool {
+        s.starts_with(|c: char| c.is_ascii_digit())
+            && s.chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == ':' || c == '.')
+            && (self.books)(s).is_none()
+    }
+
+    fn error(&self, kind: ParseErrorKind, at: &str) -> ParseError {
+        let offset = at.as_ptr() as usize - self.input.as_ptr() as usize;
+        ParseError::new(kind, self.input, offset)
+    }
+}
+
+fn unrecognized(what: &'static str, value: &str) -> ParseErrorKind {
+    ParseErrorKind::Unrecognized {
+        what,
+        value: value.to_string(),
+    }
+}
+
+pub(crate) fn to_book(s: &str) -> Option<BookIdentifier> {
+    to_book_identifier(&s.to_ascii_uppercase())
+}
+
+/// Looks up the book a name in a reference stands for.
+type BookLookup<'a> = &'a dyn Fn(&str) -> Option<BookIdentifier>;
+
+/// Gives the name to write for a book in a reference.
+type BookName<'a> = &'a dyn Fn(BookIdentifier) -> &'a str;
+
+/// Writes a reference, range or list with the given book names.
+pub(crate) struct Named<'a, T> {
+    pub(crate) value: &'a T,
+    pub(crate) name: BookName<'a>,
+}
+
+impl fmt::Display for Named<'_, Reference> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let reference = self.value;
+        if let Some(book) = reference.book {
+            f.write_str((self.name)(book))?;
+            if reference.chapter.is_some() || reference.verse.is_some() {
+                f.write_str(" ")?;
+            }
+        }
+        write_numbers(f, reference.chapter, reference.verse.as_ref())
+    }
+}
+
+impl fmt::Display for Named<'_, RefRange> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let range = self.value;
+        write!(f, "{}", self.named(&range.start))?;
+        if !range.is_single() {
+            f.write_str("-")?;
+            self.write_relative(f, &range.end, &range.start)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Named<'_, RefList> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut previous: Option<&Reference> = None;
+        for range in &self.value.0 {
+            match previous {
+                None => write!(f, "{}", self.named(&range.start))?,
+                Some(previous) => {
+                    let same_chapter = range.start.book == previous.book
+                        && range.start.chapter == previous.chapter
+                        && range.start.verse.is_some()
+                        && previous.verse.is_some();
+                    f.write_str(if same_chapter { "," } else { "; " })?;
+                    self.write_relative(f, &range.start, previous)?;
+                }
+            }
+            if !range.is_single() {
+                f.write_str("-")?;
+                self.write_relative(f, &range.end, &range.start)?;
+            }
+            previous = Some(&range.end);
+        }
+        Ok(())
+    }
+}
+
+impl<'a, T> Named<'a, T> {
+    fn named<U>(&self, value: &'a U) -> Named<'a, U> {
+        Named {
+            value,
+            name: self.name,
+        }
+    }
+
+    /// Writes `reference`, leaving out the parts it shares with `previous`.
+    fn write_relative(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        reference: &'a Reference,
+        previous: &Reference,
+    ) -> fmt::Result {
+        if reference.book != previous.book {
+            write!(f, "{}", self.named(reference))
+        } else if reference.chapter == previous.chapter
+            && reference.verse.is_some()
+            && previous.verse.is_some()
+        {
+            write_numbers(f, None, reference.verse.as_ref())
+        } else {
+            write_numbers(f, reference.chapter, reference.verse.as_ref())
+        }
+    }
+}
+
+fn write_numbers(
+    f: &mut fmt::Formatter<'_>,
+    chapter: Option<u16>,
+    verse: Option<&VerseNumber>,
+) -> fmt::Result {
+    match (chapter, verse) {
+        (Some(chapter), Some(verse)) => write!(f, "{}:{}", chapter, verse),
+        (Some(chapter), None) => write!(f, "{}", chapter),
+        (None, Some(verse)) => write!(f, "{}", verse),
+        (None, None) => Ok(()),
+    }
+}
+
+impl fmt::Display for Reference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = |book: BookIdentifier| book.to_identifier();
+        write!(
+            f,
+            "{}",
+            Named {
+                value: self,
+                name: &name
+            }
+        )
+    }
+}
+
+impl fmt::Display for RefRange {
+    /// Writes the range in its shortest canonical form, such as `GEN 1:1-3` or `GEN 1:1-2:3`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = |book: BookIdentifier| book.to_identifier();
+        write!(
+            f,
+            "{}",
+            Named {
+                value: self,
+                name: &name
+            }
+        )
+    }
+}
+
+impl fmt::Display for RefList {
+    /// Writes verses of the same chapter as a list (`GEN 1:1,5`) and anything else as a group
+    /// (`GEN 1:1; 2:4; EXO 3:14`).
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = |book: BookIdentifier| book.to_identifier();
+        write!(
+            f,
+            "{}",
+            Named {
+                value: self,
+                name: &name
+            }
+        )
+    }
+}
@@ -0,0 +1,162 @@
+use std::collections::HashMap;
+
+use super::{Named, RefList, RefParser, Reference, to_book};
+use crate::metadata::to_text;
+use crate::parser::ParseError;
+use crate::usfm::{Book, BookContents, BookIdentifier, ElementType};
+
+/// The names a translation gives a book in its `\toc1`–`\toc3` markers.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct BookNames {
+    /// `\toc1`, such as "The Gospel According to John".
+    pub long: Option<String>,
+    /// `\toc2`, such as "John".
+    pub short: Option<String>,
+    /// `\toc3`, such as "Jn".
+    pub abbreviation: Option<String>,
+    /// `\toca1`–`\toca3`: the same names in an alternative language, each with the number of
+    /// its marker.
+    pub alternates: Vec<(u8, String)>,
+}
+
+/// Which of a book's names to write references with.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum NameStyle {
+    Long,
+    Short,
+    #[default]
+    Abbreviation,
+}
+
+/// Book names in a translation's own language, for reading and writing references such as
+/// "Gén 1:1" or "创 1:1".
+///
+/// Names are matched case-insensitively and without trailing full stops. Standard book codes
+/// such as `GEN` are always understood, and are written for books the table has no name for.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BookNameTable {
+    books: HashMap<BookIdentifier, BookNames>,
+    lookup: HashMap<String, BookIdentifier>,
+}
+
+impl BookNames {
+    /// Reads the names from a book's `\toc` and `\toca` markers.
+    pub fn from_book(book: &Book) -> Self {
+        Self::from_contents(&book.contents)
+    }
+
+    /// Reads the names from the `\toc` and `\toca` markers among `contents`.
+    pub(crate) fn from_contents<'a>(contents: impl IntoIterator<Item = &'a BookContents>) -> Self {
+        let mut names = Self::default();
+        for content in contents {
+            let BookContents::Element(element) = content else {
+                continue;
+            };
+            let name = || Some(to_text(&element.contents)).filter(|n| !n.is_empty());
+            match element.ty {
+                ElementType::Contents(1) => names.long = name(),
+                ElementType::Contents(2) => names.short = name(),
+                ElementType::Contents(3) => names.abbreviation = name(),
+                ElementType::AltContents(n @ 1..=3) => {
+                    names.alternates.extend(name().map(|name| (n, name)))
+                }
+                _ => {}
+            }
+        }
+        names
+    }
+
+    pub fn get(&self, style: NameStyle) -> Option<&str> {
+        match style {
+            NameStyle::Long => self.long.as_deref(),
+            NameStyle::Short => self.short.as_deref(),
+            NameStyle::Abbreviation => self.abbreviation.as_deref(),
+        }
+    }
+
+    /// Every name, abbreviation first.
+    pub fn iter(&self) -> impl Iterator<Item = &str> {
+        [&self.abbreviation, &self.short, &self.long]
+            .into_iter()
+            .flatten()
+            .map(String::as_str)
+            .chain(self.alternates.iter().map(|(_, name)| name.as_str()))
+    }
+}
+
+impl BookNameTable {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Builds a table from the `\id` and `\toc` markers of each book.
+    pub fn from_books<'a>(books: impl IntoIterator<Item = &'a Book>) -> Self {
+        let mut table = Self::new();
+        for book in books {
+            table.add_book(book);
+        }
+        table
+    }
+
+    /// Adds the names of a book, which is identified by its `\id`.
+    pub fn add_book(&mut self, book: &Book) {
+        if let Some(id) = book.code() {
+            self.insert(id, BookNames::from_book(book));
+        }
+    }
+
+    /// Sets the names of a book.
+    ///
+    /// A name already used by another book keeps referring to that book.
+    pub fn insert(&mut self, book: BookIdentifier, names: BookNames) {
+        for name in names.iter() {
+            self.lookup.entry(normalize(name)).or_insert(book);
+        }
+        self.books.insert(book, names);
+    }
+
+    pub fn names(&self, book: BookIdentifier) -> Option<&BookNames> {
+        self.books.get(&book)
+    }
+
+    /// Finds the book a name or standard book code stands for.
+    pub fn resolve(&self, name: &str) -> Option<BookIdentifier> {
+        self.lookup
+            .get(&normalize(name))
+            .copied()
+            .or_else(|| to_book(name))
+    }
+
+    /// Parses references written with this table's book names.
+    pub fn parse(&self, input: &str) -> Result<RefList, ParseError> {
+        self.parse_relative(input, &Reference::default())
+    }
+
+    /// Like [`RefList::parse_relative`], with this table's book names.
+    pub fn parse_relative(&self, input: &str, context: &Reference) -> Result<RefList, ParseError> {
+        let books = |name: &str| self.resolve(name);
+        RefParser::new(input, context, &books).parse()
+    }
+
+    /// Writes references with this table's book names, such as `Gén 1:1-3`.
+    pub fn format(&self, references: &RefList, style: NameStyle) -> String {
+        let name = |book: BookIdentifier| {
+            self.names(book)
+                .and_then(|names| names.get(style))
+                .unwrap_or(book.to_identifier())
+        };
+        Named {
+            value: references,
+            name: &name,
+        }
+        .to_string()
+    }
+}
+
+fn normalize(name: &str) -> String {
+    let name = name.trim().trim_end_matches('.');
+    name.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
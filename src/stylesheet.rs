@@ -0,0 +1,201 @@
+//! Paratext stylesheets (`usfm.sty`, `custom.sty`), which declare the markers a project uses.
+//!
+//! Only the properties that affect how markers are parsed are kept; formatting properties such
+//! as `\FontSize` are ignored.
+
+use std::{collections::HashMap, str::FromStr};
+
+use crate::parser::{ParseError, ParseErrorKind};
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Stylesheet {
+    styles: HashMap<String, Style>,
+}
+
+/// A single `\Marker` entry of a stylesheet.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Style {
+    /// The marker name, without its backslash.
+    pub marker: String,
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub style_type: Option<StyleType>,
+    pub text_type: Option<TextType>,
+    /// Markers this one may appear inside of.
+    pub occurs_under: Vec<String>,
+    /// The closing marker, without its backslash (e.g. `zfoo*`).
+    pub endmarker: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StyleType {
+    Paragraph,
+    Character,
+    Note,
+    Milestone,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TextType {
+    Title,
+    Section,
+    VerseText,
+    NoteText,
+    Other,
+    BackTranslation,
+    ChapterNumber,
+    VerseNumber,
+    Unspecified,
+}
+
+impl Stylesheet {
+    /// Parses the text of a `.sty` file.
+    pub fn parse(input: &str) -> Result<Self, ParseError> {
+        let mut stylesheet = Self::default();
+        let mut current: Option<Style> = None;
+        for line in input.lines() {
+            let Some(property) = line.trim().strip_prefix('\\') else {
+                continue;
+            };
+            let (key, value) = match property.split_once(char::is_whitespace) {
+                Some((key, value)) => (key, value.trim()),
+                None => (property, &property[property.len()..]),
+            };
+            let value_offset = value.as_ptr() as usize - input.as_ptr() as usize;
+            if key.eq_ignore_ascii_case("marker") {
+                stylesheet.extend(current.take());
+                current = Some(Style {
+                    marker: value.to_string(),
+                    ..Style::default()
+                });
+                continue;
+            }
+            // Properties before the first `\Marker` apply to no style.
+            let Some(style) = current.as_mut() else {
+                continue;
+            };
+            match key.to_ascii_lowercase().as_str() {
+                "name" => style.name = Some(value.to_string()),
+                "description" => style.description = Some(value.to_string()),
+                "styletype" => {
+                    style.style_type =
+                        Some(to_style_type(value).ok_or_else(|| {
+                            unrecognized("style type", value, input, value_offset)
+                        })?)
+                }
+                "texttype" => {
+                    style.text_type = Some(
+                        to_text_type(value)
+                            .ok_or_else(|| unrecognized("text type", value, input, value_offset))?,
+                    )
+                }
+                "occursunder" => {
+                    style.occurs_under = value.split_whitespace().map(str::to_string).collect()
+                }
+                "endmarker" => style.endmarker = Some(value.to_string()),
+                _ => {}
+            }
+        }
+        stylesheet.extend(current);
+        Ok(stylesheet)
+    }
+
+    pub fn get(&self, marker: &str) -> Option<&Style> {
+        self.styles.get(marker)
+    }
+
+    pub fn styles(&self) -> impl Iterator<Item = &Style> {
+        self.styles.values()
+    }
+
+    /// Whether `marker` is declared with the given style type.
+    ///
+    /// Milestones may be declared by their start or end marker (`zaln-s`, `zaln-e`) as well as
+    /// by their bare name.
+    pub fn declares(&self, marker: &str, style_type: StyleType) -> bool {
+        let is = |m: &str| self.get(m).and_then(|s| s.style_type) == Some(style_type);
+        is(marker)
+            || style_type == StyleType::Milestone
+                && (is(&format!("{}-s", marker)) || is(&format!("{}-e", marker)))
+    }
+
+    /// Adds styles, merging them into any existing styles for the same markers.
+    ///
+    /// This is how a project's `custom.sty` is layered over the standard `usfm.sty`: properties
+    /// it sets replace those already declared, and the rest are kept.
+    pub fn extend(&mut self, styles: impl IntoIterator<Item = Style>) {
+        for style in styles {
+            match self.styles.get_mut(&style.marker) {
+                Some(existing) => existing.merge(style),
+                None => {
+                    self.styles.insert(style.marker.clone(), style);
+                }
+            }
+        }
+    }
+}
+
+impl Style {
+    fn merge(&mut self, other: Style) {
+        self.name = other.name.or(self.name.take());
+        self.description = other.description.or(self.description.take());
+        self.style_type = other.style_type.or(self.style_type);
+        self.text_type = other.text_type.or(self.text_type);
+        if !other.occurs_under.is_empty() {
+            self.occurs_under = other.occurs_under;
+        }
+        self.endmarker = other.endmarker.or(self.endmarker.take());
+    }
+}
+
+impl FromStr for Stylesheet {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl IntoIterator for Stylesheet {
+    type Item = Style;
+    type IntoIter = std::collections::hash_map::IntoValues<String, Style>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.styles.into_values()
+    }
+}
+
+fn unrecognized(what: &'static str, value: &str, input: &str, offset: usize) -> ParseError {
+    let kind = ParseErrorKind::Unrecognized {
+        what,
+        value: value.to_string(),
+    };
+    ParseError::new(kind, input, offset)
+}
+
+fn to_style_type(s: &str) -> Option<StyleType> {
+    use StyleType::*;
+    Some(match s.to_ascii_lowercase().as_str() {
+        "paragraph" => Paragraph,
+        "character" => Character,
+        "note" => Note,
+        "milestone" => Milestone,
+        _ => return None,
+    })
+}
+
+fn to_text_type(s: &str) -> Option<TextType> {
+    use TextType::*;
+    Some(match s.to_ascii_lowercase().as_str() {
+        "title" => Title,
+        "section" => Section,
+        "versetext" => VerseText,
+        "notetext" => NoteText,
+        "other" => Other,
+        "backtranslation" => BackTranslation,
+        "chapternumber" => ChapterNumber,
+        "versenumber" => VerseNumber,
+        "unspecified" => Unspecified,
+        _ => return None,
+    })
+}
@@ -0,0 +1,155 @@
+//! The plain text of each verse of a [`Book`], for searching, speech and comparison.
+
+use crate::index::VerseEntry;
+use crate::usfm::{
+    Book, CharacterContents, CharacterType, CrossRef, CrossRefElementStyle, Footnote,
+    FootnoteElementStyle, ParagraphContents, VerseNumber,
+};
+
+/// What besides the verse text itself to include in [`Book::verses_with`].
+///
+/// By default only the text is included, without notes, figure captions or verse numbers.
+/// Alternate verse numbers (`\va`) are never included.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct TextOptions {
+    /// The text of footnotes, without their `\fr` references.
+    pub footnotes: bool,
+    /// The text of cross-references, without their `\xo` origins.
+    pub cross_refs: bool,
+    /// Published verse numbers (`\vp`).
+    pub published_verses: bool,
+    /// Figure captions.
+    pub figures: bool,
+}
+
+/// A verse and its text, with whitespace collapsed across paragraph and poetry breaks.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct VerseText {
+    pub chapter: u16,
+    pub verse: VerseNumber,
+    pub text: String,
+}
+
+/// An iterator over the verses of a book, returned by [`Book::verses`].
+#[derive(Debug, Clone)]
+pub struct Verses<'a> {
+    book: &'a Book,
+    entries: std::vec::IntoIter<VerseEntry>,
+    options: TextOptions,
+}
+
+impl Book {
+    /// The plain text of each verse, in order.
+    pub fn verses(&self) -> Verses<'_> {
+        self.verses_with(TextOptions::default())
+    }
+
+    pub fn verses_with(&self, options: TextOptions) -> Verses<'_> {
+        Verses {
+            book: self,
+            entries: self.index().verses().to_vec().into_iter(),
+            options,
+        }
+    }
+}
+
+impl Iterator for Verses<'_> {
+    type Item = VerseText;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let entry = self.entries.next()?;
+        let mut text = Text {
+            options: self.options,
+            buffer: String::new(),
+        };
+        for part in &entry.parts {
+            text.push_paragraph(part.contents(self.book));
+            text.buffer.push(' ');
+        }
+        Some(VerseText {
+            chapter: entry.chapter,
+            verse: entry.verse,
+            text: text.finish(),
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.entries.size_hint()
+    }
+}
+
+impl ExactSizeIterator for Verses<'_> {}
+
+struct Text {
+    options: TextOptions,
+    buffer: String,
+}
+
+impl Text {
+    fn push_paragraph(&mut self, contents: &[ParagraphContents]) {
+        for content in contents {
+            use ParagraphContents as C;
+            match content {
+                C::Line(line) => self.buffer.push_str(line),
+                C::Character(c) => self.push_character(&c.ty, &c.contents),
+                C::Footnote(f) => self.push_footnote(f),
+                C::CrossRef(x) => self.push_cross_ref(x),
+                C::Figure(fig) if self.options.figures => self.push_note([&fig.contents[..]]),
+                C::Custom(c) => self.push_contents(&c.contents),
+                _ => {}
+            }
+        }
+    }
+
+    fn push_contents(&mut self, contents: &[CharacterContents]) {
+        for content in contents {
+            use CharacterContents as C;
+            match content {
+                C::Line(line) => self.buffer.push_str(line),
+                C::Character(c) => self.push_character(&c.ty, &c.contents),
+                C::Footnote(f) => self.push_footnote(f),
+                C::CrossRef(x) => self.push_cross_ref(x),
+                C::Figure(fig) if self.options.figures => self.push_note([&fig.contents[..]]),
+                C::Custom(c) => self.push_contents(&c.contents),
+                _ => {}
+            }
+        }
+    }
+
+    fn push_character(&mut self, ty: &CharacterType, contents: &[CharacterContents]) {
+        match ty {
+            CharacterType::PublishedVerse if !self.options.published_verses => {}
+            CharacterType::AltVerse => {}
+            _ => self.push_contents(contents),
+        }
+    }
+
+    fn push_footnote(&mut self, footnote: &Footnote) {
+        if self.options.footnotes {
+            let elements = footnote.elements.iter();
+            let elements = elements.filter(|e| e.style != FootnoteElementStyle::Reference);
+            self.push_note(elements.map(|e| &e.contents[..]));
+        }
+    }
+
+    fn push_cross_ref(&mut self, cross_ref: &CrossRef) {
+        if self.options.cross_refs {
+            let elements = cross_ref.elements.iter();
+            let elements = elements.filter(|e| e.style != CrossRefElementStyle::OriginRef);
+            self.push_note(elements.map(|e| &e.contents[..]));
+        }
+    }
+
+    /// Pushes the text of a note or caption, set apart from the text around it.
+    fn push_note<'c>(&mut self, elements: impl IntoIterator<Item = &'c [CharacterContents]>) {
+        for contents in elements {
+            self.buffer.push(' ');
+            self.push_contents(contents);
+        }
+        self.buffer.push(' ');
+    }
+
+    fn finish(self) -> String {
+        self.buffer.split_whitespace().collect::<Vec<_>>().join(" ")
+    }
+}
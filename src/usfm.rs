@@ -1,15 +1,46 @@
+//! The owned syntax tree of a USFM book, which [`parse`](crate::parse) produces.
+//!
+//! With the `serde` feature, every type here implements serde's `Serialize` and `Deserialize`.
+//! Structs are objects of their fields. An enum with fields is an object with its variant in
+//! `type` and its fields, if any, in `value`, while one without is the name of its variant.
+//! Variants are written in snake case, and a [`BookIdentifier`] is its code. The `ty` of an
+//! [`Element`] or [`Character`] is written as `type`, and attributes, kept in order, are an
+//! array of `[key, value]` pairs, so in JSON:
+//!
+//! ```json
+//! {"contents": [
+//!   {"type": "id", "value": {"code": "GEN", "text": null}},
+//!   {"type": "chapter", "value": 1},
+//!   {"type": "paragraph", "value": {"style": {"type": "normal"}, "contents": [
+//!     {"type": "verse", "value": {"start": 1, "start_segment": null, "end": 1, "end_segment": null, "spelling": null}},
+//!     {"type": "line", "value": "In the "},
+//!     {"type": "character", "value": {"type": {"type": "word"}, "contents": [
+//!       {"type": "line", "value": "beginning"}
+//!     ], "attributes": [["strong", "H7225"]]}}
+//!   ]}}
+//! ]}
+//! ```
+
+pub mod borrowed;
 mod identifier;
+mod verse;
 
 use rkyv::{Archive, Deserialize, Serialize};
 
-#[derive(Debug, PartialEq, Eq, Hash, Archive, Serialize, Deserialize)]
+#[derive(Debug, PartialEq, Eq, Clone, Hash, Archive, Serialize, Deserialize)]
 #[rkyv(derive(Debug, PartialEq, Eq, Hash))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Book {
     pub contents: Vec<BookContents>,
 }
 
-#[derive(Debug, PartialEq, Eq, Hash, Archive, Serialize, Deserialize)]
+#[derive(Debug, PartialEq, Eq, Clone, Hash, Archive, Serialize, Deserialize)]
 #[rkyv(derive(Debug, PartialEq, Eq, Hash))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(tag = "type", content = "value", rename_all = "snake_case")
+)]
 pub enum BookContents {
     Id {
         code: BookIdentifier,
@@ -28,12 +59,18 @@ pub enum BookContents {
     Sidebar(Sidebar),
     Peripheral(String),
     Figure(Figure),
+    Custom(CustomParagraph),
 }
 
-#[derive(Debug, PartialEq, Eq, Hash, Archive, Serialize, Deserialize)]
+#[derive(Debug, PartialEq, Eq, Clone, Hash, Archive, Serialize, Deserialize)]
 #[rkyv(derive(Debug, PartialEq, Eq, Hash))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(tag = "type", content = "value", rename_all = "snake_case")
+)]
 pub enum ParagraphContents {
-    Verse(String),
+    Verse(VerseNumber),
     Line(String),
     Character(Character),
     Footnote(Footnote),
@@ -42,10 +79,33 @@ pub enum ParagraphContents {
     Milestone(Milestone),
     Category(String),
     OptionalBreak,
+    Custom(Custom),
+}
+
+/// The number of a `\v` marker: a single verse (`4`), a segment of one (`4a`) or a range
+/// (`1-3`, `4b-5a`).
+#[derive(Debug, PartialEq, Eq, Clone, Hash, Archive, Serialize, Deserialize)]
+#[rkyv(derive(Debug, PartialEq, Eq, Hash))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct VerseNumber {
+    pub start: u16,
+    pub start_segment: Option<char>,
+    /// The last verse of a range, or `start` for a single verse.
+    pub end: u16,
+    pub end_segment: Option<char>,
+    /// The number as written, when it differs from the canonical form, for example because it
+    /// contains right-to-left or other directional marks, or is not of that form at all, as in
+    /// `1-2-3`.
+    pub spelling: Option<String>,
 }
 
-#[derive(Debug, PartialEq, Eq, Hash, Archive, Serialize, Deserialize)]
+#[derive(Debug, PartialEq, Eq, Clone, Hash, Archive, Serialize, Deserialize)]
 #[rkyv(derive(Debug, PartialEq, Eq, Hash))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(tag = "type", content = "value", rename_all = "snake_case")
+)]
 pub enum ElementContents {
     Line(String),
     Character(Character),
@@ -55,10 +115,16 @@ pub enum ElementContents {
     Milestone(Milestone),
     Category(String),
     OptionalBreak,
+    Custom(Custom),
 }
 
-#[derive(Debug, PartialEq, Eq, Hash, Archive, Serialize, Deserialize)]
+#[derive(Debug, PartialEq, Eq, Clone, Hash, Archive, Serialize, Deserialize)]
 #[rkyv(derive(Debug, PartialEq, Eq, Hash))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(tag = "type", content = "value", rename_all = "snake_case")
+)]
 pub enum CharacterContents {
     Line(String),
     Character(Character),
@@ -67,31 +133,37 @@ pub enum CharacterContents {
     Figure(Figure),
     Milestone(Milestone),
     OptionalBreak,
+    Custom(Custom),
 }
 
-#[derive(Debug, PartialEq, Eq, Hash, Archive, Serialize, Deserialize)]
+#[derive(Debug, PartialEq, Eq, Clone, Hash, Archive, Serialize, Deserialize)]
 #[rkyv(derive(Debug, PartialEq, Eq, Hash))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Paragraph {
     pub style: ParagraphStyle,
     pub contents: Vec<ParagraphContents>,
 }
 
-#[derive(Debug, PartialEq, Eq, Hash, Archive, Serialize, Deserialize)]
+#[derive(Debug, PartialEq, Eq, Clone, Hash, Archive, Serialize, Deserialize)]
 #[rkyv(derive(Debug, PartialEq, Eq, Hash))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Poetry {
     pub style: PoetryStyle,
     pub contents: Vec<ParagraphContents>,
 }
 
-#[derive(Debug, PartialEq, Eq, Hash, Archive, Serialize, Deserialize)]
+#[derive(Debug, PartialEq, Eq, Clone, Hash, Archive, Serialize, Deserialize)]
 #[rkyv(derive(Debug, PartialEq, Eq, Hash))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Element {
+    #[cfg_attr(feature = "serde", serde(rename = "type"))]
     pub ty: ElementType,
     pub contents: Vec<ElementContents>,
 }
 
-#[derive(Debug, PartialEq, Eq, Hash, Archive, Serialize, Deserialize)]
+#[derive(Debug, PartialEq, Eq, Clone, Hash, Archive, Serialize, Deserialize)]
 #[rkyv(derive(Debug, PartialEq, Eq, Hash))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[rkyv(serialize_bounds(
     __S: rkyv::ser::Writer + rkyv::ser::Allocator,
     __S::Error: rkyv::rancor::Source,
@@ -104,30 +176,34 @@ pub struct Element {
     )
 ))]
 pub struct Character {
+    #[cfg_attr(feature = "serde", serde(rename = "type"))]
     pub ty: CharacterType,
     #[rkyv(omit_bounds)]
     pub contents: Vec<CharacterContents>,
     pub attributes: Vec<(String, String)>,
 }
 
-#[derive(Debug, PartialEq, Eq, Hash, Archive, Serialize, Deserialize)]
+#[derive(Debug, PartialEq, Eq, Clone, Hash, Archive, Serialize, Deserialize)]
 #[rkyv(derive(Debug, PartialEq, Eq, Hash))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Footnote {
     pub style: FootnoteStyle,
     pub caller: Caller,
     pub elements: Vec<FootnoteElement>,
 }
 
-#[derive(Debug, PartialEq, Eq, Hash, Archive, Serialize, Deserialize)]
+#[derive(Debug, PartialEq, Eq, Clone, Hash, Archive, Serialize, Deserialize)]
 #[rkyv(derive(Debug, PartialEq, Eq, Hash))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CrossRef {
     pub style: CrossRefStyle,
     pub caller: Caller,
     pub elements: Vec<CrossRefElement>,
 }
 
-#[derive(Debug, PartialEq, Eq, Hash, Archive, Serialize, Deserialize)]
+#[derive(Debug, PartialEq, Eq, Clone, Hash, Archive, Serialize, Deserialize)]
 #[rkyv(derive(Debug, PartialEq, Eq, Hash))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[rkyv(serialize_bounds(
     __S: rkyv::ser::Writer + rkyv::ser::Allocator,
     __S::Error: rkyv::rancor::Source,
@@ -145,8 +221,9 @@ pub struct FootnoteElement {
     pub contents: Vec<CharacterContents>,
 }
 
-#[derive(Debug, PartialEq, Eq, Hash, Archive, Serialize, Deserialize)]
+#[derive(Debug, PartialEq, Eq, Clone, Hash, Archive, Serialize, Deserialize)]
 #[rkyv(derive(Debug, PartialEq, Eq, Hash))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[rkyv(serialize_bounds(
     __S: rkyv::ser::Writer + rkyv::ser::Allocator,
     __S::Error: rkyv::rancor::Source,
@@ -164,14 +241,16 @@ pub struct CrossRefElement {
     pub contents: Vec<CharacterContents>,
 }
 
-#[derive(Debug, PartialEq, Eq, Hash, Archive, Serialize, Deserialize)]
+#[derive(Debug, PartialEq, Eq, Clone, Hash, Archive, Serialize, Deserialize)]
 #[rkyv(derive(Debug, PartialEq, Eq, Hash))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TableRow {
     pub cells: Vec<TableCell>,
 }
 
-#[derive(Debug, PartialEq, Eq, Hash, Archive, Serialize, Deserialize)]
+#[derive(Debug, PartialEq, Eq, Clone, Hash, Archive, Serialize, Deserialize)]
 #[rkyv(derive(Debug, PartialEq, Eq, Hash))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TableCell {
     pub prefix: CellPrefix,
     pub column: u8,
@@ -180,6 +259,8 @@ pub struct TableCell {
 
 #[derive(Debug, PartialEq, Eq, Clone, Hash, Archive, Serialize, Deserialize)]
 #[rkyv(derive(Debug, PartialEq, Eq, Clone, Hash))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum CellPrefix {
     Header,
     HeaderRight,
@@ -189,8 +270,9 @@ pub enum CellPrefix {
     ContentCenter,
 }
 
-#[derive(Debug, PartialEq, Eq, Hash, Archive, Serialize, Deserialize)]
+#[derive(Debug, PartialEq, Eq, Clone, Hash, Archive, Serialize, Deserialize)]
 #[rkyv(derive(Debug, PartialEq, Eq, Hash))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[rkyv(serialize_bounds(
     __S: rkyv::ser::Writer + rkyv::ser::Allocator,
     __S::Error: rkyv::rancor::Source,
@@ -208,39 +290,89 @@ pub struct Figure {
     pub attributes: Vec<(String, String)>,
 }
 
-#[derive(Debug, PartialEq, Eq, Hash, Archive, Serialize, Deserialize)]
+#[derive(Debug, PartialEq, Eq, Clone, Hash, Archive, Serialize, Deserialize)]
 #[rkyv(derive(Debug, PartialEq, Eq, Hash))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Milestone {
     pub style: MilestoneStyle,
     pub attributes: Vec<(String, String)>,
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Hash, Archive, Serialize, Deserialize)]
-#[rkyv(derive(Debug, PartialEq, Eq, Clone, Hash))]
+#[rkyv(derive(Debug, PartialEq, Eq, Hash))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(tag = "type", content = "value", rename_all = "snake_case")
+)]
 pub enum MilestoneStyle {
     QuotedText(u8, MilestoneBound),
     TextSection(MilestoneBound),
     Text(MilestoneBound),
     WordsOfJesus(MilestoneBound),
     VerseId,
+    Custom(String, MilestoneBound),
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Hash, Archive, Serialize, Deserialize)]
 #[rkyv(derive(Debug, PartialEq, Eq, Clone, Hash))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum MilestoneBound {
     Start,
     End,
     None,
 }
 
-#[derive(Debug, PartialEq, Eq, Hash, Archive, Serialize, Deserialize)]
+/// A line-initial marker the parser does not know, such as a Paratext `\z` paragraph.
+#[derive(Debug, PartialEq, Eq, Clone, Hash, Archive, Serialize, Deserialize)]
+#[rkyv(derive(Debug, PartialEq, Eq, Hash))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CustomParagraph {
+    pub marker: String,
+    pub contents: Vec<ParagraphContents>,
+}
+
+/// An inline marker the parser does not know, either a span (`\zfoo ...\zfoo*`) or a
+/// milestone (`\zaln-s ...\*`).
+#[derive(Debug, PartialEq, Eq, Clone, Hash, Archive, Serialize, Deserialize)]
+#[rkyv(derive(Debug, PartialEq, Eq, Hash))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[rkyv(serialize_bounds(
+    __S: rkyv::ser::Writer + rkyv::ser::Allocator,
+    __S::Error: rkyv::rancor::Source,
+))]
+#[rkyv(deserialize_bounds(__D::Error: rkyv::rancor::Source))]
+#[rkyv(bytecheck(
+    bounds(
+        __C: rkyv::validation::ArchiveContext,
+        __C::Error: rkyv::rancor::Source,
+    )
+))]
+pub struct Custom {
+    /// The marker name, without its backslash or `-s`/`-e` suffix.
+    pub marker: String,
+    /// The bound of a milestone, or `None` for a span.
+    pub milestone: Option<MilestoneBound>,
+    #[rkyv(omit_bounds)]
+    pub contents: Vec<CharacterContents>,
+    pub attributes: Vec<(String, String)>,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash, Archive, Serialize, Deserialize)]
 #[rkyv(derive(Debug, PartialEq, Eq, Hash))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Sidebar {
     pub contents: Vec<SidebarContents>,
 }
 
-#[derive(Debug, PartialEq, Eq, Hash, Archive, Serialize, Deserialize)]
+#[derive(Debug, PartialEq, Eq, Clone, Hash, Archive, Serialize, Deserialize)]
 #[rkyv(derive(Debug, PartialEq, Eq, Hash))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(tag = "type", content = "value", rename_all = "snake_case")
+)]
 pub enum SidebarContents {
     Paragraph(Paragraph),
     Poetry(Poetry),
@@ -252,6 +384,8 @@ pub enum SidebarContents {
 
 #[derive(Debug, PartialEq, Eq, Clone, Hash, Archive, Serialize, Deserialize)]
 #[rkyv(derive(Debug, PartialEq, Eq, Clone, Hash))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum FootnoteElementStyle {
     Reference,
     TranslationQuote,
@@ -267,6 +401,8 @@ pub enum FootnoteElementStyle {
 
 #[derive(Debug, PartialEq, Eq, Clone, Hash, Archive, Serialize, Deserialize)]
 #[rkyv(derive(Debug, PartialEq, Eq, Clone, Hash))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum CrossRefElementStyle {
     Keyword,
     Quote,
@@ -281,7 +417,12 @@ pub enum CrossRefElementStyle {
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Hash, Archive, Serialize, Deserialize)]
-#[rkyv(derive(Debug, PartialEq, Eq, Clone, Hash))]
+#[rkyv(derive(Debug, PartialEq, Eq, Hash))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(tag = "type", content = "value", rename_all = "snake_case")
+)]
 pub enum CharacterType {
     IntroOutline,
     IntroQuote,
@@ -336,26 +477,44 @@ pub enum CharacterType {
     TextAlternative,
     AltVerse,
     CrossRefTarget,
+    Custom(String),
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Hash, Archive, Serialize, Deserialize)]
-#[rkyv(derive(Debug, PartialEq, Eq, Clone, Hash))]
+#[rkyv(derive(Debug, PartialEq, Eq, Hash))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(tag = "type", content = "value", rename_all = "snake_case")
+)]
 pub enum FootnoteStyle {
     Footnote,
     Endnote,
     ExtendedFootnote,
     ExtendedEndnote,
+    Custom(String),
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Hash, Archive, Serialize, Deserialize)]
-#[rkyv(derive(Debug, PartialEq, Eq, Clone, Hash))]
+#[rkyv(derive(Debug, PartialEq, Eq, Hash))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(tag = "type", content = "value", rename_all = "snake_case")
+)]
 pub enum CrossRefStyle {
     CrossRef,
     ExtendedCrossRef,
+    Custom(String),
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Hash, Archive, Serialize, Deserialize)]
-#[rkyv(derive(Debug, PartialEq, Eq, Clone, Hash))]
+#[rkyv(derive(Debug, PartialEq, Eq, Hash))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(tag = "type", content = "value", rename_all = "snake_case")
+)]
 pub enum ParagraphStyle {
     Normal,
     Margin,
@@ -378,10 +537,16 @@ pub enum ParagraphStyle {
     Descriptive,
     ListEntry(u8),
     EmbeddedListEntry(u8),
+    Custom(String),
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Hash, Archive, Serialize, Deserialize)]
 #[rkyv(derive(Debug, PartialEq, Eq, Clone, Hash))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(tag = "type", content = "value", rename_all = "snake_case")
+)]
 pub enum PoetryStyle {
     Normal(u8),
     Right,
@@ -392,7 +557,12 @@ pub enum PoetryStyle {
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Hash, Archive, Serialize, Deserialize)]
-#[rkyv(derive(Debug, PartialEq, Eq, Clone, Hash))]
+#[rkyv(derive(Debug, PartialEq, Eq, Hash))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(tag = "type", content = "value", rename_all = "snake_case")
+)]
 pub enum ElementType {
     Remark,
     Header,
@@ -432,10 +602,24 @@ pub enum ElementType {
     Speaker,
     Division(u8),
     Restore,
+    Custom(String, ElementKind),
+}
+
+/// What a custom element declared in a stylesheet is, from its `\TextType`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, Archive, Serialize, Deserialize)]
+#[rkyv(derive(Debug, PartialEq, Eq, Clone, Hash))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum ElementKind {
+    Title,
+    Section,
+    Other,
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Hash, Archive, Serialize, Deserialize)]
 #[rkyv(derive(Debug, PartialEq, Eq, Clone, Hash))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum EmptyType {
     Blank,
     PageBreak,
@@ -444,13 +628,20 @@ pub enum EmptyType {
 
 #[derive(Debug, PartialEq, Eq, Clone, Hash, Archive, Serialize, Deserialize)]
 #[rkyv(derive(Debug, PartialEq, Eq, Clone, Hash))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(tag = "type", content = "value", rename_all = "snake_case")
+)]
 pub enum Caller {
     Auto,
     None,
     Some(char),
 }
 
-#[derive(Debug, PartialEq, Eq, Clone, Hash, Archive, Serialize, Deserialize)]
+#[derive(
+    Debug, PartialEq, Eq, Clone, Copy, PartialOrd, Ord, Hash, Archive, Serialize, Deserialize,
+)]
 #[rkyv(derive(Debug, PartialEq, Eq, Clone, Hash))]
 pub enum BookIdentifier {
     Genesis,
@@ -576,6 +767,8 @@ pub enum BookIdentifier {
 
 #[derive(Debug, PartialEq, Eq, Clone, Hash, Archive, Serialize, Deserialize)]
 #[rkyv(derive(Debug, PartialEq, Eq, Clone, Hash))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum BookEncoding {
     CP1252,
     CP1251,
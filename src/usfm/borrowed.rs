@@ -0,0 +1,622 @@
+//! A variant of the AST that borrows its text from the parsed input.
+//!
+//! Produced by [`parse_borrowed`](crate::parser::parse_borrowed); call [`Book::into_owned`] to
+//! get the owned [`Book`](super::Book) used for formatting and rkyv serialization.
+
+pub use super::{
+    BookEncoding, BookIdentifier, Caller, CellPrefix, CrossRefElementStyle, ElementKind, EmptyType,
+    FootnoteElementStyle, MilestoneBound, PoetryStyle,
+};
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub struct Book<'a> {
+    pub contents: Vec<BookContents<'a>>,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub enum BookContents<'a> {
+    Id {
+        code: BookIdentifier,
+        text: Option<&'a str>,
+    },
+    Usfm(&'a str),
+    Encoding(BookEncoding),
+    Status(u16),
+    Chapter(u16),
+    AltChapter(u16),
+    Paragraph(Paragraph<'a>),
+    Poetry(Poetry<'a>),
+    Element(Element<'a>),
+    Empty(EmptyType),
+    TableRow(TableRow<'a>),
+    Sidebar(Sidebar<'a>),
+    Peripheral(&'a str),
+    Figure(Figure<'a>),
+    Custom(CustomParagraph<'a>),
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub enum ParagraphContents<'a> {
+    Verse(VerseNumber<'a>),
+    Line(&'a str),
+    Character(Character<'a>),
+    Footnote(Footnote<'a>),
+    CrossRef(CrossRef<'a>),
+    Figure(Figure<'a>),
+    Milestone(Milestone<'a>),
+    Category(&'a str),
+    OptionalBreak,
+    Custom(Custom<'a>),
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub enum ElementContents<'a> {
+    Line(&'a str),
+    Character(Character<'a>),
+    Footnote(Footnote<'a>),
+    CrossRef(CrossRef<'a>),
+    Figure(Figure<'a>),
+    Milestone(Milestone<'a>),
+    Category(&'a str),
+    OptionalBreak,
+    Custom(Custom<'a>),
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub enum CharacterContents<'a> {
+    Line(&'a str),
+    Character(Character<'a>),
+    Footnote(Footnote<'a>),
+    CrossRef(CrossRef<'a>),
+    Figure(Figure<'a>),
+    Milestone(Milestone<'a>),
+    OptionalBreak,
+    Custom(Custom<'a>),
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub struct Paragraph<'a> {
+    pub style: ParagraphStyle<'a>,
+    pub contents: Vec<ParagraphContents<'a>>,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub struct Poetry<'a> {
+    pub style: PoetryStyle,
+    pub contents: Vec<ParagraphContents<'a>>,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub struct Element<'a> {
+    pub ty: ElementType<'a>,
+    pub contents: Vec<ElementContents<'a>>,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub struct Character<'a> {
+    pub ty: CharacterType<'a>,
+    pub contents: Vec<CharacterContents<'a>>,
+    pub attributes: Vec<(&'a str, &'a str)>,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub struct Footnote<'a> {
+    pub style: FootnoteStyle<'a>,
+    pub caller: Caller,
+    pub elements: Vec<FootnoteElement<'a>>,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub struct CrossRef<'a> {
+    pub style: CrossRefStyle<'a>,
+    pub caller: Caller,
+    pub elements: Vec<CrossRefElement<'a>>,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub struct FootnoteElement<'a> {
+    pub style: FootnoteElementStyle,
+    pub contents: Vec<CharacterContents<'a>>,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub struct CrossRefElement<'a> {
+    pub style: CrossRefElementStyle,
+    pub contents: Vec<CharacterContents<'a>>,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub struct TableRow<'a> {
+    pub cells: Vec<TableCell<'a>>,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub struct TableCell<'a> {
+    pub prefix: CellPrefix,
+    pub column: u8,
+    pub contents: Vec<ParagraphContents<'a>>,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub struct Figure<'a> {
+    pub contents: Vec<CharacterContents<'a>>,
+    pub attributes: Vec<(&'a str, &'a str)>,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub struct Milestone<'a> {
+    pub style: MilestoneStyle<'a>,
+    pub attributes: Vec<(&'a str, &'a str)>,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub struct CustomParagraph<'a> {
+    pub marker: &'a str,
+    pub contents: Vec<ParagraphContents<'a>>,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub struct Custom<'a> {
+    pub marker: &'a str,
+    pub milestone: Option<MilestoneBound>,
+    pub contents: Vec<CharacterContents<'a>>,
+    pub attributes: Vec<(&'a str, &'a str)>,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub struct Sidebar<'a> {
+    pub contents: Vec<SidebarContents<'a>>,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub enum SidebarContents<'a> {
+    Paragraph(Paragraph<'a>),
+    Poetry(Poetry<'a>),
+    Element(Element<'a>),
+    Empty(EmptyType),
+    TableRow(TableRow<'a>),
+    Category(&'a str),
+}
+
+/// Declares the borrowed copy of a style of the owned AST, whose `Custom` variant borrows the
+/// name of its marker, along with its conversion into the owned style.
+macro_rules! style {
+    (
+        $Style:ident {
+            $($Variant:ident $(($($field:ident: $Field:ty),+))?),*;
+            Custom(name $(, $custom:ident: $Custom:ty)*)
+        }
+    ) => {
+        #[derive(Debug, PartialEq, Eq, Clone, Hash)]
+        pub enum $Style<'a> {
+            $($Variant $(($($Field),+))?,)*
+            Custom(&'a str $(, $Custom)*),
+        }
+
+        impl From<$Style<'_>> for super::$Style {
+            fn from(value: $Style<'_>) -> Self {
+                match value {
+                    $($Style::$Variant $(($($field),+))? => Self::$Variant $(($($field),+))?,)*
+                    $Style::Custom(name $(, $custom)*) => {
+                        Self::Custom(name.to_string() $(, $custom)*)
+                    }
+                }
+            }
+        }
+    };
+}
+
+style!(ParagraphStyle {
+    Normal,
+    Margin,
+    Opening,
+    Right,
+    Closure,
+    EmbeddedOpening,
+    Embedded,
+    EmbeddedClosing,
+    EmbeddedRefrain,
+    Indented(n: u8),
+    MarginIndented,
+    MarginIndentedNum(n: u8),
+    Basic,
+    Centered,
+    HangingIndented(n: u8),
+    LiturgicalNote,
+    ListHeader,
+    ListFooter,
+    Descriptive,
+    ListEntry(n: u8),
+    EmbeddedListEntry(n: u8);
+    Custom(name)
+});
+
+style!(ElementType {
+    Remark,
+    Header,
+    Contents(n: u8),
+    AltContents(n: u8),
+    MajorIntro(n: u8),
+    SectionIntro(n: u8),
+    Intro,
+    IndentedIntro,
+    MarginIntro,
+    MarginIndentedIntro,
+    QuotedIntro,
+    MarginQuotedIntro,
+    RightIntro,
+    CenteredIntro,
+    LiturgicalIntro,
+    PoetryIntro(n: u8),
+    ListIntro(n: u8),
+    OutlineIntro,
+    EntryIntro(n: u8),
+    BridgeIntro,
+    MajorTitleEndingIntro(n: u8),
+    EndIntro,
+    ChapterLabel,
+    ChapterPublishedLabel,
+    ChapterDescription,
+    MajorTitle(n: u8),
+    MajorTitleEnding(n: u8),
+    MajorSection(n: u8),
+    MajorReference,
+    Section(n: u8),
+    Reference,
+    Parallel,
+    Speaker,
+    Division(n: u8),
+    Restore;
+    Custom(name, kind: ElementKind)
+});
+
+style!(CharacterType {
+    IntroOutline,
+    IntroQuote,
+    InlineQuote,
+    PublishedVerse,
+    Selah,
+    AcrosticLetter,
+    Addition,
+    BookQuote,
+    DeuteroAddition,
+    Keyword,
+    Deity,
+    Ordinal,
+    Proper,
+    Geographic,
+    ProperAddition,
+    QuotedText,
+    Signature,
+    SecondaryText,
+    Transliterated,
+    Jesus,
+    Emphasis,
+    Bold,
+    Italic,
+    BoldItalic,
+    Normal,
+    SmallCap,
+    Superscipt,
+    Index,
+    Ruby,
+    Pronunciation,
+    Word,
+    GreekWord,
+    HebrewWord,
+    AramaicWord,
+    ForeignWord,
+    Link,
+    ExtFootnoteRef,
+    FootnoteVerse,
+    FootnoteRef,
+    ListTotal,
+    ListKey,
+    ListValue(n: u8),
+    InlineSubheading,
+    ScriptureRef,
+    TextAlternative,
+    AltVerse,
+    CrossRefTarget;
+    Custom(name)
+});
+
+style!(MilestoneStyle {
+    QuotedText(n: u8, bound: MilestoneBound),
+    TextSection(bound: MilestoneBound),
+    Text(bound: MilestoneBound),
+    WordsOfJesus(bound: MilestoneBound),
+    VerseId;
+    Custom(name, bound: MilestoneBound)
+});
+
+style!(FootnoteStyle {
+    Footnote,
+    Endnote,
+    ExtendedFootnote,
+    ExtendedEndnote;
+    Custom(name)
+});
+
+style!(CrossRefStyle {
+    CrossRef,
+    ExtendedCrossRef;
+    Custom(name)
+});
+
+/// A [`VerseNumber`](super::VerseNumber) whose spelling borrows from the input.
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub struct VerseNumber<'a> {
+    pub start: u16,
+    pub start_segment: Option<char>,
+    pub end: u16,
+    pub end_segment: Option<char>,
+    pub spelling: Option<&'a str>,
+}
+
+impl Book<'_> {
+    pub fn into_owned(self) -> super::Book {
+        self.into()
+    }
+}
+
+fn owned<T: Into<U>, U>(items: Vec<T>) -> Vec<U> {
+    items.into_iter().map(Into::into).collect()
+}
+
+fn owned_attributes(attributes: Vec<(&str, &str)>) -> Vec<(String, String)> {
+    attributes
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect()
+}
+
+impl From<Book<'_>> for super::Book {
+    fn from(value: Book<'_>) -> Self {
+        Self {
+            contents: owned(value.contents),
+        }
+    }
+}
+
+impl From<BookContents<'_>> for super::BookContents {
+    fn from(value: BookContents<'_>) -> Self {
+        use BookContents as C;
+        match value {
+            C::Id { code, text } => Self::Id {
+                code,
+                text: text.map(str::to_string),
+            },
+            C::Usfm(version) => Self::Usfm(version.to_string()),
+            C::Encoding(encoding) => Self::Encoding(encoding),
+            C::Status(status) => Self::Status(status),
+            C::Chapter(chapter) => Self::Chapter(chapter),
+            C::AltChapter(chapter) => Self::AltChapter(chapter),
+            C::Paragraph(p) => Self::Paragraph(p.into()),
+            C::Poetry(p) => Self::Poetry(p.into()),
+            C::Element(e) => Self::Element(e.into()),
+            C::Empty(ty) => Self::Empty(ty),
+            C::TableRow(tr) => Self::TableRow(tr.into()),
+            C::Sidebar(sb) => Self::Sidebar(sb.into()),
+            C::Peripheral(text) => Self::Peripheral(text.to_string()),
+            C::Figure(fig) => Self::Figure(fig.into()),
+            C::Custom(p) => Self::Custom(p.into()),
+        }
+    }
+}
+
+impl From<ParagraphContents<'_>> for super::ParagraphContents {
+    fn from(value: ParagraphContents<'_>) -> Self {
+        use ParagraphContents as C;
+        match value {
+            C::Verse(verse) => Self::Verse(verse.into()),
+            C::Line(text) => Self::Line(text.to_string()),
+            C::Character(c) => Self::Character(c.into()),
+            C::Footnote(f) => Self::Footnote(f.into()),
+            C::CrossRef(x) => Self::CrossRef(x.into()),
+            C::Figure(fig) => Self::Figure(fig.into()),
+            C::Milestone(ms) => Self::Milestone(ms.into()),
+            C::Category(cat) => Self::Category(cat.to_string()),
+            C::OptionalBreak => Self::OptionalBreak,
+            C::Custom(c) => Self::Custom(c.into()),
+        }
+    }
+}
+
+impl From<ElementContents<'_>> for super::ElementContents {
+    fn from(value: ElementContents<'_>) -> Self {
+        use ElementContents as C;
+        match value {
+            C::Line(text) => Self::Line(text.to_string()),
+            C::Character(c) => Self::Character(c.into()),
+            C::Footnote(f) => Self::Footnote(f.into()),
+            C::CrossRef(x) => Self::CrossRef(x.into()),
+            C::Figure(fig) => Self::Figure(fig.into()),
+            C::Milestone(ms) => Self::Milestone(ms.into()),
+            C::Category(cat) => Self::Category(cat.to_string()),
+            C::OptionalBreak => Self::OptionalBreak,
+            C::Custom(c) => Self::Custom(c.into()),
+        }
+    }
+}
+
+impl From<CharacterContents<'_>> for super::CharacterContents {
+    fn from(value: CharacterContents<'_>) -> Self {
+        use CharacterContents as C;
+        match value {
+            C::Line(text) => Self::Line(text.to_string()),
+            C::Character(c) => Self::Character(c.into()),
+            C::Footnote(f) => Self::Footnote(f.into()),
+            C::CrossRef(x) => Self::CrossRef(x.into()),
+            C::Figure(fig) => Self::Figure(fig.into()),
+            C::Milestone(ms) => Self::Milestone(ms.into()),
+            C::OptionalBreak => Self::OptionalBreak,
+            C::Custom(c) => Self::Custom(c.into()),
+        }
+    }
+}
+
+impl From<Paragraph<'_>> for super::Paragraph {
+    fn from(value: Paragraph<'_>) -> Self {
+        Self {
+            style: value.style.into(),
+            contents: owned(value.contents),
+        }
+    }
+}
+
+impl From<Poetry<'_>> for super::Poetry {
+    fn from(value: Poetry<'_>) -> Self {
+        Self {
+            style: value.style,
+            contents: owned(value.contents),
+        }
+    }
+}
+
+impl From<Element<'_>> for super::Element {
+    fn from(value: Element<'_>) -> Self {
+        Self {
+            ty: value.ty.into(),
+            contents: owned(value.contents),
+        }
+    }
+}
+
+impl From<Character<'_>> for super::Character {
+    fn from(value: Character<'_>) -> Self {
+        Self {
+            ty: value.ty.into(),
+            contents: owned(value.contents),
+            attributes: owned_attributes(value.attributes),
+        }
+    }
+}
+
+impl From<Footnote<'_>> for super::Footnote {
+    fn from(value: Footnote<'_>) -> Self {
+        Self {
+            style: value.style.into(),
+            caller: value.caller,
+            elements: owned(value.elements),
+        }
+    }
+}
+
+impl From<CrossRef<'_>> for super::CrossRef {
+    fn from(value: CrossRef<'_>) -> Self {
+        Self {
+            style: value.style.into(),
+            caller: value.caller,
+            elements: owned(value.elements),
+        }
+    }
+}
+
+impl From<FootnoteElement<'_>> for super::FootnoteElement {
+    fn from(value: FootnoteElement<'_>) -> Self {
+        Self {
+            style: value.style,
+            contents: owned(value.contents),
+        }
+    }
+}
+
+impl From<CrossRefElement<'_>> for super::CrossRefElement {
+    fn from(value: CrossRefElement<'_>) -> Self {
+        Self {
+            style: value.style,
+            contents: owned(value.contents),
+        }
+    }
+}
+
+impl From<TableRow<'_>> for super::TableRow {
+    fn from(value: TableRow<'_>) -> Self {
+        Self {
+            cells: owned(value.cells),
+        }
+    }
+}
+
+impl From<TableCell<'_>> for super::TableCell {
+    fn from(value: TableCell<'_>) -> Self {
+        Self {
+            prefix: value.prefix,
+            column: value.column,
+            contents: owned(value.contents),
+        }
+    }
+}
+
+impl From<Figure<'_>> for super::Figure {
+    fn from(value: Figure<'_>) -> Self {
+        Self {
+            contents: owned(value.contents),
+            attributes: owned_attributes(value.attributes),
+        }
+    }
+}
+
+impl From<Milestone<'_>> for super::Milestone {
+    fn from(value: Milestone<'_>) -> Self {
+        Self {
+            style: value.style.into(),
+            attributes: owned_attributes(value.attributes),
+        }
+    }
+}
+
+impl From<CustomParagraph<'_>> for super::CustomParagraph {
+    fn from(value: CustomParagraph<'_>) -> Self {
+        Self {
+            marker: value.marker.to_string(),
+            contents: owned(value.contents),
+        }
+    }
+}
+
+impl From<Custom<'_>> for super::Custom {
+    fn from(value: Custom<'_>) -> Self {
+        Self {
+            marker: value.marker.to_string(),
+            milestone: value.milestone,
+            contents: owned(value.contents),
+            attributes: owned_attributes(value.attributes),
+        }
+    }
+}
+
+impl From<VerseNumber<'_>> for super::VerseNumber {
+    fn from(value: VerseNumber<'_>) -> Self {
+        Self {
+            start: value.start,
+            start_segment: value.start_segment,
+            end: value.end,
+            end_segment: value.end_segment,
+            spelling: value.spelling.map(str::to_string),
+        }
+    }
+}
+
+impl From<Sidebar<'_>> for super::Sidebar {
+    fn from(value: Sidebar<'_>) -> Self {
+        Self {
+            contents: owned(value.contents),
+        }
+    }
+}
+
+impl From<SidebarContents<'_>> for super::SidebarContents {
+    fn from(value: SidebarContents<'_>) -> Self {
+        use SidebarContents as C;
+        match value {
+            C::Paragraph(p) => Self::Paragraph(p.into()),
+            C::Poetry(p) => Self::Poetry(p.into()),
+            C::Element(e) => Self::Element(e.into()),
+            C::Empty(ty) => Self::Empty(ty),
+            C::TableRow(tr) => Self::TableRow(tr.into()),
+            C::Category(cat) => Self::Category(cat.to_string()),
+        }
+    }
+}
@@ -124,9 +124,144 @@ macro_rules! id {
                     ExtraG => "XXG",
                 }
             }
+
+            /// The OSIS abbreviation of the book, such as `Gen` or `1Sam`. Books that OSIS does not
+            /// name, like the front matter, have the names that `usfm2osis` gives them.
+            pub fn to_osis(&self) -> &'static str {
+                use $ty::*;
+                match self {
+                    Genesis => "Gen",
+                    Exodus => "Exod",
+                    Leviticus => "Lev",
+                    Numbers => "Num",
+                    Deuteronomy => "Deut",
+                    Joshua => "Josh",
+                    Judges => "Judg",
+                    Ruth => "Ruth",
+                    OneSamuel => "1Sam",
+                    TwoSamuel => "2Sam",
+                    OneKings => "1Kgs",
+                    TwoKings => "2Kgs",
+                    OneChronicles => "1Chr",
+                    TwoChronicles => "2Chr",
+                    Ezra => "Ezra",
+                    Nehemiah => "Neh",
+                    Esther => "Esth",
+                    Job => "Job",
+                    Psalms => "Ps",
+                    Proverbs => "Prov",
+                    Ecclesiastes => "Eccl",
+                    SongOfSongs => "Song",
+                    Isaiah => "Isa",
+                    Jeremiah => "Jer",
+                    Lamentations => "Lam",
+                    Ezekiel => "Ezek",
+                    Daniel => "Dan",
+                    Hosea => "Hos",
+                    Joel => "Joel",
+                    Amos => "Amos",
+                    Obadiah => "Obad",
+                    Jonah => "Jonah",
+                    Micah => "Mic",
+                    Nahum => "Nah",
+                    Habakkuk => "Hab",
+                    Zephaniah => "Zeph",
+                    Haggai => "Hag",
+                    Zechariah => "Zech",
+                    Malachi => "Mal",
+                    Matthew => "Matt",
+                    Mark => "Mark",
+                    Luke => "Luke",
+                    John => "John",
+                    Acts => "Acts",
+                    Romans => "Rom",
+                    OneCorinthians => "1Cor",
+                    TwoCorinthians => "2Cor",
+                    Galatians => "Gal",
+                    Ephesians => "Eph",
+                    Philippians => "Phil",
+                    Colossians => "Col",
+                    OneThessalonians => "1Thess",
+                    TwoThessalonians => "2Thess",
+                    OneTimothy => "1Tim",
+                    TwoTimothy => "2Tim",
+                    Titus => "Titus",
+                    Philemon => "Phlm",
+                    Hebrews => "Heb",
+                    James => "Jas",
+                    OnePeter => "1Pet",
+                    TwoPeter => "2Pet",
+                    OneJohn => "1John",
+                    TwoJohn => "2John",
+                    ThreeJohn => "3John",
+                    Jude => "Jude",
+                    Revelation => "Rev",
+                    Tobit => "Tob",
+                    Judith => "Jdt",
+                    EstherGreek => "EsthGr",
+                    WisdomOfSolomon => "Wis",
+                    Sirach => "Sir",
+                    Baruch => "Bar",
+                    LetterOfJeremiah => "EpJer",
+                    SongOfThreeYoungMen => "PrAzar",
+                    Susanna => "Sus",
+                    BelAndTheDragon => "Bel",
+                    OneMaccabees => "1Macc",
+                    TwoMaccabees => "2Macc",
+                    ThreeMaccabees => "3Macc",
+                    FourMaccabees => "4Macc",
+                    OneEsdras => "1Esd",
+                    TwoEsdras => "2Esd",
+                    PrayerOfManasseh => "PrMan",
+                    Psalm151 => "AddPs",
+                    Odes => "Odes",
+                    PsalmsOfSolomon => "PssSol",
+                    EzraApocalypse => "4Ezra",
+                    FiveEzra => "5Ezra",
+                    SixEzra => "6Ezra",
+                    DanielGreek => "DanGr",
+                    Psalms152To155 => "5ApocSyrPss",
+                    TwoBaruch => "2Bar",
+                    LetterOfBaruch => "EpBar",
+                    Jubilees => "Jub",
+                    Enoch => "1En",
+                    OneMeqabyan => "1Meq",
+                    TwoMeqabyan => "2Meq",
+                    ThreeMeqabyan => "3Meq",
+                    Reproof => "Reproof",
+                    FourBaruch => "4Bar",
+                    LetterToLaodiceans => "EpLao",
+                    FrontMatter => "FRONT",
+                    BackMatter => "BACK",
+                    OtherMatter => "X-OTHER",
+                    IntroductionMatter => "INTRODUCTION",
+                    Concordance => "CONCORDANCE",
+                    Glossary => "GLOSSARY",
+                    TopicalIndex => "INDEX",
+                    NamesIndex => "GAZETTEER",
+                    ExtraA | ExtraB | ExtraC | ExtraD | ExtraE | ExtraF | ExtraG => "X-OTHER",
+                }
+            }
         }
     };
 }
 
 id!(BookIdentifier);
 id!(ArchivedBookIdentifier);
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for BookIdentifier {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.to_identifier())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for BookIdentifier {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let code = String::deserialize(deserializer)?;
+        crate::parser::to_book_identifier(&code).ok_or_else(|| {
+            serde::de::Error::custom(format!("unrecognized book identifier `{}`", code))
+        })
+    }
+}
@@ -0,0 +1,218 @@
+use std::{borrow::Cow, cmp::Ordering, fmt, str::FromStr};
+
+use super::{VerseNumber, borrowed};
+use crate::parser::{ParseError, ParseErrorKind};
+
+/// Directional and zero-width marks that may surround or split a verse number in
+/// right-to-left texts, and which are not part of the number itself.
+fn is_directional_mark(c: char) -> bool {
+    matches!(
+        c,
+        '\u{200B}'..='\u{200F}' | '\u{202A}'..='\u{202E}' | '\u{2066}'..='\u{2069}' | '\u{FEFF}' | '\u{061C}'
+    )
+}
+
+impl VerseNumber {
+    pub fn new(verse: u16) -> Self {
+        Self::range(verse, verse)
+    }
+
+    pub fn range(start: u16, end: u16) -> Self {
+        Self {
+            start,
+            start_segment: None,
+            end,
+            end_segment: None,
+            spelling: None,
+        }
+    }
+
+    /// The number as it was written in the source.
+    pub fn spelling(&self) -> Cow<'_, str> {
+        match &self.spelling {
+            Some(spelling) => Cow::Borrowed(spelling),
+            None => Cow::Owned(self.to_string()),
+        }
+    }
+
+    pub fn is_range(&self) -> bool {
+        self.start != self.end || self.start_segment != self.end_segment
+    }
+
+    /// Whether `verse` is one of the verses this number covers, in whole or in part.
+    pub fn contains(&self, verse: u16) -> bool {
+        (self.start..=self.end).contains(&verse)
+    }
+
+    /// Whether every part of `other` is covered by this number.
+    ///
+    /// A verse without a segment covers all of its segments, so `4-5` covers `4b`, but `4a`
+    /// does not cover `4`.
+    pub fn contains_range(&self, other: &VerseNumber) -> bool {
+        let starts_after = match other.start.cmp(&self.start) {
+            Ordering::Greater => true,
+            Ordering::Less => false,
+            Ordering::Equal => match (self.start_segment, other.start_segment) {
+                (None, _) => true,
+                (Some(_), None) => false,
+                (Some(a), Some(b)) => a <= b,
+            },
+        };
+        let ends_before = match other.end.cmp(&self.end) {
+            Ordering::Less => true,
+            Ordering::Greater => false,
+            Ordering::Equal => match (self.end_segment, other.end_segment) {
+                (None, _) => true,
+                (Some(_), None) => false,
+                (Some(a), Some(b)) => b <= a,
+            },
+        };
+        starts_after && ends_before
+    }
+}
+
+impl From<u16> for VerseNumber {
+    fn from(value: u16) -> Self {
+        Self::new(value)
+    }
+}
+
+impl From<u16> for borrowed::VerseNumber<'_> {
+    fn from(value: u16) -> Self {
+        Self {
+            start: value,
+            start_segment: None,
+            end: value,
+            end_segment: None,
+            spelling: None,
+        }
+    }
+}
+
+impl FromStr for VerseNumber {
+    type Err = ParseError;
+
+    /// Parses a verse number such as `1`, `4a`, `1-3` or `4b-5a`, ignoring directional marks.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        borrowed::VerseNumber::try_from(s).map(Into::into)
+    }
+}
+
+impl TryFrom<&str> for VerseNumber {
+    type Error = ParseError;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl<'a> TryFrom<&'a str> for borrowed::VerseNumber<'a> {
+    type Error = ParseError;
+
+    /// Parses a verse number as [`VerseNumber::from_str`] does, borrowing its spelling from `s`.
+    fn try_from(s: &'a str) -> Result<Self, Self::Error> {
+        let unrecognized = || {
+            let kind = ParseErrorKind::Unrecognized {
+                what: "verse number",
+                value: s.to_string(),
+            };
+            ParseError::new(kind, s, 0)
+        };
+        let cleaned: String = s.chars().filter(|&c| !is_directional_mark(c)).collect();
+        let (start, end) = match cleaned.split_once('-') {
+            Some((start, end)) => (start, Some(end)),
+            None => (cleaned.as_str(), None),
+        };
+        let (start, start_segment) = parse_part(start).ok_or_else(unrecognized)?;
+        let (end, end_segment) = match end {
+            Some(end) => parse_part(end).ok_or_else(unrecognized)?,
+            None => (start, start_segment),
+        };
+        if (end, end_segment) < (start, start_segment) {
+            return Err(unrecognized());
+        }
+        let canonical = VerseNumber {
+            start,
+            start_segment,
+            end,
+            end_segment,
+            spelling: None,
+        };
+        Ok(Self {
+            start,
+            start_segment,
+            end,
+            end_segment,
+            spelling: (canonical.to_string() != s).then_some(s),
+        })
+    }
+}
+
+impl<'a> borrowed::VerseNumber<'a> {
+    /// A number the grammar accepts but that is not of the form [`VerseNumber::from_str`]
+    /// reads, such as `1-2-3` or `1ab`. It is kept as written, and covers the verses from the
+    /// first number in it to the last, or verse 0 if it has none that fit.
+    pub(crate) fn from_spelling(s: &'a str) -> Self {
+        let mut numbers = s
+            .split(|c: char| !c.is_ascii_digit())
+            .filter_map(|n| n.parse::<u16>().ok());
+        let start = numbers.next().unwrap_or(0);
+        let end = numbers.next_back().unwrap_or(start).max(start);
+        Self {
+            start,
+            start_segment: None,
+            end,
+            end_segment: None,
+            spelling: Some(s),
+        }
+    }
+}
+
+/// Parses a verse and an optional single-letter segment, as in `4` or `4a`.
+fn parse_part(s: &str) -> Option<(u16, Option<char>)> {
+    let digits = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    let verse = s[..digits].parse().ok()?;
+    let mut rest = s[digits..].chars();
+    match (rest.next(), rest.next()) {
+        (None, _) => Some((verse, None)),
+        (Some(c), None) if c.is_ascii_alphabetic() => Some((verse, Some(c))),
+        _ => None,
+    }
+}
+
+impl fmt::Display for VerseNumber {
+    /// Writes the canonical form of the number, without any directional marks.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.start)?;
+        if let Some(segment) = self.start_segment {
+            write!(f, "{}", segment)?;
+        }
+        if self.is_range() {
+            write!(f, "-{}", self.end)?;
+            if let Some(segment) = self.end_segment {
+                write!(f, "{}", segment)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl PartialOrd for VerseNumber {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for VerseNumber {
+    /// Orders by where the numbers start, then by where they end; the spelling only breaks ties.
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.start, self.start_segment, self.end, self.end_segment)
+            .cmp(&(
+                other.start,
+                other.start_segment,
+                other.end,
+                other.end_segment,
+            ))
+            .then_with(|| self.spelling.cmp(&other.spelling))
+    }
+}
@@ -0,0 +1,725 @@
+//! Conversion between a [`Book`] and USJ 3.1, the JSON form of USFM.
+//!
+//! USJ has the nodes and styles of [USX](crate::usx), with an object for each element and a
+//! string for each run of text. Chapters and verses are marked where they start, by a node with
+//! a `sid`, a `\cat` that opens a sidebar is its `category` and `\ref` is a `ref` node. The types
+//! here implement serde's traits, so a document can be read and written in JSON or any other
+//! serde format.
+//!
+//! ```
+//! use usfm::usj::{Usj, from_usj, to_usj};
+//!
+//! let book = usfm::parse("\\id GEN\n\\c 1\n\\p\n\\v 1 In the beginning\n").unwrap();
+//! let json = serde_json::to_string(&to_usj(&book)).unwrap();
+//! assert!(json.starts_with(r#"{"type":"USJ","version":"3.1","content":[{"type":"book""#));
+//!
+//! let usj: Usj = serde_json::from_str(&json).unwrap();
+//! assert_eq!(from_usj(&usj).unwrap(), book);
+//! ```
+
+use std::error::Error;
+use std::fmt;
+
+use serde::de::{self, MapAccess, Visitor};
+use serde::ser::SerializeMap;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::edit::to_character_contents;
+use crate::format::{
+    cross_ref_element_style_to_str, cross_ref_style_to_str, element_type_to_str, empty_type_to_str,
+    encoding_to_str, footnote_element_style_to_str, footnote_style_to_str, poetry_style_to_str,
+};
+use crate::parser::{ParseErrorKind, describe, to_book_encoding, to_book_identifier};
+use crate::reference::Reference;
+use crate::usfm::*;
+use crate::usx::{
+    Inline, NoteChild, caller_to_str, cell_align, cell_style, character_style, figure_attributes,
+    milestone_style, ms_style, para_style, ref_loc, to_cell_style, to_char, to_figure,
+    to_milestone, to_note, to_para, to_ref, to_sidebar_contents, to_verse,
+};
+
+/// A USJ document.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Usj {
+    /// The kind of document, which is always `USJ`.
+    #[serde(rename = "type")]
+    pub ty: String,
+    pub version: String,
+    pub content: Vec<UsjContent>,
+}
+
+/// An item of the content of a USJ document or node.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum UsjContent {
+    Text(String),
+    Node(UsjNode),
+}
+
+/// A USJ node, such as a paragraph, character style, note or milestone.
+///
+/// It is written as an object with its `type` and `marker` first, then its attributes, then its
+/// `content`.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct UsjNode {
+    /// The kind of node: `book`, `chapter`, `verse`, `para`, `char`, `note`, `ms`, `figure`,
+    /// `ref`, `sidebar`, `table`, `table:row`, `table:cell` or `optbreak`.
+    pub ty: String,
+    /// The marker without its backslash, such as `p`, `nd` or `qt-s`.
+    pub marker: Option<String>,
+    /// The other properties of the node, such as `number`, `sid` or the attributes of a `\w`, in
+    /// the order they are written.
+    pub attributes: Vec<(String, String)>,
+    /// The contents of a node that holds any.
+    pub content: Option<Vec<UsjContent>>,
+}
+
+impl UsjNode {
+    pub fn new(ty: impl Into<String>, marker: Option<&str>) -> Self {
+        Self {
+            ty: ty.into(),
+            marker: marker.map(str::to_string),
+            ..Default::default()
+        }
+    }
+
+    /// The value of the attribute `key`.
+    pub fn attribute(&self, key: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+
+    fn with_attribute(mut self, key: &str, value: impl Into<String>) -> Self {
+        self.attributes.push((key.to_string(), value.into()));
+        self
+    }
+
+    fn with_content(mut self, content: Vec<UsjContent>) -> Self {
+        self.content = Some(content);
+        self
+    }
+}
+
+impl Serialize for UsjNode {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry("type", &self.ty)?;
+        if let Some(marker) = &self.marker {
+            map.serialize_entry("marker", marker)?;
+        }
+        for (key, value) in &self.attributes {
+            map.serialize_entry(key, value)?;
+        }
+        if let Some(content) = &self.content {
+            map.serialize_entry("content", content)?;
+        }
+        map.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for UsjNode {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_map(UsjNodeVisitor)
+    }
+}
+
+struct UsjNodeVisitor;
+
+impl<'de> Visitor<'de> for UsjNodeVisitor {
+    type Value = UsjNode;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a USJ node")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<UsjNode, A::Error> {
+        let mut ty = None;
+        let mut node = UsjNode::default();
+        while let Some(key) = map.next_key::<String>()? {
+            match key.as_str() {
+                "type" => ty = Some(map.next_value()?),
+                "marker" => node.marker = Some(map.next_value()?),
+                "content" => node.content = Some(map.next_value()?),
+                _ => {
+                    let value = map.next_value()?;
+                    node.attributes.push((key, value));
+                }
+            }
+        }
+        node.ty = ty.ok_or_else(|| de::Error::missing_field("type"))?;
+        Ok(node)
+    }
+}
+
+/// Converts `book` to a USJ document.
+pub fn to_usj(book: &Book) -> Usj {
+    let mut writer = Writer::default();
+    let mut content = Vec::new();
+    for (i, contents) in book.contents.iter().enumerate() {
+        writer.book_contents(contents, book.contents.get(i + 1), &mut content);
+    }
+    Usj {
+        ty: "USJ".to_string(),
+        version: "3.1".to_string(),
+        content,
+    }
+}
+
+/// An error produced while reading a USJ document, carrying the JSON Pointer of the value it is
+/// in rather than a line and column.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct UsjError {
+    /// What went wrong, which is always [`Unrecognized`](ParseErrorKind::Unrecognized) for a
+    /// value that has no place in a book.
+    pub kind: ParseErrorKind,
+    /// The JSON Pointer of the value, such as `/content/1/code`.
+    pub path: String,
+}
+
+impl UsjError {
+    /// A short description of the error, without its path.
+    pub fn message(&self) -> String {
+        describe(&self.kind, &[])
+    }
+}
+
+impl fmt::Display for UsjError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.path, self.message())
+    }
+}
+
+impl Error for UsjError {}
+
+/// Reads a USJ document into a [`Book`].
+///
+/// Nodes that have no USFM counterpart are replaced by their contents. An error is located by
+/// the JSON Pointer of the value that has no place in a book.
+pub fn from_usj(usj: &Usj) -> Result<Book, UsjError> {
+    if usj.ty != "USJ" {
+        return Err(unrecognized(
+            "document type",
+            &usj.ty,
+            Pointer::DOCUMENT,
+            "type",
+        ));
+    }
+    let mut contents = Vec::new();
+    for (i, node) in nodes(&usj.content) {
+        block(node, Pointer::DOCUMENT.child(i), &mut contents)?;
+    }
+    Ok(Book { contents })
+}
+
+#[derive(Default)]
+struct Writer {
+    book: Option<BookIdentifier>,
+    chapter: Option<u16>,
+}
+
+impl Writer {
+    fn book_contents(
+        &mut self,
+        content: &BookContents,
+        next: Option<&BookContents>,
+        out: &mut Vec<UsjContent>,
+    ) {
+        use BookContents as C;
+        let node = match content {
+            C::Id { code, text } => {
+                self.book = Some(*code);
+                UsjNode::new("book", Some("id"))
+                    .with_attribute("code", code.to_identifier())
+                    .with_content(text.iter().cloned().map(UsjContent::Text).collect())
+            }
+            C::Usfm(_) | C::AltChapter(_) => return,
+            C::Encoding(enc) => text_para("ide", encoding_to_str(enc)),
+            C::Status(status) => text_para("sts", &status.to_string()),
+            C::Chapter(chapter) => {
+                self.chapter = Some(*chapter);
+                let mut node = UsjNode::new("chapter", Some("c"))
+                    .with_attribute("number", chapter.to_string());
+                if let Some(C::AltChapter(alt)) = next {
+                    node = node.with_attribute("altnumber", alt.to_string());
+                }
+                node.with_attribute("sid", self.reference(None))
+            }
+            C::Paragraph(p) => self.para(&para_style(&p.style), &p.contents),
+            C::Poetry(p) => self.para(&poetry_style_to_str(&p.style), &p.contents),
+            C::Custom(p) => self.para(&p.marker, &p.contents),
+            C::Element(e) => self.element(e),
+            C::Empty(ty) => UsjNode::new("para", Some(empty_type_to_str(ty))),
+            C::TableRow(tr) => return self.table_row(tr, out),
+            C::Sidebar(sb) => self.sidebar(sb),
+            C::Peripheral(text) => text_para("periph", text),
+            C::Figure(fig) => self.figure(fig),
+        };
+        out.push(UsjContent::Node(node));
+    }
+
+    fn para(&self, style: &str, contents: &[ParagraphContents]) -> UsjNode {
+        UsjNode::new("para", Some(style)).with_content(self.inline(contents))
+    }
+
+    fn element(&self, e: &Element) -> UsjNode {
+        UsjNode::new("para", Some(&element_type_to_str(&e.ty)))
+            .with_content(self.inline(&e.contents))
+    }
+
+    /// Adds a row to the table that ends `out`, or to a new one.
+    fn table_row(&self, tr: &TableRow, out: &mut Vec<UsjContent>) {
+        let cells = tr
+            .cells
+            .iter()
+            .map(|cell| {
+                UsjContent::Node(
+                    UsjNode::new("table:cell", Some(&cell_style(cell)))
+                        .with_attribute("align", cell_align(&cell.prefix))
+                        .with_content(self.inline(&cell.contents)),
+                )
+            })
+            .collect();
+        let row = UsjContent::Node(UsjNode::new("table:row", Some("tr")).with_content(cells));
+        match out.last_mut() {
+            Some(UsjContent::Node(UsjNode {
+                ty,
+                content: Some(rows),
+                ..
+            })) if ty == "table" => rows.push(row),
+            _ => out.push(UsjContent::Node(
+                UsjNode::new("table", None).with_content(vec![row]),
+            )),
+        }
+    }
+
+    fn sidebar(&self, sb: &Sidebar) -> UsjNode {
+        use SidebarContents as C;
+        let mut node = UsjNode::new("sidebar", Some("esb"));
+        let mut contents = sb.contents.as_slice();
+        if let [C::Category(cat), rest @ ..] = contents {
+            node = node.with_attribute("category", cat.as_str());
+            contents = rest;
+        }
+        let mut content = Vec::new();
+        for sidebar_contents in contents {
+            let node = match sidebar_contents {
+                C::Paragraph(p) => self.para(&para_style(&p.style), &p.contents),
+                C::Poetry(p) => self.para(&poetry_style_to_str(&p.style), &p.contents),
+                C::Element(e) => self.element(e),
+                C::Empty(ty) => UsjNode::new("para", Some(empty_type_to_str(ty))),
+                C::TableRow(tr) => {
+                    self.table_row(tr, &mut content);
+                    continue;
+                }
+                C::Category(cat) => category(cat),
+            };
+            content.push(UsjContent::Node(node));
+        }
+        node.with_content(content)
+    }
+
+    fn inline<'a, T>(&self, contents: &'a [T]) -> Vec<UsjContent>
+    where
+        &'a T: Into<Inline<'a>>,
+    {
+        contents
+            .iter()
+            .map(|content| match content.into() {
+                Inline::Line(text) => UsjContent::Text(text.to_string()),
+                content => UsjContent::Node(self.inline_node(content)),
+            })
+            .collect()
+    }
+
+    fn inline_node(&self, content: Inline) -> UsjNode {
+        match content {
+            Inline::Verse(verse) => UsjNode::new("verse", Some("v"))
+                .with_attribute("number", verse.to_string())
+                .with_attribute("sid", self.reference(Some(verse))),
+            Inline::Line(text) => unreachable!("text `{}` is not a node", text),
+            Inline::Character(c) => self.character(c),
+            Inline::Footnote(f) => self.footnote(f),
+            Inline::CrossRef(x) => self.cross_ref(x),
+            Inline::Figure(fig) => self.figure(fig),
+            Inline::Milestone(ms) => ms_node(&milestone_style(ms), &ms.attributes),
+            Inline::Category(cat) => category(cat),
+            Inline::OptionalBreak => UsjNode::new("optbreak", None),
+            Inline::Custom(c) => self.custom(c),
+        }
+    }
+
+    fn character(&self, c: &Character) -> UsjNode {
+        if c.ty == CharacterType::ScriptureRef {
+            return UsjNode::new("ref", None)
+                .with_attribute("loc", ref_loc(c))
+                .with_content(self.inline(&c.contents));
+        }
+        self.char(&character_style(c), &c.attributes, &c.contents)
+    }
+
+    fn char(
+        &self,
+        style: &str,
+        attributes: &[(String, String)],
+        contents: &[CharacterContents],
+    ) -> UsjNode {
+        UsjNode {
+            attributes: attributes.to_vec(),
+            ..UsjNode::new("char", Some(style))
+        }
+        .with_content(self.inline(contents))
+    }
+
+    fn footnote(&self, f: &Footnote) -> UsjNode {
+        let content = f
+            .elements
+            .iter()
+            .map(|element| {
+                let style = footnote_element_style_to_str(&element.style);
+                UsjContent::Node(self.char(style, &[], &element.contents))
+            })
+            .collect();
+        note(footnote_style_to_str(&f.style), &f.caller).with_content(content)
+    }
+
+    fn cross_ref(&self, x: &CrossRef) -> UsjNode {
+        let content = x
+            .elements
+            .iter()
+            .map(|element| {
+                let style = cross_ref_element_style_to_str(&element.style);
+                UsjContent::Node(self.char(style, &[], &element.contents))
+            })
+            .collect();
+        note(cross_ref_style_to_str(&x.style), &x.caller).with_content(content)
+    }
+
+    fn figure(&self, fig: &Figure) -> UsjNode {
+        UsjNode {
+            attributes: figure_attributes(&fig.attributes),
+            ..UsjNode::new("figure", Some("fig"))
+        }
+        .with_content(self.inline(&fig.contents))
+    }
+
+    fn custom(&self, c: &Custom) -> UsjNode {
+        match &c.milestone {
+            Some(bound) => ms_node(&ms_style(&c.marker, bound), &c.attributes),
+            None => self.char(&c.marker, &c.attributes, &c.contents),
+        }
+    }
+
+    /// The `sid` of the current chapter, or of `verse` in it.
+    fn reference(&self, verse: Option<&VerseNumber>) -> String {
+        Reference {
+            book: self.book,
+            chapter: self.chapter,
+            verse: verse.cloned(),
+        }
+        .to_string()
+    }
+}
+
+fn text_para(style: &str, text: &str) -> UsjNode {
+    UsjNode::new("para", Some(style)).with_content(vec![UsjContent::Text(text.to_string())])
+}
+
+fn category(cat: &str) -> UsjNode {
+    UsjNode::new("char", Some("cat")).with_content(vec![UsjContent::Text(cat.to_string())])
+}
+
+fn note(style: &str, caller: &Caller) -> UsjNode {
+    UsjNode::new("note", Some(style)).with_attribute("caller", caller_to_str(caller))
+}
+
+fn ms_node(style: &str, attributes: &[(String, String)]) -> UsjNode {
+    UsjNode {
+        attributes: attributes.to_vec(),
+        ..UsjNode::new("ms", Some(style))
+    }
+}
+
+/// Where a node is in a USJ document, as its index in the `content` of each node on the way to
+/// it, which errors give as a JSON Pointer.
+#[derive(Clone, Copy)]
+struct Pointer<'a>(Option<(&'a Pointer<'a>, usize)>);
+
+impl<'a> Pointer<'a> {
+    const DOCUMENT: Pointer<'static> = Pointer(None);
+
+    fn child(&'a self, index: usize) -> Pointer<'a> {
+        Pointer(Some((self, index)))
+    }
+}
+
+impl fmt::Display for Pointer<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some((parent, index)) = self.0 {
+            write!(f, "{}/content/{}", parent, index)?;
+        }
+        Ok(())
+    }
+}
+
+fn block(node: &UsjNode, at: Pointer, out: &mut Vec<BookContents>) -> Result<(), UsjError> {
+    use BookContents as C;
+    match node.ty.as_str() {
+        "book" => {
+            let code = node.attribute("code").unwrap_or_default();
+            let text = text(node);
+            out.push(C::Id {
+                code: to_book_identifier(code)
+                    .ok_or_else(|| unrecognized("book identifier", code, at, "code"))?,
+                text: (!text.is_empty()).then_some(text),
+            });
+        }
+        "chapter" => {
+            if node.attribute("number").is_some() {
+                out.push(C::Chapter(number(node, "number", at)?));
+                if node.attribute("altnumber").is_some() {
+                    out.push(C::AltChapter(number(node, "altnumber", at)?));
+                }
+                if let Some(number) = node.attribute("pubnumber") {
+                    out.push(C::Element(Element {
+                        ty: ElementType::ChapterPublishedLabel,
+                        contents: vec![ElementContents::Line(number.to_string())],
+                    }));
+                }
+            }
+        }
+        "para" => out.push(para(node, at)?),
+        "table" => {
+            for (i, row) in nodes(content(node)).filter(|(_, n)| n.ty == "table:row") {
+                out.push(C::TableRow(table_row(row, at.child(i))?));
+            }
+        }
+        "sidebar" => out.push(C::Sidebar(sidebar(node, at)?)),
+        "figure" => out.push(C::Figure(figure(node, at)?)),
+        _ => {
+            for (i, child) in nodes(content(node)) {
+                block(child, at.child(i), out)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn para(node: &UsjNode, at: Pointer) -> Result<BookContents, UsjError> {
+    use BookContents as C;
+    let style = node.marker.as_deref().unwrap_or_default();
+    Ok(match style {
+        "ide" => {
+            let text = text(node);
+            C::Encoding(
+                to_book_encoding(&text)
+                    .ok_or_else(|| unrecognized("encoding", &text, at, "content"))?,
+            )
+        }
+        "sts" => {
+            let text = text(node);
+            C::Status(
+                text.parse()
+                    .map_err(|_| unrecognized("status", &text, at, "content"))?,
+            )
+        }
+        "periph" => C::Peripheral(text(node)),
+        _ => to_para(style, inline(content(node), at)?),
+    })
+}
+
+fn table_row(node: &UsjNode, at: Pointer) -> Result<TableRow, UsjError> {
+    let mut cells = Vec::new();
+    for (i, cell) in nodes(content(node)).filter(|(_, n)| n.ty == "table:cell") {
+        let at = at.child(i);
+        let style = cell.marker.as_deref().unwrap_or_default();
+        let (prefix, column) = to_cell_style(style)
+            .ok_or_else(|| unrecognized("table cell style", style, at, "marker"))?;
+        cells.push(TableCell {
+            prefix,
+            column,
+            contents: inline(content(cell), at)?,
+        });
+    }
+    Ok(TableRow { cells })
+}
+
+fn sidebar(node: &UsjNode, at: Pointer) -> Result<Sidebar, UsjError> {
+    use SidebarContents as C;
+    let mut contents = Vec::new();
+    if let Some(cat) = node.attribute("category") {
+        contents.push(C::Category(cat.to_string()));
+    }
+    for (i, child) in nodes(content(node)) {
+        let at = at.child(i);
+        let marker = child.marker.as_deref().unwrap_or_default();
+        match child.ty.as_str() {
+            "para" => contents
+                .push(to_sidebar_contents(para(child, at)?).ok_or_else(|| {
+                    unrecognized("sidebar paragraph style", marker, at, "marker")
+                })?),
+            "table" => {
+                for (i, row) in nodes(content(child)).filter(|(_, n)| n.ty == "table:row") {
+                    contents.push(C::TableRow(table_row(row, at.child(i))?));
+                }
+            }
+            "char" if marker == "cat" => contents.push(C::Category(text(child))),
+            _ => {}
+        }
+    }
+    Ok(Sidebar { contents })
+}
+
+/// Reads `content`, the content of the node at `at`.
+fn inline(content: &[UsjContent], at: Pointer) -> Result<Vec<ParagraphContents>, UsjError> {
+    let mut contents = Vec::new();
+    for (i, item) in content.iter().enumerate() {
+        inline_item(item, at.child(i), &mut contents)?;
+    }
+    Ok(contents)
+}
+
+fn inline_item(
+    item: &UsjContent,
+    at: Pointer,
+    out: &mut Vec<ParagraphContents>,
+) -> Result<(), UsjError> {
+    use ParagraphContents as C;
+    let node = match item {
+        UsjContent::Text(text) => {
+            out.push(C::Line(text.clone()));
+            return Ok(());
+        }
+        UsjContent::Node(node) => node,
+    };
+    let marker = node.marker.as_deref().unwrap_or_default();
+    match node.ty.as_str() {
+        "verse" => {
+            if let Some(number) = node.attribute("number") {
+                let number = number
+                    .parse()
+                    .map_err(|_| unrecognized("verse number", number, at, "number"))?;
+                out.extend(to_verse(
+                    number,
+                    node.attribute("altnumber"),
+                    node.attribute("pubnumber"),
+                ));
+            }
+        }
+        "char" if marker == "cat" => out.push(C::Category(text(node))),
+        "char" => out.push(to_char(
+            marker,
+            character_contents(content(node), at)?,
+            attributes(node, &["closed"]),
+        )),
+        "note" => {
+            let mut children = Vec::new();
+            for (i, item) in content(node).iter().enumerate() {
+                let at = at.child(i);
+                match item {
+                    UsjContent::Node(child) if child.ty == "char" => {
+                        children.push(NoteChild::Char {
+                            style: child.marker.clone().unwrap_or_default(),
+                            contents: character_contents(content(child), at)?,
+                            attributes: attributes(child, &["closed"]),
+                        })
+                    }
+                    item => {
+                        let mut contents = Vec::new();
+                        inline_item(item, at, &mut contents)?;
+                        children.push(NoteChild::Other(
+                            contents
+                                .into_iter()
+                                .filter_map(to_character_contents)
+                                .collect(),
+                        ));
+                    }
+                }
+            }
+            out.push(to_note(marker, node.attribute("caller"), children));
+        }
+        "figure" => out.push(C::Figure(figure(node, at)?)),
+        "ms" => out.push(to_milestone(marker, node.attributes.clone())),
+        "ref" => out.push(to_ref(
+            node.attribute("loc").unwrap_or_default(),
+            character_contents(content(node), at)?,
+        )),
+        "optbreak" => out.push(C::OptionalBreak),
+        _ => {
+            for (i, item) in content(node).iter().enumerate() {
+                inline_item(item, at.child(i), out)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn character_contents(
+    content: &[UsjContent],
+    at: Pointer,
+) -> Result<Vec<CharacterContents>, UsjError> {
+    Ok(inline(content, at)?
+        .into_iter()
+        .filter_map(to_character_contents)
+        .collect())
+}
+
+fn figure(node: &UsjNode, at: Pointer) -> Result<Figure, UsjError> {
+    Ok(to_figure(
+        character_contents(content(node), at)?,
+        node.attributes.clone(),
+    ))
+}
+
+fn number(node: &UsjNode, key: &str, at: Pointer) -> Result<u16, UsjError> {
+    let value = node.attribute(key).unwrap_or_default();
+    value
+        .parse()
+        .map_err(|_| unrecognized("chapter number", value, at, key))
+}
+
+fn content(node: &UsjNode) -> &[UsjContent] {
+    node.content.as_deref().unwrap_or_default()
+}
+
+/// The nodes in `content`, with their indexes in it, leaving out its text.
+fn nodes(content: &[UsjContent]) -> impl Iterator<Item = (usize, &UsjNode)> {
+    content
+        .iter()
+        .enumerate()
+        .filter_map(|(i, item)| match item {
+            UsjContent::Node(node) => Some((i, node)),
+            UsjContent::Text(_) => None,
+        })
+}
+
+fn text(node: &UsjNode) -> String {
+    content(node)
+        .iter()
+        .map(|item| match item {
+            UsjContent::Text(text) => text.clone(),
+            UsjContent::Node(node) => text(node),
+        })
+        .collect()
+}
+
+fn attributes(node: &UsjNode, skip: &[&str]) -> Vec<(String, String)> {
+    node.attributes
+        .iter()
+        .filter(|(key, _)| !skip.contains(&key.as_str()))
+        .cloned()
+        .collect()
+}
+
+/// An error in `value`, which is the property `key` of the node at `at`.
+fn unrecognized(what: &'static str, value: &str, at: Pointer, key: &str) -> UsjError {
+    let kind = ParseErrorKind::Unrecognized {
+        what,
+        value: value.to_string(),
+    };
+    UsjError {
+        kind,
+        path: format!("{}/{}", at, key),
+    }
+}
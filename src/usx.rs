@@ -0,0 +1,1177 @@
+//! Conversion between a [`Book`] and USX 3.0, the XML form of USFM.
+//!
+//! Paragraphs, headings and the other line-initial markers become `<para>` elements, and
+//! character styles and note elements become `<char>`. Chapters and verses are written as
+//! milestones with a `sid` where they start and an `eid` where they end, so a verse that
+//! continues across a paragraph break ends in the last paragraph it is in. The `\usfm` marker
+//! is left out, its version being implied by that of USX.
+//!
+//! The rest follows the USX 3.0 schema rather than the USFM: a `\cp` becomes the `pubnumber`
+//! of the chapter it is in, wherever in it it is (or of the first chapter, if it comes before
+//! it, and only the first `\cp` of a chapter is kept), a `\periph` opens a `<periph>` holding
+//! what follows it, categories are `<char style="cat">` or the `category` of their sidebar,
+//! `\ref` is a `<ref>` and a figure outside any paragraph is put in one of its own.
+//!
+//! Reading goes the other way, taking styles the parser does not know as custom markers just as
+//! it does, so that a book written by [`to_usx`] reads back unchanged but for its `\usfm`
+//! marker and any `\cp` that did not directly follow its `\c`, which reads back there.
+
+use roxmltree::{Document, Node};
+
+use crate::edit::to_character_contents;
+use crate::format::{
+    cell_prefix_to_str, character_type_to_str, cross_ref_element_style_to_str,
+    cross_ref_style_to_str, element_type_to_str, empty_type_to_str, encoding_to_str,
+    footnote_element_style_to_str, footnote_style_to_str, milestone_style_to_str,
+    paragraph_style_to_str, poetry_style_to_str,
+};
+use crate::metadata::to_text;
+use crate::parser::{
+    ParseError, ParseErrorKind, to_book_encoding, to_book_identifier, to_caller, to_cell_prefix,
+    to_character_type, to_cross_ref_element_style, to_cross_ref_style, to_element_type,
+    to_empty_type, to_footnote_element_style, to_footnote_style, to_milestone_style,
+    to_numbered_character_type, to_numbered_element_type, to_numbered_milestone_style,
+    to_numbered_paragraph_style, to_numbered_poetry_style, to_paragraph_style, to_poetry_style,
+};
+use crate::reference::Reference;
+use crate::usfm::*;
+
+/// Writes `book` as a USX document.
+pub fn to_usx(book: &Book) -> String {
+    let mut writer = Writer::default();
+    writer
+        .out
+        .push_str("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<usx version=\"3.0\">\n");
+    for (i, content) in book.contents.iter().enumerate() {
+        writer.book_contents(content, &book.contents[i + 1..]);
+    }
+    writer.end_chapter();
+    writer.end_periph();
+    writer.out.push_str("</usx>\n");
+    writer.out
+}
+
+/// Reads a USX document into a [`Book`].
+///
+/// Verse and chapter milestones are read from their `sid` side, and the `eid` side is skipped.
+/// Elements that have no USFM counterpart are replaced by their contents, and whitespace that
+/// breaks a line between elements is taken as indentation and left out.
+/// Errors are [`Syntax`](ParseErrorKind::Syntax) for XML that is not well-formed and
+/// [`Unrecognized`](ParseErrorKind::Unrecognized) for a value that has no place in a book.
+pub fn from_usx(input: &str) -> Result<Book, ParseError> {
+    let doc = Document::parse(input).map_err(|error| xml_error(error, input))?;
+    let reader = Reader { input };
+    let root = doc.root_element();
+    if !root.has_tag_name("usx") {
+        return Err(reader.unrecognized("root element", root.tag_name().name(), root));
+    }
+    let mut contents = Vec::new();
+    for node in root.children().filter(Node::is_element) {
+        reader.block(node, &mut contents)?;
+    }
+    Ok(Book { contents })
+}
+
+#[derive(Default)]
+struct Writer {
+    out: String,
+    book: Option<BookIdentifier>,
+    chapter: Option<u16>,
+    /// The `sid` of the verse whose end has not been written yet.
+    verse: Option<String>,
+    /// Whether the last `<para>` is still open, for a verse to end in.
+    para: bool,
+    /// Whether the open `<para>` is a blank line, which a figure outside any paragraph goes in.
+    empty: bool,
+    table: bool,
+    periph: bool,
+    /// A `\cp` before the first chapter, written as that chapter's `pubnumber`.
+    carried: Option<String>,
+}
+
+impl Writer {
+    fn book_contents(&mut self, content: &BookContents, rest: &[BookContents]) {
+        use BookContents as C;
+        match content {
+            C::Id { code, text } => {
+                self.book = Some(*code);
+                self.out.push_str(&format!(
+                    "<book code=\"{}\" style=\"id\">{}</book>\n",
+                    code.to_identifier(),
+                    escape(text.as_deref().unwrap_or_default())
+                ));
+            }
+            C::Usfm(_) | C::AltChapter(_) => {}
+            C::Encoding(enc) => self.text_para("ide", encoding_to_str(enc)),
+            C::Status(status) => self.text_para("sts", &status.to_string()),
+            C::Chapter(chapter) => {
+                self.end_chapter();
+                self.chapter = Some(*chapter);
+                let sid = self.reference(None);
+                self.out
+                    .push_str(&format!("<chapter number=\"{}\" style=\"c\"", chapter));
+                let mut rest = rest.iter().peekable();
+                if let Some(C::AltChapter(alt)) = rest.peek() {
+                    self.out.push_str(&format!(" altnumber=\"{}\"", alt));
+                    rest.next();
+                }
+                let own = rest
+                    .take_while(|c| !matches!(c, C::Chapter(_)))
+                    .find_map(|c| match c {
+                        C::Element(e) if e.ty == ElementType::ChapterPublishedLabel => {
+                            Some(to_text(&e.contents))
+                        }
+                        _ => None,
+                    });
+                if let Some(pubnumber) = own.or(self.carried.take()) {
+                    self.out
+                        .push_str(&format!(" pubnumber=\"{}\"", escape(&pubnumber)));
+                }
+                self.out.push_str(&format!(" sid=\"{}\"/>\n", sid));
+            }
+            C::Paragraph(p) => self.para(&para_style(&p.style), &p.contents),
+            C::Poetry(p) => self.para(&poetry_style_to_str(&p.style), &p.contents),
+            C::Custom(p) => self.para(&p.marker, &p.contents),
+            C::Element(e) => self.element(e),
+            C::Empty(ty) => {
+                self.end_block();
+                self.out
+                    .push_str(&format!("<para style=\"{}\">", empty_type_to_str(ty)));
+                self.para = true;
+                self.empty = true;
+            }
+            C::TableRow(tr) => self.table_row(tr),
+            C::Sidebar(sb) => self.sidebar(sb),
+            C::Peripheral(text) => {
+                self.end_block();
+                self.end_periph();
+                self.out
+                    .push_str(&format!("<periph alt=\"{}\">\n", escape(text)));
+                self.periph = true;
+            }
+            C::Figure(fig) => {
+                // USX has figures only within paragraphs, so one that follows a blank line goes
+                // in it, and any other goes in a paragraph of its own.
+                if !self.empty {
+                    self.end_block();
+                    self.out.push_str("<para style=\"p\">");
+                    self.para = true;
+                }
+                self.figure(fig);
+            }
+        }
+    }
+
+    fn para(&mut self, style: &str, contents: &[ParagraphContents]) {
+        if let Some(ParagraphContents::Verse(_)) = contents.first() {
+            self.end_verse();
+        }
+        self.end_para();
+        self.end_table();
+        self.out.push_str(&format!("<para style=\"{}\">", style));
+        self.para = true;
+        self.paragraph_contents(contents);
+    }
+
+    fn element(&mut self, e: &Element) {
+        // USX has no `cp` paragraph, so a `\cp` is written as the `pubnumber` of its chapter,
+        // or of the next one if there has been none yet.
+        if e.ty == ElementType::ChapterPublishedLabel {
+            if self.chapter.is_none() {
+                self.carried = Some(to_text(&e.contents));
+            }
+            return;
+        }
+        self.end_block();
+        self.out
+            .push_str(&format!("<para style=\"{}\">", element_type_to_str(&e.ty)));
+        for content in &e.contents {
+            self.inline(content.into());
+        }
+        self.out.push_str("</para>\n");
+    }
+
+    fn text_para(&mut self, style: &str, text: &str) {
+        self.end_block();
+        self.out.push_str(&format!(
+            "<para style=\"{}\">{}</para>\n",
+            style,
+            escape(text)
+        ));
+    }
+
+    fn table_row(&mut self, tr: &TableRow) {
+        self.end_verse();
+        self.end_para();
+        if !self.table {
+            self.out.push_str("<table>\n");
+            self.table = true;
+        }
+        self.out.push_str("<row style=\"tr\">");
+        for cell in &tr.cells {
+            self.out.push_str(&format!(
+                "<cell style=\"{}\" align=\"{}\">",
+                cell_style(cell),
+                cell_align(&cell.prefix)
+            ));
+            self.paragraph_contents(&cell.contents);
+            self.end_verse();
+            self.out.push_str("</cell>");
+        }
+        self.out.push_str("</row>\n");
+    }
+
+    fn sidebar(&mut self, sb: &Sidebar) {
+        self.end_block();
+        let mut contents = sb.contents.as_slice();
+        self.out.push_str("<sidebar style=\"esb\"");
+        if let [SidebarContents::Category(cat), rest @ ..] = contents {
+            self.out.push_str(&format!(" category=\"{}\"", escape(cat)));
+            contents = rest;
+        }
+        self.out.push_str(">\n");
+        for content in contents {
+            use SidebarContents as C;
+            match content {
+                C::Paragraph(p) => self.para(&para_style(&p.style), &p.contents),
+                C::Poetry(p) => self.para(&poetry_style_to_str(&p.style), &p.contents),
+                C::Element(e) => self.element(e),
+                C::Empty(ty) => {
+                    self.end_block();
+                    self.out
+                        .push_str(&format!("<para style=\"{}\"/>\n", empty_type_to_str(ty)));
+                }
+                C::TableRow(tr) => self.table_row(tr),
+                C::Category(cat) => {
+                    self.end_block();
+                    self.category(cat);
+                    self.out.push('\n');
+                }
+            }
+        }
+        self.end_block();
+        self.out.push_str("</sidebar>\n");
+    }
+
+    fn paragraph_contents(&mut self, contents: &[ParagraphContents]) {
+        for content in contents {
+            self.inline(content.into());
+        }
+    }
+
+    fn character_contents(&mut self, contents: &[CharacterContents]) {
+        for content in contents {
+            self.inline(content.into());
+        }
+    }
+
+    fn inline(&mut self, content: Inline) {
+        match content {
+            Inline::Verse(verse) => {
+                self.end_verse();
+                let sid = self.reference(Some(verse));
+                self.out.push_str(&format!(
+                    "<verse number=\"{}\" style=\"v\" sid=\"{}\"/>",
+                    verse, sid
+                ));
+                self.verse = Some(sid);
+            }
+            Inline::Line(text) => self.out.push_str(&escape(text)),
+            Inline::Character(c) => self.character(c),
+            Inline::Footnote(f) => self.footnote(f),
+            Inline::CrossRef(x) => self.cross_ref(x),
+            Inline::Figure(fig) => self.figure(fig),
+            Inline::Milestone(ms) => self.ms(&milestone_style(ms), &ms.attributes),
+            Inline::Category(cat) => self.category(cat),
+            Inline::OptionalBreak => self.out.push_str("<optbreak/>"),
+            Inline::Custom(c) => self.custom(c),
+        }
+    }
+
+    fn character(&mut self, c: &Character) {
+        if c.ty == CharacterType::ScriptureRef {
+            return self.reference_link(c);
+        }
+        self.char(&character_style(c), &c.attributes, &c.contents);
+    }
+
+    fn char(
+        &mut self,
+        style: &str,
+        attributes: &[(String, String)],
+        contents: &[CharacterContents],
+    ) {
+        self.out.push_str(&format!(
+            "<char style=\"{}\"{}>",
+            style,
+            format_attributes(attributes)
+        ));
+        self.character_contents(contents);
+        self.out.push_str("</char>");
+    }
+
+    /// Writes `\ref` as the `<ref>` USX has for it.
+    fn reference_link(&mut self, c: &Character) {
+        self.out
+            .push_str(&format!("<ref loc=\"{}\">", escape(&ref_loc(c))));
+        self.character_contents(&c.contents);
+        self.out.push_str("</ref>");
+    }
+
+    fn footnote(&mut self, f: &Footnote) {
+        self.note(footnote_style_to_str(&f.style), &f.caller);
+        for element in &f.elements {
+            self.note_element(
+                footnote_element_style_to_str(&element.style),
+                &element.contents,
+            );
+        }
+        self.out.push_str("</note>");
+    }
+
+    fn cross_ref(&mut self, x: &CrossRef) {
+        self.note(cross_ref_style_to_str(&x.style), &x.caller);
+        for element in &x.elements {
+            self.note_element(
+                cross_ref_element_style_to_str(&element.style),
+                &element.contents,
+            );
+        }
+        self.out.push_str("</note>");
+    }
+
+    fn note(&mut self, style: &str, caller: &Caller) {
+        self.out.push_str(&format!(
+            "<note caller=\"{}\" style=\"{}\">",
+            escape(&caller_to_str(caller)),
+            style
+        ));
+    }
+
+    fn note_element(&mut self, style: &str, contents: &[CharacterContents]) {
+        self.out
+            .push_str(&format!("<char style=\"{}\" closed=\"false\">", style));
+        self.character_contents(contents);
+        self.out.push_str("</char>");
+    }
+
+    fn figure(&mut self, fig: &Figure) {
+        self.out.push_str(&format!(
+            "<figure style=\"fig\"{}>",
+            format_attributes(&figure_attributes(&fig.attributes))
+        ));
+        self.character_contents(&fig.contents);
+        self.out.push_str("</figure>");
+    }
+
+    fn ms(&mut self, style: &str, attributes: &[(String, String)]) {
+        self.out.push_str(&format!(
+            "<ms style=\"{}\"{}/>",
+            style,
+            format_attributes(attributes)
+        ));
+    }
+
+    fn custom(&mut self, c: &Custom) {
+        match &c.milestone {
+            Some(bound) => self.ms(&ms_style(&c.marker, bound), &c.attributes),
+            None => self.char(&c.marker, &c.attributes, &c.contents),
+        }
+    }
+
+    fn category(&mut self, cat: &str) {
+        self.out
+            .push_str(&format!("<char style=\"cat\">{}</char>", escape(cat)));
+    }
+
+    /// The `sid` of the current chapter, or of `verse` in it.
+    fn reference(&self, verse: Option<&VerseNumber>) -> String {
+        Reference {
+            book: self.book,
+            chapter: self.chapter,
+            verse: verse.cloned(),
+        }
+        .to_string()
+    }
+
+    fn end_verse(&mut self) {
+        if let Some(sid) = self.verse.take() {
+            self.out.push_str(&format!("<verse eid=\"{}\"/>", sid));
+        }
+    }
+
+    fn end_para(&mut self) {
+        if self.para {
+            self.out.push_str("</para>\n");
+            self.para = false;
+            self.empty = false;
+        }
+    }
+
+    fn end_table(&mut self) {
+        if self.table {
+            self.out.push_str("</table>\n");
+            self.table = false;
+        }
+    }
+
+    fn end_block(&mut self) {
+        self.end_verse();
+        self.end_para();
+        self.end_table();
+    }
+
+    fn end_periph(&mut self) {
+        if self.periph {
+            self.end_block();
+            self.out.push_str("</periph>\n");
+            self.periph = false;
+        }
+    }
+
+    fn end_chapter(&mut self) {
+        self.end_block();
+        if self.chapter.is_some() {
+            let eid = self.reference(None);
+            self.out.push_str(&format!("<chapter eid=\"{}\"/>\n", eid));
+            self.chapter = None;
+        }
+    }
+}
+
+// The rest of writing is shared with USJ, which has the same nodes and styles as USX.
+
+/// A piece of inline content, from whichever of the content enums holds it.
+pub(crate) enum Inline<'a> {
+    Verse(&'a VerseNumber),
+    Line(&'a str),
+    Character(&'a Character),
+    Footnote(&'a Footnote),
+    CrossRef(&'a CrossRef),
+    Figure(&'a Figure),
+    Milestone(&'a Milestone),
+    Category(&'a str),
+    OptionalBreak,
+    Custom(&'a Custom),
+}
+
+impl<'a> From<&'a ParagraphContents> for Inline<'a> {
+    fn from(content: &'a ParagraphContents) -> Self {
+        use ParagraphContents as C;
+        match content {
+            C::Verse(verse) => Inline::Verse(verse),
+            C::Line(text) => Inline::Line(text),
+            C::Character(c) => Inline::Character(c),
+            C::Footnote(f) => Inline::Footnote(f),
+            C::CrossRef(x) => Inline::CrossRef(x),
+            C::Figure(fig) => Inline::Figure(fig),
+            C::Milestone(ms) => Inline::Milestone(ms),
+            C::Category(cat) => Inline::Category(cat),
+            C::OptionalBreak => Inline::OptionalBreak,
+            C::Custom(c) => Inline::Custom(c),
+        }
+    }
+}
+
+impl<'a> From<&'a ElementContents> for Inline<'a> {
+    fn from(content: &'a ElementContents) -> Self {
+        use ElementContents as C;
+        match content {
+            C::Line(text) => Inline::Line(text),
+            C::Character(c) => Inline::Character(c),
+            C::Footnote(f) => Inline::Footnote(f),
+            C::CrossRef(x) => Inline::CrossRef(x),
+            C::Figure(fig) => Inline::Figure(fig),
+            C::Milestone(ms) => Inline::Milestone(ms),
+            C::Category(cat) => Inline::Category(cat),
+            C::OptionalBreak => Inline::OptionalBreak,
+            C::Custom(c) => Inline::Custom(c),
+        }
+    }
+}
+
+impl<'a> From<&'a CharacterContents> for Inline<'a> {
+    fn from(content: &'a CharacterContents) -> Self {
+        use CharacterContents as C;
+        match content {
+            C::Line(text) => Inline::Line(text),
+            C::Character(c) => Inline::Character(c),
+            C::Footnote(f) => Inline::Footnote(f),
+            C::CrossRef(x) => Inline::CrossRef(x),
+            C::Figure(fig) => Inline::Figure(fig),
+            C::Milestone(ms) => Inline::Milestone(ms),
+            C::OptionalBreak => Inline::OptionalBreak,
+            C::Custom(c) => Inline::Custom(c),
+        }
+    }
+}
+
+/// The style of a paragraph, with `\mi` written without the number the grammar gives it.
+pub(crate) fn para_style(style: &ParagraphStyle) -> String {
+    match style {
+        ParagraphStyle::MarginIndentedNum(1) => "mi".to_string(),
+        style => paragraph_style_to_str(style),
+    }
+}
+
+/// The style of a character, with the number of a `\litl` that has one.
+pub(crate) fn character_style(c: &Character) -> String {
+    match &c.ty {
+        CharacterType::ListValue(n) if *n > 0 => format!("{}{}", character_type_to_str(&c.ty), n),
+        ty => character_type_to_str(ty).to_string(),
+    }
+}
+
+/// The style of a table cell, such as `thr2`.
+pub(crate) fn cell_style(cell: &TableCell) -> String {
+    format!("{}{}", cell_prefix_to_str(&cell.prefix), cell.column)
+}
+
+pub(crate) fn cell_align(prefix: &CellPrefix) -> &'static str {
+    match prefix {
+        CellPrefix::HeaderRight | CellPrefix::ContentRight => "end",
+        CellPrefix::HeaderCenter | CellPrefix::ContentCenter => "center",
+        CellPrefix::Header | CellPrefix::Content => "start",
+    }
+}
+
+/// Where a `\ref` points: its `loc` attribute, or the default one, and otherwise its own text.
+pub(crate) fn ref_loc(c: &Character) -> String {
+    c.attributes
+        .iter()
+        .find(|(key, _)| key == "loc" || key == "lemma")
+        .map_or_else(|| char_text(&c.contents), |(_, value)| value.clone())
+}
+
+pub(crate) fn caller_to_str(caller: &Caller) -> String {
+    match caller {
+        Caller::Auto => "+".to_string(),
+        Caller::None => "-".to_string(),
+        Caller::Some(c) => c.to_string(),
+    }
+}
+
+/// The style of a milestone, ending in `-s` or `-e` if it is one side of a pair.
+pub(crate) fn milestone_style(ms: &Milestone) -> String {
+    use MilestoneStyle::*;
+    let bound = match &ms.style {
+        QuotedText(_, b) | TextSection(b) | Text(b) | WordsOfJesus(b) | Custom(_, b) => b,
+        VerseId => &MilestoneBound::None,
+    };
+    ms_style(&milestone_style_to_str(&ms.style), bound)
+}
+
+pub(crate) fn ms_style(style: &str, bound: &MilestoneBound) -> String {
+    let suffix = match bound {
+        MilestoneBound::Start => "-s",
+        MilestoneBound::End => "-e",
+        MilestoneBound::None => "",
+    };
+    format!("{}{}", style, suffix)
+}
+
+/// The attributes of a figure, with `src` named `file`.
+pub(crate) fn figure_attributes(attributes: &[(String, String)]) -> Vec<(String, String)> {
+    attributes
+        .iter()
+        .map(|(key, value)| match key.as_str() {
+            "src" => ("file".to_string(), value.clone()),
+            _ => (key.clone(), value.clone()),
+        })
+        .collect()
+}
+
+fn format_attributes(attributes: &[(String, String)]) -> String {
+    attributes
+        .iter()
+        .map(|(key, value)| format!(" {}=\"{}\"", key, escape(value)))
+        .collect()
+}
+
+pub(crate) fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+struct Reader<'i> {
+    input: &'i str,
+}
+
+impl Reader<'_> {
+    fn block(&self, node: Node, out: &mut Vec<BookContents>) -> Result<(), ParseError> {
+        use BookContents as C;
+        match node.tag_name().name() {
+            "book" => {
+                let code = node.attribute("code").unwrap_or_default();
+                let text = text(node);
+                out.push(C::Id {
+                    code: to_book_identifier(code)
+                        .ok_or_else(|| self.unrecognized("book identifier", code, node))?,
+                    text: (!text.is_empty()).then_some(text),
+                });
+            }
+            "chapter" => {
+                if node.has_attribute("number") {
+                    out.push(C::Chapter(self.number(node, "number")?));
+                    if node.has_attribute("altnumber") {
+                        out.push(C::AltChapter(self.number(node, "altnumber")?));
+                    }
+                    if let Some(number) = node.attribute("pubnumber") {
+                        out.push(C::Element(Element {
+                            ty: ElementType::ChapterPublishedLabel,
+                            contents: vec![ElementContents::Line(number.to_string())],
+                        }));
+                    }
+                }
+            }
+            "para" => {
+                let para = self.para(node)?;
+                let empty = matches!(para, C::Empty(_));
+                out.push(para);
+                if empty {
+                    for figure in node.children().filter(|n| n.has_tag_name("figure")) {
+                        out.push(C::Figure(self.figure(figure)?));
+                    }
+                }
+            }
+            "periph" => {
+                out.push(C::Peripheral(
+                    node.attribute("alt").unwrap_or_default().to_string(),
+                ));
+                for child in node.children().filter(Node::is_element) {
+                    self.block(child, out)?;
+                }
+            }
+            "table" => {
+                for row in node.children().filter(|n| n.has_tag_name("row")) {
+                    out.push(C::TableRow(self.table_row(row)?));
+                }
+            }
+            "sidebar" => out.push(C::Sidebar(self.sidebar(node)?)),
+            "figure" => out.push(C::Figure(self.figure(node)?)),
+            _ => {
+                for child in node.children().filter(Node::is_element) {
+                    self.block(child, out)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn para(&self, node: Node) -> Result<BookContents, ParseError> {
+        use BookContents as C;
+        let style = node.attribute("style").unwrap_or_default();
+        Ok(match style {
+            "ide" => {
+                let text = text(node);
+                let encoding = to_book_encoding(&text)
+                    .ok_or_else(|| self.unrecognized("encoding", &text, node))?;
+                C::Encoding(encoding)
+            }
+            "sts" => {
+                let text = text(node);
+                let status = text
+                    .parse()
+                    .map_err(|_| self.unrecognized("status", &text, node))?;
+                C::Status(status)
+            }
+            "periph" => C::Peripheral(text(node)),
+            _ => to_para(style, self.inline(node)?),
+        })
+    }
+
+    fn table_row(&self, node: Node) -> Result<TableRow, ParseError> {
+        let mut cells = Vec::new();
+        for cell in node.children().filter(|n| n.has_tag_name("cell")) {
+            let style = cell.attribute("style").unwrap_or_default();
+            let (prefix, column) = to_cell_style(style)
+                .ok_or_else(|| self.unrecognized("table cell style", style, cell))?;
+            cells.push(TableCell {
+                prefix,
+                column,
+                contents: self.inline(cell)?,
+            });
+        }
+        Ok(TableRow { cells })
+    }
+
+    fn sidebar(&self, node: Node) -> Result<Sidebar, ParseError> {
+        use SidebarContents as C;
+        let mut contents = Vec::new();
+        if let Some(cat) = node.attribute("category") {
+            contents.push(C::Category(cat.to_string()));
+        }
+        for child in node.children().filter(Node::is_element) {
+            match child.tag_name().name() {
+                "para" => {
+                    contents.push(to_sidebar_contents(self.para(child)?).ok_or_else(|| {
+                        let style = child.attribute("style").unwrap_or_default();
+                        self.unrecognized("sidebar paragraph style", style, child)
+                    })?)
+                }
+                "table" => {
+                    for row in child.children().filter(|n| n.has_tag_name("row")) {
+                        contents.push(C::TableRow(self.table_row(row)?));
+                    }
+                }
+                "char" if child.attribute("style") == Some("cat") => {
+                    contents.push(C::Category(text(child)))
+                }
+                _ => {}
+            }
+        }
+        Ok(Sidebar { contents })
+    }
+
+    fn inline(&self, node: Node) -> Result<Vec<ParagraphContents>, ParseError> {
+        let mut contents = Vec::new();
+        for child in node.children() {
+            self.inline_node(child, &mut contents)?;
+        }
+        Ok(contents)
+    }
+
+    fn inline_node(&self, node: Node, out: &mut Vec<ParagraphContents>) -> Result<(), ParseError> {
+        use ParagraphContents as C;
+        if let Some(text) = node.text().filter(|_| node.is_text()) {
+            // Whitespace that breaks a line is indentation from pretty-printing.
+            if !(text.contains('\n') && text.trim().is_empty()) {
+                out.push(C::Line(text.to_string()));
+            }
+            return Ok(());
+        }
+        match node.tag_name().name() {
+            "verse" => {
+                if let Some(number) = node.attribute("number") {
+                    let number = number
+                        .parse()
+                        .map_err(|_| self.unrecognized("verse number", number, node))?;
+                    out.extend(to_verse(
+                        number,
+                        node.attribute("altnumber"),
+                        node.attribute("pubnumber"),
+                    ));
+                }
+            }
+            "char" if node.attribute("style") == Some("cat") => out.push(C::Category(text(node))),
+            "char" => out.push(to_char(
+                node.attribute("style").unwrap_or_default(),
+                self.character_contents(node)?,
+                attributes(node, &["style", "closed"]),
+            )),
+            "note" => out.push(self.note(node)?),
+            "figure" => out.push(C::Figure(self.figure(node)?)),
+            "ms" => out.push(to_milestone(
+                node.attribute("style").unwrap_or_default(),
+                attributes(node, &["style"]),
+            )),
+            "ref" => out.push(to_ref(
+                node.attribute("loc").unwrap_or_default(),
+                self.character_contents(node)?,
+            )),
+            "optbreak" => out.push(C::OptionalBreak),
+            _ => {
+                for child in node.children() {
+                    self.inline_node(child, out)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn character_contents(&self, node: Node) -> Result<Vec<CharacterContents>, ParseError> {
+        Ok(self
+            .inline(node)?
+            .into_iter()
+            .filter_map(to_character_contents)
+            .collect())
+    }
+
+    fn note(&self, node: Node) -> Result<ParagraphContents, ParseError> {
+        let mut children = Vec::new();
+        for child in node.children() {
+            if child.has_tag_name("char") {
+                children.push(NoteChild::Char {
+                    style: child.attribute("style").unwrap_or_default().to_string(),
+                    contents: self.character_contents(child)?,
+                    attributes: attributes(child, &["style", "closed"]),
+                });
+            } else {
+                let mut contents = Vec::new();
+                self.inline_node(child, &mut contents)?;
+                children.push(NoteChild::Other(
+                    contents
+                        .into_iter()
+                        .filter_map(to_character_contents)
+                        .collect(),
+                ));
+            }
+        }
+        Ok(to_note(
+            node.attribute("style").unwrap_or_default(),
+            node.attribute("caller"),
+            children,
+        ))
+    }
+
+    fn figure(&self, node: Node) -> Result<Figure, ParseError> {
+        Ok(to_figure(
+            self.character_contents(node)?,
+            attributes(node, &["style"]),
+        ))
+    }
+
+    fn number(&self, node: Node, key: &str) -> Result<u16, ParseError> {
+        let value = node.attribute(key).unwrap_or_default();
+        value
+            .parse()
+            .map_err(|_| self.unrecognized("chapter number", value, node))
+    }
+
+    fn unrecognized(&self, what: &'static str, value: &str, node: Node) -> ParseError {
+        let kind = ParseErrorKind::Unrecognized {
+            what,
+            value: value.to_string(),
+        };
+        ParseError::new(kind, self.input, node.range().start)
+    }
+}
+
+fn text(node: Node) -> String {
+    node.descendants()
+        .filter(Node::is_text)
+        .filter_map(|n| n.text())
+        .collect()
+}
+
+/// The text of a character's contents, without its notes.
+fn char_text(contents: &[CharacterContents]) -> String {
+    contents
+        .iter()
+        .map(|content| match content {
+            CharacterContents::Line(line) => line.clone(),
+            CharacterContents::Character(c) => char_text(&c.contents),
+            _ => String::new(),
+        })
+        .collect()
+}
+
+fn attributes(node: Node, skip: &[&str]) -> Vec<(String, String)> {
+    node.attributes()
+        .filter(|a| !skip.contains(&a.name()))
+        .map(|a| (a.name().to_string(), a.value().to_string()))
+        .collect()
+}
+
+// The rest of reading is shared with USJ, which has the same nodes and styles as USX.
+
+/// The block a paragraph of `style` is, other than `\ide`, `\sts` and `\periph`, whose text
+/// has to be read. A style the parser does not know gives a custom paragraph.
+pub(crate) fn to_para(style: &str, contents: Vec<ParagraphContents>) -> BookContents {
+    use BookContents as C;
+    if let Some(ty) = to_empty_type(style) {
+        C::Empty(ty)
+    } else if let Some(ty) = styled(style, to_element_type, to_numbered_element_type) {
+        C::Element(Element {
+            ty: ty.into(),
+            contents: contents
+                .into_iter()
+                .filter_map(to_element_contents)
+                .collect(),
+        })
+    } else if let Some(style) = styled(style, to_paragraph_style, to_numbered_paragraph_style) {
+        C::Paragraph(Paragraph {
+            style: style.into(),
+            contents,
+        })
+    } else if let Some(style) = styled(style, to_poetry_style, to_numbered_poetry_style) {
+        C::Poetry(Poetry { style, contents })
+    } else {
+        C::Custom(CustomParagraph {
+            marker: style.to_string(),
+            contents,
+        })
+    }
+}
+
+/// The contents of a sidebar that `content` can be.
+pub(crate) fn to_sidebar_contents(content: BookContents) -> Option<SidebarContents> {
+    use SidebarContents as C;
+    Some(match content {
+        BookContents::Paragraph(p) => C::Paragraph(p),
+        BookContents::Poetry(p) => C::Poetry(p),
+        BookContents::Element(e) => C::Element(e),
+        BookContents::Empty(ty) => C::Empty(ty),
+        BookContents::TableRow(tr) => C::TableRow(tr),
+        _ => return None,
+    })
+}
+
+/// The prefix and column of a table cell style such as `thr2`.
+pub(crate) fn to_cell_style(style: &str) -> Option<(CellPrefix, u8)> {
+    let (prefix, column) = split_number(style);
+    Some((to_cell_prefix(prefix)?, column.parse().ok()?))
+}
+
+/// A `\v` marker, followed by the `\va` and `\vp` that USX and USJ give as attributes of it.
+pub(crate) fn to_verse(
+    number: VerseNumber,
+    altnumber: Option<&str>,
+    pubnumber: Option<&str>,
+) -> Vec<ParagraphContents> {
+    let mut contents = vec![ParagraphContents::Verse(number)];
+    for (number, ty) in [
+        (altnumber, CharacterType::AltVerse),
+        (pubnumber, CharacterType::PublishedVerse),
+    ] {
+        if let Some(number) = number {
+            contents.push(ParagraphContents::Character(Character::new(ty, number)));
+        }
+    }
+    contents
+}
+
+/// A character style, or a custom span if the parser does not know `style`.
+pub(crate) fn to_char(
+    style: &str,
+    contents: Vec<CharacterContents>,
+    attributes: Vec<(String, String)>,
+) -> ParagraphContents {
+    match styled(style, to_character_type, to_numbered_character_type) {
+        Some(ty) => ParagraphContents::Character(Character {
+            ty: ty.into(),
+            contents,
+            attributes,
+        }),
+        None => ParagraphContents::Custom(Custom {
+            marker: style.to_string(),
+            milestone: None,
+            contents,
+            attributes,
+        }),
+    }
+}
+
+/// A `\ref` to `loc`, which is left out where it is the text of the reference.
+pub(crate) fn to_ref(loc: &str, contents: Vec<CharacterContents>) -> ParagraphContents {
+    let attributes = if loc == char_text(&contents) {
+        Vec::new()
+    } else {
+        vec![("loc".to_string(), loc.to_string())]
+    };
+    ParagraphContents::Character(Character {
+        ty: CharacterType::ScriptureRef,
+        contents,
+        attributes,
+    })
+}
+
+/// A milestone, from a style that ends in `-s` or `-e` unless it is a standalone one like `vid`.
+pub(crate) fn to_milestone(style: &str, attributes: Vec<(String, String)>) -> ParagraphContents {
+    let (marker, bound) = match style.rsplit_once('-') {
+        Some((marker, bound @ ("s" | "e"))) => (marker, Some(bound)),
+        _ => (style, None),
+    };
+    let (name, n) = split_number(marker);
+    let style = to_milestone_style(marker, bound).or_else(|| {
+        let n = if n.is_empty() {
+            Some(1)
+        } else {
+            n.parse().ok()
+        };
+        to_numbered_milestone_style(name, n?, bound)
+    });
+    match style {
+        Some(style) => ParagraphContents::Milestone(Milestone {
+            style: style.into(),
+            attributes,
+        }),
+        None => ParagraphContents::Custom(Custom {
+            marker: marker.to_string(),
+            milestone: Some(match bound {
+                Some("s") => MilestoneBound::Start,
+                Some(_) => MilestoneBound::End,
+                None => MilestoneBound::None,
+            }),
+            contents: Vec::new(),
+            attributes,
+        }),
+    }
+}
+
+pub(crate) fn to_figure(
+    contents: Vec<CharacterContents>,
+    attributes: Vec<(String, String)>,
+) -> Figure {
+    let attributes = attributes
+        .into_iter()
+        .map(|(key, value)| match key.as_str() {
+            "file" => ("src".to_string(), value),
+            _ => (key, value),
+        })
+        .collect();
+    Figure {
+        contents,
+        attributes,
+    }
+}
+
+/// A child of a note, which is one of its elements if it is a character style of an element
+/// style.
+pub(crate) enum NoteChild {
+    Char {
+        style: String,
+        contents: Vec<CharacterContents>,
+        attributes: Vec<(String, String)>,
+    },
+    Other(Vec<CharacterContents>),
+}
+
+/// A footnote or cross reference. A note of a style the parser does not know is a footnote
+/// unless its elements are those of a cross reference.
+pub(crate) fn to_note(
+    style: &str,
+    caller: Option<&str>,
+    children: Vec<NoteChild>,
+) -> ParagraphContents {
+    let caller = to_caller(caller.and_then(|c| c.chars().next()).unwrap_or('+'));
+    let is_footnote = match (to_footnote_style(style), to_cross_ref_style(style)) {
+        (Some(_), _) => true,
+        (None, Some(_)) => false,
+        (None, None) => children.iter().all(|child| match child {
+            NoteChild::Char { style, .. } => to_footnote_element_style(style).is_some(),
+            NoteChild::Other(_) => true,
+        }),
+    };
+    if is_footnote {
+        ParagraphContents::Footnote(Footnote {
+            style: to_footnote_style(style)
+                .map(Into::into)
+                .unwrap_or_else(|| FootnoteStyle::Custom(style.to_string())),
+            caller,
+            elements: note_elements(
+                children,
+                to_footnote_element_style,
+                FootnoteElementStyle::Text,
+            )
+            .into_iter()
+            .map(|(style, contents)| FootnoteElement { style, contents })
+            .collect(),
+        })
+    } else {
+        ParagraphContents::CrossRef(CrossRef {
+            style: to_cross_ref_style(style)
+                .map(Into::into)
+                .unwrap_or_else(|| CrossRefStyle::Custom(style.to_string())),
+            caller,
+            elements: note_elements(
+                children,
+                to_cross_ref_element_style,
+                CrossRefElementStyle::Target,
+            )
+            .into_iter()
+            .map(|(style, contents)| CrossRefElement { style, contents })
+            .collect(),
+        })
+    }
+}
+
+/// The elements of a note, each a character style of an element style followed by whatever
+/// comes before the next one. Contents before the first element go in one of style `default`.
+fn note_elements<S>(
+    children: Vec<NoteChild>,
+    to_style: fn(&str) -> Option<S>,
+    default: S,
+) -> Vec<(S, Vec<CharacterContents>)> {
+    let mut elements = Vec::new();
+    let mut default = Some(default);
+    for child in children {
+        let contents = match child {
+            NoteChild::Char {
+                style,
+                contents,
+                attributes,
+            } => match to_style(&style) {
+                Some(style) => {
+                    elements.push((style, contents));
+                    continue;
+                }
+                None => to_character_contents(to_char(&style, contents, attributes))
+                    .into_iter()
+                    .collect(),
+            },
+            NoteChild::Other(contents) => contents,
+        };
+        match elements.last_mut() {
+            Some((_, last)) => last.extend(contents),
+            None => {
+                if let Some(default) = default.take_if(|_| !contents.is_empty()) {
+                    elements.push((default, contents));
+                }
+            }
+        }
+    }
+    elements
+}
+
+/// Looks `style` up as a marker with a number, which is `1` where it is left out, and then as
+/// one without, in the order the grammar tries them.
+fn styled<T>(
+    style: &str,
+    plain: fn(&str) -> Option<T>,
+    numbered: fn(&str, u8) -> Option<T>,
+) -> Option<T> {
+    let (name, n) = split_number(style);
+    let n = if n.is_empty() {
+        Some(1)
+    } else {
+        n.parse().ok()
+    };
+    n.and_then(|n| numbered(name, n)).or_else(|| plain(style))
+}
+
+/// Splits a style into its name and the digits that end it.
+fn split_number(style: &str) -> (&str, &str) {
+    let name = style.trim_end_matches(|c: char| c.is_ascii_digit());
+    (name, &style[name.len()..])
+}
+
+fn to_element_contents(content: ParagraphContents) -> Option<ElementContents> {
+    use ParagraphContents as C;
+    Some(match content {
+        C::Line(text) => ElementContents::Line(text),
+        C::Character(c) => ElementContents::Character(c),
+        C::Footnote(f) => ElementContents::Footnote(f),
+        C::CrossRef(x) => ElementContents::CrossRef(x),
+        C::Figure(fig) => ElementContents::Figure(fig),
+        C::Milestone(ms) => ElementContents::Milestone(ms),
+        C::Category(cat) => ElementContents::Category(cat),
+        C::OptionalBreak => ElementContents::OptionalBreak,
+        C::Custom(c) => ElementContents::Custom(c),
+        C::Verse(_) => return None,
+    })
+}
+
+/// Turns the row and column of an XML error into an offset into `input`.
+fn xml_error(error: roxmltree::Error, input: &str) -> ParseError {
+    let pos = error.pos();
+    let line_start: usize = input
+        .split_inclusive('\n')
+        .take(pos.row.saturating_sub(1) as usize)
+        .map(str::len)
+        .sum();
+    let offset = input[line_start..]
+        .char_indices()
+        .nth(pos.col.saturating_sub(1) as usize)
+        .map_or(input.len(), |(i, _)| line_start + i);
+    ParseError::new(ParseErrorKind::Syntax, input, offset)
+}
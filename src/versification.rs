@@ -0,0 +1,220 @@
+//! Versification systems, which give the number of verses in each chapter and how verses line
+//! up between translations that number them differently.
+//!
+//! Systems are read from Paratext `.vrs` files such as `org.vrs`, `eng.vrs`, `lxx.vrs`,
+//! `vul.vrs`, `rso.vrs` and `rsc.vrs`. Their mappings relate each system to the original
+//! (`org`) versification, through which any two systems can be mapped to each other.
+
+use std::{
+    collections::{HashMap, HashSet},
+    str::FromStr,
+};
+
+use crate::parser::{ParseError, ParseErrorKind, to_book_identifier};
+use crate::reference::{RefRange, Reference};
+use crate::usfm::{BookIdentifier, VerseNumber};
+
+/// A single verse: book, chapter and verse.
+type Verse = (BookIdentifier, u16, u16);
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Versification {
+    name: Option<String>,
+    /// The last verse of each chapter, by book, or `None` for a chapter the file leaves out.
+    books: HashMap<BookIdentifier, Vec<Option<u16>>>,
+    excluded: HashSet<Verse>,
+    to_original: HashMap<Verse, Verse>,
+    from_original: HashMap<Verse, Verse>,
+}
+
+impl Versification {
+    /// Parses the text of a `.vrs` file.
+    ///
+    /// Each line is one of:
+    /// - `GEN 1:31 2:25 ...`: the last verse of each chapter of a book;
+    /// - `-GEN 1:5`: a verse this system leaves out;
+    /// - `PSA 3:1-8 = PSA 3:2-9`: verses of this system and the original verses they match.
+    ///
+    /// Comments start with `#`, except for mappings written as `#! ...`, which are read. Verse
+    /// segment lines (`*MAT 1:1,-,a,b`) are ignored.
+    pub fn parse(input: &str) -> Result<Self, ParseError> {
+        let mut versification = Self::default();
+        let mut offset = 0;
+        for line in input.split_inclusive('\n') {
+            let base = offset;
+            offset += line.len();
+            let content = match line.trim_start().strip_prefix("#!") {
+                Some(mapping) => mapping,
+                None => {
+                    if let Some(name) = to_name(line) {
+                        versification.name = Some(name.to_string());
+                    }
+                    line.split('#').next().unwrap_or_default()
+                }
+            };
+            let content = content.trim();
+            if content.is_empty() || content.starts_with('*') {
+                continue;
+            }
+            let result = if let Some((from, to)) = content.split_once('=') {
+                let from = from.trim().trim_start_matches('&');
+                versification.add_mapping(from, to.trim())
+            } else if let Some(excluded) = content.strip_prefix('-') {
+                versification.add_excluded(excluded)
+            } else {
+                versification.add_book(content)
+            };
+            result.map_err(|_| {
+                let kind = ParseErrorKind::Unrecognized {
+                    what: "versification line",
+                    value: line.trim().to_string(),
+                };
+                ParseError::new(kind, input, base)
+            })?;
+        }
+        Ok(versification)
+    }
+
+    /// The name given in the file's `# Versification "..."` comment.
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    pub fn books(&self) -> impl Iterator<Item = BookIdentifier> + '_ {
+        self.books.keys().copied()
+    }
+
+    /// The number of chapters in `book`.
+    pub fn last_chapter(&self, book: BookIdentifier) -> Option<u16> {
+        self.books.get(&book).map(|chapters| chapters.len() as u16)
+    }
+
+    /// The number of verses in a chapter of `book`, if the file gives it.
+    pub fn last_verse(&self, book: BookIdentifier, chapter: u16) -> Option<u16> {
+        let chapters = self.books.get(&book)?;
+        *chapters.get(usize::from(chapter).checked_sub(1)?)?
+    }
+
+    /// Whether a verse within the bounds of its chapter is left out of this system.
+    pub fn is_excluded(&self, book: BookIdentifier, chapter: u16, verse: u16) -> bool {
+        self.excluded.contains(&(book, chapter, verse))
+    }
+
+    /// Maps a reference in this system onto the original versification.
+    pub fn to_original(&self, reference: &Reference) -> Reference {
+        map_reference(reference, |verse| {
+            self.to_original.get(&verse).copied().unwrap_or(verse)
+        })
+    }
+
+    /// Maps a reference in the original versification onto this system.
+    pub fn from_original(&self, reference: &Reference) -> Reference {
+        map_reference(reference, |verse| {
+            self.from_original.get(&verse).copied().unwrap_or(verse)
+        })
+    }
+
+    /// Maps a reference in this system onto the same text in `other`.
+    pub fn map(&self, reference: &Reference, other: &Versification) -> Reference {
+        other.from_original(&self.to_original(reference))
+    }
+
+    pub fn map_range(&self, range: &RefRange, other: &Versification) -> RefRange {
+        RefRange::new(self.map(&range.start, other), self.map(&range.end, other))
+    }
+
+    fn add_book(&mut self, line: &str) -> Result<(), ()> {
+        let mut parts = line.split_whitespace();
+        let book = parts.next().and_then(to_book_identifier).ok_or(())?;
+        let chapters = self.books.entry(book).or_default();
+        for part in parts {
+            let (chapter, verse) = part.split_once(':').ok_or(())?;
+            let chapter: usize = chapter.parse().map_err(|_| ())?;
+            let verse = verse.parse().map_err(|_| ())?;
+            if chapter == 0 {
+                return Err(());
+            }
+            if chapters.len() < chapter {
+                chapters.resize(chapter, None);
+            }
+            chapters[chapter - 1] = Some(verse);
+        }
+        Ok(())
+    }
+
+    fn add_excluded(&mut self, verse: &str) -> Result<(), ()> {
+        let verse = to_verse(&verse.parse().map_err(|_| ())?).ok_or(())?;
+        self.excluded.insert(verse);
+        Ok(())
+    }
+
+    fn add_mapping(&mut self, from: &str, to: &str) -> Result<(), ()> {
+        let from = expand(&from.parse().map_err(|_| ())?).ok_or(())?;
+        let to = expand(&to.parse().map_err(|_| ())?).ok_or(())?;
+        let last = *to.last().ok_or(())?;
+        // Extra verses on the left all map to the last verse on the right.
+        for (i, verse) in from.into_iter().enumerate() {
+            let original = to.get(i).copied().unwrap_or(last);
+            self.to_original.insert(verse, original);
+            self.from_original.entry(original).or_insert(verse);
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Versification {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+/// Reads the name from a `# Versification "English"` comment.
+fn to_name(line: &str) -> Option<&str> {
+    let rest = line.trim().strip_prefix('#')?.trim_start();
+    let rest = rest.strip_prefix("Versification")?.trim();
+    Some(rest.trim_matches('"'))
+}
+
+fn to_verse(reference: &Reference) -> Option<Verse> {
+    let verse = reference.verse.as_ref()?;
+    Some((reference.book?, reference.chapter?, verse.start))
+}
+
+/// Lists the verses of a range within one chapter.
+fn expand(range: &RefRange) -> Option<Vec<Verse>> {
+    let (book, chapter, start) = to_verse(&range.start)?;
+    let (end_book, end_chapter, _) = to_verse(&range.end)?;
+    if (book, chapter) != (end_book, end_chapter) {
+        return None;
+    }
+    let end = range.end.verse.as_ref()?.end;
+    Some((start..=end).map(|verse| (book, chapter, verse)).collect())
+}
+
+/// Maps the first and last verse of a reference, keeping a verse range only if both ends are
+/// still in the same chapter.
+fn map_reference(reference: &Reference, map: impl Fn(Verse) -> Verse) -> Reference {
+    let (Some(book), Some(chapter), Some(verse)) =
+        (reference.book, reference.chapter, &reference.verse)
+    else {
+        return reference.clone();
+    };
+    let (mapped_book, mapped_chapter, start) = map((book, chapter, verse.start));
+    let mut mapped = VerseNumber {
+        start,
+        start_segment: verse.start_segment,
+        end: start,
+        end_segment: verse.start_segment,
+        spelling: None,
+    };
+    if verse.is_range() {
+        let (end_book, end_chapter, end) = map((book, chapter, verse.end));
+        if (end_book, end_chapter) == (mapped_book, mapped_chapter) && end >= start {
+            mapped.end = end;
+            mapped.end_segment = verse.end_segment;
+        }
+    }
+    Reference::verse(mapped_book, mapped_chapter, mapped)
+}
@@ -0,0 +1,168 @@
+//! A hierarchical view of a [`Book`]: its header, titles, introduction and chapters, with the
+//! chapters divided into sections.
+
+use crate::usfm::{Book, BookContents, Element, ElementKind, ElementType};
+
+/// The structure implied by the flat contents of a book, borrowing its nodes.
+///
+/// Everything before the first chapter goes into the header, the titles or the introduction.
+/// Each chapter is divided into sections at its `\ms` and `\s` headings; the contents before
+/// the first heading of a chapter form a section without headings.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BookView<'a> {
+    header: Vec<&'a BookContents>,
+    titles: Vec<&'a Element>,
+    intro: Vec<&'a BookContents>,
+    chapters: Vec<ChapterView<'a>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChapterView<'a> {
+    pub number: u16,
+    pub sections: Vec<SectionView<'a>>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SectionView<'a> {
+    /// The headings the section starts with, such as `\ms`, `\mr`, `\s`, `\sr` and `\r`.
+    pub headings: Vec<&'a Element>,
+    pub contents: Vec<&'a BookContents>,
+}
+
+impl<'a> BookView<'a> {
+    pub fn new(book: &'a Book) -> Self {
+        let mut view = Self::default();
+        for content in &book.contents {
+            use BookContents as C;
+            match (content, view.chapters.last_mut()) {
+                (C::Chapter(number), _) => view.chapters.push(ChapterView {
+                    number: *number,
+                    sections: Vec::new(),
+                }),
+                (_, Some(chapter)) => chapter.push(content),
+                (C::Id { .. } | C::Usfm(_) | C::Encoding(_) | C::Status(_), None) => {
+                    view.header.push(content)
+                }
+                (C::Element(e), None) if is_header(&e.ty) => view.header.push(content),
+                (C::Element(e), None) if is_title(&e.ty) => view.titles.push(e),
+                (_, None) => view.intro.push(content),
+            }
+        }
+        view
+    }
+
+    /// The identification and header markers: `\id`, `\usfm`, `\ide`, `\sts`, `\rem`, `\h` and
+    /// the `\toc` markers.
+    pub fn header(&self) -> &[&'a BookContents] {
+        &self.header
+    }
+
+    /// The main titles (`\mt`, `\mte`) before the first chapter.
+    pub fn titles(&self) -> &[&'a Element] {
+        &self.titles
+    }
+
+    /// Everything else before the first chapter, normally `\imt` to `\ie`.
+    pub fn intro(&self) -> &[&'a BookContents] {
+        &self.intro
+    }
+
+    pub fn chapters(&self) -> &[ChapterView<'a>] {
+        &self.chapters
+    }
+
+    pub fn chapter(&self, number: u16) -> Option<&ChapterView<'a>> {
+        self.chapters.iter().find(|c| c.number == number)
+    }
+
+    /// The sections of every chapter, in order.
+    pub fn sections(&self) -> impl Iterator<Item = &SectionView<'a>> {
+        self.chapters.iter().flat_map(|c| &c.sections)
+    }
+}
+
+impl<'a> ChapterView<'a> {
+    fn push(&mut self, content: &'a BookContents) {
+        if let BookContents::Element(e) = content
+            && is_heading(&e.ty)
+        {
+            let open = self
+                .sections
+                .last_mut()
+                .filter(|s| s.contents.is_empty() && !s.headings.is_empty());
+            match open {
+                Some(section) => {
+                    section.headings.push(e);
+                    return;
+                }
+                None if is_section(&e.ty) => {
+                    self.sections.push(SectionView {
+                        headings: vec![e],
+                        contents: Vec::new(),
+                    });
+                    return;
+                }
+                // A reference heading outside a section's headings is ordinary content.
+                None => {}
+            }
+        }
+        match self.sections.last_mut() {
+            Some(section) => section.contents.push(content),
+            None => self.sections.push(SectionView {
+                headings: Vec::new(),
+                contents: vec![content],
+            }),
+        }
+    }
+}
+
+impl<'a> SectionView<'a> {
+    /// The first heading of the section, if it has any.
+    pub fn heading(&self) -> Option<&'a Element> {
+        self.headings.first().copied()
+    }
+}
+
+impl Book {
+    pub fn view(&self) -> BookView<'_> {
+        BookView::new(self)
+    }
+}
+
+fn is_header(ty: &ElementType) -> bool {
+    matches!(
+        ty,
+        ElementType::Remark
+            | ElementType::Header
+            | ElementType::Contents(_)
+            | ElementType::AltContents(_)
+    )
+}
+
+fn is_title(ty: &ElementType) -> bool {
+    matches!(
+        ty,
+        ElementType::MajorTitle(_)
+            | ElementType::MajorTitleEnding(_)
+            | ElementType::Custom(_, ElementKind::Title)
+    )
+}
+
+/// Whether an element starts a section.
+fn is_section(ty: &ElementType) -> bool {
+    matches!(
+        ty,
+        ElementType::MajorSection(_)
+            | ElementType::Section(_)
+            | ElementType::Custom(_, ElementKind::Section)
+    )
+}
+
+/// Whether an element belongs to the headings of a section.
+fn is_heading(ty: &ElementType) -> bool {
+    is_section(ty)
+        || matches!(
+            ty,
+            ElementType::MajorReference | ElementType::Reference | ElementType::Parallel
+        )
+}
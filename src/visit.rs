@@ -0,0 +1,451 @@
+//! Read-only traversal of a [`Book`](crate::Book).
+//!
+//! Each method of [`Visit`] visits one type of node, and by default walks into its children by
+//! calling the free function of the same name. Override the methods for the nodes of interest,
+//! calling the free function to keep walking into their children:
+//!
+//! ```
+//! use usfm::visit::{self, Visit};
+//! use usfm::Footnote;
+//!
+//! struct Footnotes(usize);
+//!
+//! impl<'ast> Visit<'ast> for Footnotes {
+//!     fn visit_footnote(&mut self, node: &'ast Footnote) {
+//!         self.0 += 1;
+//!         visit::visit_footnote(self, node);
+//!     }
+//! }
+//!
+//! let book = usfm::parse("\\id GEN\n\\p\n\\v 1 In\\f + \\ft Or, At\\f* the beginning.\n").unwrap();
+//! let mut footnotes = Footnotes(0);
+//! footnotes.visit_book(&book);
+//! assert_eq!(footnotes.0, 1);
+//! ```
+//!
+//! The same traversal over archived books is in [`archived`].
+
+pub mod archived;
+
+macro_rules! visit {
+    (
+        Book = $Book:ident,
+        BookContents = $BookContents:ident,
+        Paragraph = $Paragraph:ident,
+        Poetry = $Poetry:ident,
+        Element = $Element:ident,
+        TableRow = $TableRow:ident,
+        TableCell = $TableCell:ident,
+        Sidebar = $Sidebar:ident,
+        SidebarContents = $SidebarContents:ident,
+        CustomParagraph = $CustomParagraph:ident,
+        ParagraphContents = $ParagraphContents:ident,
+        ElementContents = $ElementContents:ident,
+        CharacterContents = $CharacterContents:ident,
+        Character = $Character:ident,
+        Footnote = $Footnote:ident,
+        FootnoteElement = $FootnoteElement:ident,
+        CrossRef = $CrossRef:ident,
+        CrossRefElement = $CrossRefElement:ident,
+        Figure = $Figure:ident,
+        Milestone = $Milestone:ident,
+        Custom = $Custom:ident,
+        VerseNumber = $VerseNumber:ident,
+        BookIdentifier = $BookIdentifier:ident,
+        BookEncoding = $BookEncoding:ident,
+        EmptyType = $EmptyType:ident $(,)?
+    ) => {
+        pub trait Visit<'ast> {
+            fn visit_book(&mut self, node: &'ast $Book) {
+                visit_book(self, node)
+            }
+
+            fn visit_book_contents(&mut self, node: &'ast $BookContents) {
+                visit_book_contents(self, node)
+            }
+
+            /// Visits an `\\id` marker and the text after its book code.
+            fn visit_id(&mut self, code: &'ast $BookIdentifier, text: Option<&'ast str>) {
+                visit_id(self, code, text)
+            }
+
+            fn visit_usfm(&mut self, version: &'ast str) {
+                visit_usfm(self, version)
+            }
+
+            fn visit_encoding(&mut self, node: &'ast $BookEncoding) {
+                visit_encoding(self, node)
+            }
+
+            fn visit_status(&mut self, status: u16) {
+                visit_status(self, status)
+            }
+
+            fn visit_chapter(&mut self, number: u16) {
+                visit_chapter(self, number)
+            }
+
+            fn visit_alt_chapter(&mut self, number: u16) {
+                visit_alt_chapter(self, number)
+            }
+
+            fn visit_empty(&mut self, node: &'ast $EmptyType) {
+                visit_empty(self, node)
+            }
+
+            fn visit_peripheral(&mut self, title: &'ast str) {
+                visit_peripheral(self, title)
+            }
+
+            fn visit_paragraph(&mut self, node: &'ast $Paragraph) {
+                visit_paragraph(self, node)
+            }
+
+            fn visit_poetry(&mut self, node: &'ast $Poetry) {
+                visit_poetry(self, node)
+            }
+
+            fn visit_element(&mut self, node: &'ast $Element) {
+                visit_element(self, node)
+            }
+
+            fn visit_table_row(&mut self, node: &'ast $TableRow) {
+                visit_table_row(self, node)
+            }
+
+            fn visit_table_cell(&mut self, node: &'ast $TableCell) {
+                visit_table_cell(self, node)
+            }
+
+            fn visit_sidebar(&mut self, node: &'ast $Sidebar) {
+                visit_sidebar(self, node)
+            }
+
+            fn visit_sidebar_contents(&mut self, node: &'ast $SidebarContents) {
+                visit_sidebar_contents(self, node)
+            }
+
+            fn visit_custom_paragraph(&mut self, node: &'ast $CustomParagraph) {
+                visit_custom_paragraph(self, node)
+            }
+
+            fn visit_paragraph_contents(&mut self, node: &'ast $ParagraphContents) {
+                visit_paragraph_contents(self, node)
+            }
+
+            fn visit_element_contents(&mut self, node: &'ast $ElementContents) {
+                visit_element_contents(self, node)
+            }
+
+            fn visit_character_contents(&mut self, node: &'ast $CharacterContents) {
+                visit_character_contents(self, node)
+            }
+
+            fn visit_character(&mut self, node: &'ast $Character) {
+                visit_character(self, node)
+            }
+
+            fn visit_footnote(&mut self, node: &'ast $Footnote) {
+                visit_footnote(self, node)
+            }
+
+            fn visit_footnote_element(&mut self, node: &'ast $FootnoteElement) {
+                visit_footnote_element(self, node)
+            }
+
+            fn visit_cross_ref(&mut self, node: &'ast $CrossRef) {
+                visit_cross_ref(self, node)
+            }
+
+            fn visit_cross_ref_element(&mut self, node: &'ast $CrossRefElement) {
+                visit_cross_ref_element(self, node)
+            }
+
+            fn visit_figure(&mut self, node: &'ast $Figure) {
+                visit_figure(self, node)
+            }
+
+            fn visit_milestone(&mut self, node: &'ast $Milestone) {
+                visit_milestone(self, node)
+            }
+
+            fn visit_custom(&mut self, node: &'ast $Custom) {
+                visit_custom(self, node)
+            }
+
+            fn visit_verse(&mut self, node: &'ast $VerseNumber) {
+                visit_verse(self, node)
+            }
+
+            fn visit_category(&mut self, category: &'ast str) {
+                visit_category(self, category)
+            }
+
+            fn visit_optional_break(&mut self) {
+                visit_optional_break(self)
+            }
+
+            /// Visits the text of a paragraph, element or character style.
+            fn visit_text(&mut self, text: &'ast str) {
+                visit_text(self, text)
+            }
+        }
+
+        pub fn visit_book<'ast, V: Visit<'ast> + ?Sized>(v: &mut V, node: &'ast $Book) {
+            for content in node.contents.iter() {
+                v.visit_book_contents(content);
+            }
+        }
+
+        pub fn visit_book_contents<'ast, V: Visit<'ast> + ?Sized>(
+            v: &mut V,
+            node: &'ast $BookContents,
+        ) {
+            match node {
+                $BookContents::Id { code, text } => {
+                    v.visit_id(code, text.as_ref().map(|t| t.as_str()))
+                }
+                $BookContents::Usfm(version) => v.visit_usfm(version.as_str()),
+                $BookContents::Encoding(encoding) => v.visit_encoding(encoding),
+                $BookContents::Status(status) => v.visit_status(u16::from(*status)),
+                $BookContents::Chapter(number) => v.visit_chapter(u16::from(*number)),
+                $BookContents::AltChapter(number) => v.visit_alt_chapter(u16::from(*number)),
+                $BookContents::Paragraph(p) => v.visit_paragraph(p),
+                $BookContents::Poetry(p) => v.visit_poetry(p),
+                $BookContents::Element(e) => v.visit_element(e),
+                $BookContents::Empty(ty) => v.visit_empty(ty),
+                $BookContents::TableRow(tr) => v.visit_table_row(tr),
+                $BookContents::Sidebar(sb) => v.visit_sidebar(sb),
+                $BookContents::Peripheral(title) => v.visit_peripheral(title.as_str()),
+                $BookContents::Figure(fig) => v.visit_figure(fig),
+                $BookContents::Custom(p) => v.visit_custom_paragraph(p),
+            }
+        }
+
+        pub fn visit_id<'ast, V: Visit<'ast> + ?Sized>(
+            _v: &mut V,
+            _code: &'ast $BookIdentifier,
+            _text: Option<&'ast str>,
+        ) {
+        }
+
+        pub fn visit_usfm<'ast, V: Visit<'ast> + ?Sized>(_v: &mut V, _version: &'ast str) {}
+
+        pub fn visit_encoding<'ast, V: Visit<'ast> + ?Sized>(
+            _v: &mut V,
+            _node: &'ast $BookEncoding,
+        ) {
+        }
+
+        pub fn visit_status<'ast, V: Visit<'ast> + ?Sized>(_v: &mut V, _status: u16) {}
+
+        pub fn visit_chapter<'ast, V: Visit<'ast> + ?Sized>(_v: &mut V, _number: u16) {}
+
+        pub fn visit_alt_chapter<'ast, V: Visit<'ast> + ?Sized>(_v: &mut V, _number: u16) {}
+
+        pub fn visit_empty<'ast, V: Visit<'ast> + ?Sized>(_v: &mut V, _node: &'ast $EmptyType) {}
+
+        pub fn visit_peripheral<'ast, V: Visit<'ast> + ?Sized>(_v: &mut V, _title: &'ast str) {}
+
+        pub fn visit_paragraph<'ast, V: Visit<'ast> + ?Sized>(v: &mut V, node: &'ast $Paragraph) {
+            for content in node.contents.iter() {
+                v.visit_paragraph_contents(content);
+            }
+        }
+
+        pub fn visit_poetry<'ast, V: Visit<'ast> + ?Sized>(v: &mut V, node: &'ast $Poetry) {
+            for content in node.contents.iter() {
+                v.visit_paragraph_contents(content);
+            }
+        }
+
+        pub fn visit_element<'ast, V: Visit<'ast> + ?Sized>(v: &mut V, node: &'ast $Element) {
+            for content in node.contents.iter() {
+                v.visit_element_contents(content);
+            }
+        }
+
+        pub fn visit_table_row<'ast, V: Visit<'ast> + ?Sized>(v: &mut V, node: &'ast $TableRow) {
+            for cell in node.cells.iter() {
+                v.visit_table_cell(cell);
+            }
+        }
+
+        pub fn visit_table_cell<'ast, V: Visit<'ast> + ?Sized>(v: &mut V, node: &'ast $TableCell) {
+            for content in node.contents.iter() {
+                v.visit_paragraph_contents(content);
+            }
+        }
+
+        pub fn visit_sidebar<'ast, V: Visit<'ast> + ?Sized>(v: &mut V, node: &'ast $Sidebar) {
+            for content in node.contents.iter() {
+                v.visit_sidebar_contents(content);
+            }
+        }
+
+        pub fn visit_sidebar_contents<'ast, V: Visit<'ast> + ?Sized>(
+            v: &mut V,
+            node: &'ast $SidebarContents,
+        ) {
+            match node {
+                $SidebarContents::Paragraph(p) => v.visit_paragraph(p),
+                $SidebarContents::Poetry(p) => v.visit_poetry(p),
+                $SidebarContents::Element(e) => v.visit_element(e),
+                $SidebarContents::TableRow(tr) => v.visit_table_row(tr),
+                $SidebarContents::Empty(ty) => v.visit_empty(ty),
+                $SidebarContents::Category(category) => v.visit_category(category.as_str()),
+            }
+        }
+
+        pub fn visit_custom_paragraph<'ast, V: Visit<'ast> + ?Sized>(
+            v: &mut V,
+            node: &'ast $CustomParagraph,
+        ) {
+            for content in node.contents.iter() {
+                v.visit_paragraph_contents(content);
+            }
+        }
+
+        pub fn visit_paragraph_contents<'ast, V: Visit<'ast> + ?Sized>(
+            v: &mut V,
+            node: &'ast $ParagraphContents,
+        ) {
+            match node {
+                $ParagraphContents::Verse(verse) => v.visit_verse(verse),
+                $ParagraphContents::Line(text) => v.visit_text(text.as_str()),
+                $ParagraphContents::Character(c) => v.visit_character(c),
+                $ParagraphContents::Footnote(f) => v.visit_footnote(f),
+                $ParagraphContents::CrossRef(x) => v.visit_cross_ref(x),
+                $ParagraphContents::Figure(fig) => v.visit_figure(fig),
+                $ParagraphContents::Milestone(ms) => v.visit_milestone(ms),
+                $ParagraphContents::Custom(c) => v.visit_custom(c),
+                $ParagraphContents::Category(category) => v.visit_category(category.as_str()),
+                $ParagraphContents::OptionalBreak => v.visit_optional_break(),
+            }
+        }
+
+        pub fn visit_element_contents<'ast, V: Visit<'ast> + ?Sized>(
+            v: &mut V,
+            node: &'ast $ElementContents,
+        ) {
+            match node {
+                $ElementContents::Line(text) => v.visit_text(text.as_str()),
+                $ElementContents::Character(c) => v.visit_character(c),
+                $ElementContents::Footnote(f) => v.visit_footnote(f),
+                $ElementContents::CrossRef(x) => v.visit_cross_ref(x),
+                $ElementContents::Figure(fig) => v.visit_figure(fig),
+                $ElementContents::Milestone(ms) => v.visit_milestone(ms),
+                $ElementContents::Custom(c) => v.visit_custom(c),
+                $ElementContents::Category(category) => v.visit_category(category.as_str()),
+                $ElementContents::OptionalBreak => v.visit_optional_break(),
+            }
+        }
+
+        pub fn visit_character_contents<'ast, V: Visit<'ast> + ?Sized>(
+            v: &mut V,
+            node: &'ast $CharacterContents,
+        ) {
+            match node {
+                $CharacterContents::Line(text) => v.visit_text(text.as_str()),
+                $CharacterContents::Character(c) => v.visit_character(c),
+                $CharacterContents::Footnote(f) => v.visit_footnote(f),
+                $CharacterContents::CrossRef(x) => v.visit_cross_ref(x),
+                $CharacterContents::Figure(fig) => v.visit_figure(fig),
+                $CharacterContents::Milestone(ms) => v.visit_milestone(ms),
+                $CharacterContents::Custom(c) => v.visit_custom(c),
+                $CharacterContents::OptionalBreak => v.visit_optional_break(),
+            }
+        }
+
+        pub fn visit_character<'ast, V: Visit<'ast> + ?Sized>(v: &mut V, node: &'ast $Character) {
+            for content in node.contents.iter() {
+                v.visit_character_contents(content);
+            }
+        }
+
+        pub fn visit_footnote<'ast, V: Visit<'ast> + ?Sized>(v: &mut V, node: &'ast $Footnote) {
+            for element in node.elements.iter() {
+                v.visit_footnote_element(element);
+            }
+        }
+
+        pub fn visit_footnote_element<'ast, V: Visit<'ast> + ?Sized>(
+            v: &mut V,
+            node: &'ast $FootnoteElement,
+        ) {
+            for content in node.contents.iter() {
+                v.visit_character_contents(content);
+            }
+        }
+
+        pub fn visit_cross_ref<'ast, V: Visit<'ast> + ?Sized>(v: &mut V, node: &'ast $CrossRef) {
+            for element in node.elements.iter() {
+                v.visit_cross_ref_element(element);
+            }
+        }
+
+        pub fn visit_cross_ref_element<'ast, V: Visit<'ast> + ?Sized>(
+            v: &mut V,
+            node: &'ast $CrossRefElement,
+        ) {
+            for content in node.contents.iter() {
+                v.visit_character_contents(content);
+            }
+        }
+
+        pub fn visit_figure<'ast, V: Visit<'ast> + ?Sized>(v: &mut V, node: &'ast $Figure) {
+            for content in node.contents.iter() {
+                v.visit_character_contents(content);
+            }
+        }
+
+        pub fn visit_milestone<'ast, V: Visit<'ast> + ?Sized>(_v: &mut V, _node: &'ast $Milestone) {
+        }
+
+        pub fn visit_custom<'ast, V: Visit<'ast> + ?Sized>(v: &mut V, node: &'ast $Custom) {
+            for content in node.contents.iter() {
+                v.visit_character_contents(content);
+            }
+        }
+
+        pub fn visit_verse<'ast, V: Visit<'ast> + ?Sized>(_v: &mut V, _node: &'ast $VerseNumber) {}
+
+        pub fn visit_category<'ast, V: Visit<'ast> + ?Sized>(_v: &mut V, _category: &'ast str) {}
+
+        pub fn visit_optional_break<'ast, V: Visit<'ast> + ?Sized>(_v: &mut V) {}
+
+        pub fn visit_text<'ast, V: Visit<'ast> + ?Sized>(_v: &mut V, _text: &'ast str) {}
+    };
+}
+
+use visit;
+
+use crate::usfm::*;
+
+visit! {
+    Book = Book,
+    BookContents = BookContents,
+    Paragraph = Paragraph,
+    Poetry = Poetry,
+    Element = Element,
+    TableRow = TableRow,
+    TableCell = TableCell,
+    Sidebar = Sidebar,
+    SidebarContents = SidebarContents,
+    CustomParagraph = CustomParagraph,
+    ParagraphContents = ParagraphContents,
+    ElementContents = ElementContents,
+    CharacterContents = CharacterContents,
+    Character = Character,
+    Footnote = Footnote,
+    FootnoteElement = FootnoteElement,
+    CrossRef = CrossRef,
+    CrossRefElement = CrossRefElement,
+    Figure = Figure,
+    Milestone = Milestone,
+    Custom = Custom,
+    VerseNumber = VerseNumber,
+    BookIdentifier = BookIdentifier,
+    BookEncoding = BookEncoding,
+    EmptyType = EmptyType,
+}
@@ -0,0 +1,34 @@
+//! Read-only traversal of an archived book, without deserializing it.
+//!
+//! This is the same as [`Visit`](super::Visit), over the `Archived*` types.
+
+use super::visit;
+use crate::usfm::*;
+
+visit! {
+    Book = ArchivedBook,
+    BookContents = ArchivedBookContents,
+    Paragraph = ArchivedParagraph,
+    Poetry = ArchivedPoetry,
+    Element = ArchivedElement,
+    TableRow = ArchivedTableRow,
+    TableCell = ArchivedTableCell,
+    Sidebar = ArchivedSidebar,
+    SidebarContents = ArchivedSidebarContents,
+    CustomParagraph = ArchivedCustomParagraph,
+    ParagraphContents = ArchivedParagraphContents,
+    ElementContents = ArchivedElementContents,
+    CharacterContents = ArchivedCharacterContents,
+    Character = ArchivedCharacter,
+    Footnote = ArchivedFootnote,
+    FootnoteElement = ArchivedFootnoteElement,
+    CrossRef = ArchivedCrossRef,
+    CrossRefElement = ArchivedCrossRefElement,
+    Figure = ArchivedFigure,
+    Milestone = ArchivedMilestone,
+    Custom = ArchivedCustom,
+    VerseNumber = ArchivedVerseNumber,
+    BookIdentifier = ArchivedBookIdentifier,
+    BookEncoding = ArchivedBookEncoding,
+    EmptyType = ArchivedEmptyType,
+}
@@ -0,0 +1,361 @@
+//! Traversal of a [`Book`] that may change its nodes in place.
+//!
+//! This works like [`Visit`](crate::visit::Visit), with methods and free functions suffixed
+//! with `_mut`:
+//!
+//! ```
+//! use usfm::visit_mut::{self, VisitMut};
+//! use usfm::{Character, CharacterType};
+//!
+//! struct Unbold;
+//!
+//! impl VisitMut for Unbold {
+//!     fn visit_character_mut(&mut self, node: &mut Character) {
+//!         if node.ty == CharacterType::Bold {
+//!             node.ty = CharacterType::Italic;
+//!         }
+//!         visit_mut::visit_character_mut(self, node);
+//!     }
+//! }
+//!
+//! let mut book = usfm::parse("\\id GEN\n\\p\n\\v 1 In \\bd the\\bd* beginning.\n").unwrap();
+//! Unbold.visit_book_mut(&mut book);
+//! assert!(usfm::format(&book).contains("\\it the\\it*"));
+//! ```
+
+use crate::usfm::*;
+
+pub trait VisitMut {
+    fn visit_book_mut(&mut self, node: &mut Book) {
+        visit_book_mut(self, node)
+    }
+
+    fn visit_book_contents_mut(&mut self, node: &mut BookContents) {
+        visit_book_contents_mut(self, node)
+    }
+
+    /// Visits an `\id` marker and the text after its book code.
+    fn visit_id_mut(&mut self, code: &mut BookIdentifier, text: &mut Option<String>) {
+        visit_id_mut(self, code, text)
+    }
+
+    fn visit_usfm_mut(&mut self, version: &mut String) {
+        visit_usfm_mut(self, version)
+    }
+
+    fn visit_encoding_mut(&mut self, node: &mut BookEncoding) {
+        visit_encoding_mut(self, node)
+    }
+
+    fn visit_status_mut(&mut self, status: &mut u16) {
+        visit_status_mut(self, status)
+    }
+
+    fn visit_chapter_mut(&mut self, number: &mut u16) {
+        visit_chapter_mut(self, number)
+    }
+
+    fn visit_alt_chapter_mut(&mut self, number: &mut u16) {
+        visit_alt_chapter_mut(self, number)
+    }
+
+    fn visit_empty_mut(&mut self, node: &mut EmptyType) {
+        visit_empty_mut(self, node)
+    }
+
+    fn visit_peripheral_mut(&mut self, title: &mut String) {
+        visit_peripheral_mut(self, title)
+    }
+
+    fn visit_paragraph_mut(&mut self, node: &mut Paragraph) {
+        visit_paragraph_mut(self, node)
+    }
+
+    fn visit_poetry_mut(&mut self, node: &mut Poetry) {
+        visit_poetry_mut(self, node)
+    }
+
+    fn visit_element_mut(&mut self, node: &mut Element) {
+        visit_element_mut(self, node)
+    }
+
+    fn visit_table_row_mut(&mut self, node: &mut TableRow) {
+        visit_table_row_mut(self, node)
+    }
+
+    fn visit_table_cell_mut(&mut self, node: &mut TableCell) {
+        visit_table_cell_mut(self, node)
+    }
+
+    fn visit_sidebar_mut(&mut self, node: &mut Sidebar) {
+        visit_sidebar_mut(self, node)
+    }
+
+    fn visit_sidebar_contents_mut(&mut self, node: &mut SidebarContents) {
+        visit_sidebar_contents_mut(self, node)
+    }
+
+    fn visit_custom_paragraph_mut(&mut self, node: &mut CustomParagraph) {
+        visit_custom_paragraph_mut(self, node)
+    }
+
+    fn visit_paragraph_contents_mut(&mut self, node: &mut ParagraphContents) {
+        visit_paragraph_contents_mut(self, node)
+    }
+
+    fn visit_element_contents_mut(&mut self, node: &mut ElementContents) {
+        visit_element_contents_mut(self, node)
+    }
+
+    fn visit_character_contents_mut(&mut self, node: &mut CharacterContents) {
+        visit_character_contents_mut(self, node)
+    }
+
+    fn visit_character_mut(&mut self, node: &mut Character) {
+        visit_character_mut(self, node)
+    }
+
+    fn visit_footnote_mut(&mut self, node: &mut Footnote) {
+        visit_footnote_mut(self, node)
+    }
+
+    fn visit_footnote_element_mut(&mut self, node: &mut FootnoteElement) {
+        visit_footnote_element_mut(self, node)
+    }
+
+    fn visit_cross_ref_mut(&mut self, node: &mut CrossRef) {
+        visit_cross_ref_mut(self, node)
+    }
+
+    fn visit_cross_ref_element_mut(&mut self, node: &mut CrossRefElement) {
+        visit_cross_ref_element_mut(self, node)
+    }
+
+    fn visit_figure_mut(&mut self, node: &mut Figure) {
+        visit_figure_mut(self, node)
+    }
+
+    fn visit_milestone_mut(&mut self, node: &mut Milestone) {
+        visit_milestone_mut(self, node)
+    }
+
+    fn visit_custom_mut(&mut self, node: &mut Custom) {
+        visit_custom_mut(self, node)
+    }
+
+    fn visit_verse_mut(&mut self, node: &mut VerseNumber) {
+        visit_verse_mut(self, node)
+    }
+
+    fn visit_category_mut(&mut self, category: &mut String) {
+        visit_category_mut(self, category)
+    }
+
+    fn visit_optional_break_mut(&mut self) {
+        visit_optional_break_mut(self)
+    }
+
+    /// Visits the text of a paragraph, element or character style.
+    fn visit_text_mut(&mut self, text: &mut String) {
+        visit_text_mut(self, text)
+    }
+}
+
+pub fn visit_book_mut<V: VisitMut + ?Sized>(v: &mut V, node: &mut Book) {
+    for content in &mut node.contents {
+        v.visit_book_contents_mut(content);
+    }
+}
+
+pub fn visit_book_contents_mut<V: VisitMut + ?Sized>(v: &mut V, node: &mut BookContents) {
+    use BookContents as C;
+    match node {
+        C::Id { code, text } => v.visit_id_mut(code, text),
+        C::Usfm(version) => v.visit_usfm_mut(version),
+        C::Encoding(encoding) => v.visit_encoding_mut(encoding),
+        C::Status(status) => v.visit_status_mut(status),
+        C::Chapter(number) => v.visit_chapter_mut(number),
+        C::AltChapter(number) => v.visit_alt_chapter_mut(number),
+        C::Paragraph(p) => v.visit_paragraph_mut(p),
+        C::Poetry(p) => v.visit_poetry_mut(p),
+        C::Element(e) => v.visit_element_mut(e),
+        C::Empty(ty) => v.visit_empty_mut(ty),
+        C::TableRow(tr) => v.visit_table_row_mut(tr),
+        C::Sidebar(sb) => v.visit_sidebar_mut(sb),
+        C::Peripheral(title) => v.visit_peripheral_mut(title),
+        C::Figure(fig) => v.visit_figure_mut(fig),
+        C::Custom(p) => v.visit_custom_paragraph_mut(p),
+    }
+}
+
+pub fn visit_id_mut<V: VisitMut + ?Sized>(
+    _v: &mut V,
+    _code: &mut BookIdentifier,
+    _text: &mut Option<String>,
+) {
+}
+
+pub fn visit_usfm_mut<V: VisitMut + ?Sized>(_v: &mut V, _version: &mut String) {}
+
+pub fn visit_encoding_mut<V: VisitMut + ?Sized>(_v: &mut V, _node: &mut BookEncoding) {}
+
+pub fn visit_status_mut<V: VisitMut + ?Sized>(_v: &mut V, _status: &mut u16) {}
+
+pub fn visit_chapter_mut<V: VisitMut + ?Sized>(_v: &mut V, _number: &mut u16) {}
+
+pub fn visit_alt_chapter_mut<V: VisitMut + ?Sized>(_v: &mut V, _number: &mut u16) {}
+
+pub fn visit_empty_mut<V: VisitMut + ?Sized>(_v: &mut V, _node: &mut EmptyType) {}
+
+pub fn visit_peripheral_mut<V: VisitMut + ?Sized>(_v: &mut V, _title: &mut String) {}
+
+pub fn visit_paragraph_mut<V: VisitMut + ?Sized>(v: &mut V, node: &mut Paragraph) {
+    for content in &mut node.contents {
+        v.visit_paragraph_contents_mut(content);
+    }
+}
+
+pub fn visit_poetry_mut<V: VisitMut + ?Sized>(v: &mut V, node: &mut Poetry) {
+    for content in &mut node.contents {
+        v.visit_paragraph_contents_mut(content);
+    }
+}
+
+pub fn visit_element_mut<V: VisitMut + ?Sized>(v: &mut V, node: &mut Element) {
+    for content in &mut node.contents {
+        v.visit_element_contents_mut(content);
+    }
+}
+
+pub fn visit_table_row_mut<V: VisitMut + ?Sized>(v: &mut V, node: &mut TableRow) {
+    for cell in &mut node.cells {
+        v.visit_table_cell_mut(cell);
+    }
+}
+
+pub fn visit_table_cell_mut<V: VisitMut + ?Sized>(v: &mut V, node: &mut TableCell) {
+    for content in &mut node.contents {
+        v.visit_paragraph_contents_mut(content);
+    }
+}
+
+pub fn visit_sidebar_mut<V: VisitMut + ?Sized>(v: &mut V, node: &mut Sidebar) {
+    for content in &mut node.contents {
+        v.visit_sidebar_contents_mut(content);
+    }
+}
+
+pub fn visit_sidebar_contents_mut<V: VisitMut + ?Sized>(v: &mut V, node: &mut SidebarContents) {
+    use SidebarContents as C;
+    match node {
+        C::Paragraph(p) => v.visit_paragraph_mut(p),
+        C::Poetry(p) => v.visit_poetry_mut(p),
+        C::Element(e) => v.visit_element_mut(e),
+        C::TableRow(tr) => v.visit_table_row_mut(tr),
+        C::Empty(ty) => v.visit_empty_mut(ty),
+        C::Category(category) => v.visit_category_mut(category),
+    }
+}
+
+pub fn visit_custom_paragraph_mut<V: VisitMut + ?Sized>(v: &mut V, node: &mut CustomParagraph) {
+    for content in &mut node.contents {
+        v.visit_paragraph_contents_mut(content);
+    }
+}
+
+pub fn visit_paragraph_contents_mut<V: VisitMut + ?Sized>(v: &mut V, node: &mut ParagraphContents) {
+    use ParagraphContents as C;
+    match node {
+        C::Verse(verse) => v.visit_verse_mut(verse),
+        C::Line(text) => v.visit_text_mut(text),
+        C::Character(c) => v.visit_character_mut(c),
+        C::Footnote(f) => v.visit_footnote_mut(f),
+        C::CrossRef(x) => v.visit_cross_ref_mut(x),
+        C::Figure(fig) => v.visit_figure_mut(fig),
+        C::Milestone(ms) => v.visit_milestone_mut(ms),
+        C::Custom(c) => v.visit_custom_mut(c),
+        C::Category(category) => v.visit_category_mut(category),
+        C::OptionalBreak => v.visit_optional_break_mut(),
+    }
+}
+
+pub fn visit_element_contents_mut<V: VisitMut + ?Sized>(v: &mut V, node: &mut ElementContents) {
+    use ElementContents as C;
+    match node {
+        C::Line(text) => v.visit_text_mut(text),
+        C::Character(c) => v.visit_character_mut(c),
+        C::Footnote(f) => v.visit_footnote_mut(f),
+        C::CrossRef(x) => v.visit_cross_ref_mut(x),
+        C::Figure(fig) => v.visit_figure_mut(fig),
+        C::Milestone(ms) => v.visit_milestone_mut(ms),
+        C::Custom(c) => v.visit_custom_mut(c),
+        C::Category(category) => v.visit_category_mut(category),
+        C::OptionalBreak => v.visit_optional_break_mut(),
+    }
+}
+
+pub fn visit_character_contents_mut<V: VisitMut + ?Sized>(v: &mut V, node: &mut CharacterContents) {
+    use CharacterContents as C;
+    match node {
+        C::Line(text) => v.visit_text_mut(text),
+        C::Character(c) => v.visit_character_mut(c),
+        C::Footnote(f) => v.visit_footnote_mut(f),
+        C::CrossRef(x) => v.visit_cross_ref_mut(x),
+        C::Figure(fig) => v.visit_figure_mut(fig),
+        C::Milestone(ms) => v.visit_milestone_mut(ms),
+        C::Custom(c) => v.visit_custom_mut(c),
+        C::OptionalBreak => v.visit_optional_break_mut(),
+    }
+}
+
+pub fn visit_character_mut<V: VisitMut + ?Sized>(v: &mut V, node: &mut Character) {
+    for content in &mut node.contents {
+        v.visit_character_contents_mut(content);
+    }
+}
+
+pub fn visit_footnote_mut<V: VisitMut + ?Sized>(v: &mut V, node: &mut Footnote) {
+    for element in &mut node.elements {
+        v.visit_footnote_element_mut(element);
+    }
+}
+
+pub fn visit_footnote_element_mut<V: VisitMut + ?Sized>(v: &mut V, node: &mut FootnoteElement) {
+    for content in &mut node.contents {
+        v.visit_character_contents_mut(content);
+    }
+}
+
+pub fn visit_cross_ref_mut<V: VisitMut + ?Sized>(v: &mut V, node: &mut CrossRef) {
+    for element in &mut node.elements {
+        v.visit_cross_ref_element_mut(element);
+    }
+}
+
+pub fn visit_cross_ref_element_mut<V: VisitMut + ?Sized>(v: &mut V, node: &mut CrossRefElement) {
+    for content in &mut node.contents {
+        v.visit_character_contents_mut(content);
+    }
+}
+
+pub fn visit_figure_mut<V: VisitMut + ?Sized>(v: &mut V, node: &mut Figure) {
+    for content in &mut node.contents {
+        v.visit_character_contents_mut(content);
+    }
+}
+
+pub fn visit_milestone_mut<V: VisitMut + ?Sized>(_v: &mut V, _node: &mut Milestone) {}
+
+pub fn visit_custom_mut<V: VisitMut + ?Sized>(v: &mut V, node: &mut Custom) {
+    for content in &mut node.contents {
+        v.visit_character_contents_mut(content);
+    }
+}
+
+pub fn visit_verse_mut<V: VisitMut + ?Sized>(_v: &mut V, _node: &mut VerseNumber) {}
+
+pub fn visit_category_mut<V: VisitMut + ?Sized>(_v: &mut V, _category: &mut String) {}
+
+pub fn visit_optional_break_mut<V: VisitMut + ?Sized>(_v: &mut V) {}
+
+pub fn visit_text_mut<V: VisitMut + ?Sized>(_v: &mut V, _text: &mut String) {}
@@ -0,0 +1,60 @@
+use usfm::{BookIdentifier::*, BookNameTable, NameStyle, RefList, RefRange, Reference, parse};
+
+fn table() -> BookNameTable {
+    let genesis = parse("\\id GEN\n\\toc1 Le livre de la Genèse\n\\toc2 Genèse\n\\toc3 Gén.\n\\toca3 Gn\n\\c 1\n\\p\n\\v 1 Au commencement\n").unwrap();
+    let exodus = parse("\\id EXO\n\\toc1 Exode\n\\toc2 Exode\n\\toc3 Ex\n").unwrap();
+    let john = parse("\\id JHN\n\\toc2 约翰福音\n\\toc3 约\n").unwrap();
+    BookNameTable::from_books([&genesis, &exodus, &john])
+}
+
+#[test]
+fn reads_names_from_books() {
+    let table = table();
+    let names = table.names(Genesis).unwrap();
+    assert_eq!(names.long.as_deref(), Some("Le livre de la Genèse"));
+    assert_eq!(names.get(NameStyle::Abbreviation), Some("Gén."));
+    assert_eq!(names.alternates, vec![(3, "Gn".to_string())]);
+    assert_eq!(table.names(Leviticus), None);
+}
+
+#[test]
+fn parses_localized_references() {
+    let table = table();
+    assert_eq!(
+        table.parse("Gén. 1:1-3; ex 3:14").unwrap(),
+        RefList(vec![
+            RefRange::new(
+                Reference::verse(Genesis, 1, 1),
+                Reference::verse(Genesis, 1, 3)
+            ),
+            Reference::verse(Exodus, 3, 14).into(),
+        ])
+    );
+    assert_eq!(
+        table.parse("约 3:16").unwrap(),
+        Reference::verse(John, 3, 16).into()
+    );
+    assert_eq!(
+        table.parse("Le livre de la Genèse 2").unwrap(),
+        table.parse("GEN 2").unwrap()
+    );
+    assert_eq!(table.parse("Gn 1:1").unwrap(), "GEN 1:1".parse().unwrap());
+    assert!(table.parse("Lévitique 1:1").is_err());
+    assert!("Gén 1:1".parse::<RefList>().is_err());
+}
+
+#[test]
+fn formats_with_localized_names() {
+    let table = table();
+    let references = table.parse("GEN 1:1,5; EXO 3:14; LEV 1:1").unwrap();
+    assert_eq!(
+        table.format(&references, NameStyle::Abbreviation),
+        "Gén. 1:1,5; Ex 3:14; LEV 1:1"
+    );
+    assert_eq!(
+        table.format(&references, NameStyle::Short),
+        "Genèse 1:1,5; Exode 3:14; LEV 1:1"
+    );
+    let round_trip = table.format(&references, NameStyle::Long);
+    assert_eq!(table.parse(&round_trip).unwrap(), references);
+}
@@ -0,0 +1,61 @@
+use std::fs::read_to_string;
+use usfm::{borrowed, parse, parse_borrowed};
+
+#[test]
+fn borrowed_into_owned_matches_parse() {
+    for file in [
+        "usfm/sample.usfm",
+        "usfm/02-GENeng-web.usfm",
+        "usfm/02-GENengwebpb.usfm",
+    ] {
+        let input = read_to_string(file).unwrap();
+        let book = parse_borrowed(&input).unwrap();
+        assert_eq!(book.into_owned(), parse(&input).unwrap(), "{file}");
+    }
+}
+
+#[test]
+fn borrowed_text_points_into_input() {
+    use borrowed::{BookContents as A, CharacterContents as C, ParagraphContents as B};
+    let input = "\\id GEN\n\\p\n\\v 1 In the \\w beginning|strong=\"H7225\"\\w*\n";
+    let book = parse_borrowed(input).unwrap();
+    let A::Paragraph(paragraph) = &book.contents[1] else {
+        panic!("expected paragraph, got {:?}", book.contents[1]);
+    };
+    let [B::Verse(verse), B::Line(line), B::Character(word)] = paragraph.contents.as_slice() else {
+        panic!("unexpected contents {:?}", paragraph.contents);
+    };
+    let [C::Line(word_text)] = word.contents.as_slice() else {
+        panic!("unexpected contents {:?}", word.contents);
+    };
+    let (key, value) = word.attributes[0];
+    for s in [*line, *word_text, key, value] {
+        let offset = s.as_ptr() as usize - input.as_ptr() as usize;
+        assert_eq!(&input[offset..offset + s.len()], s);
+    }
+    assert_eq!(*verse, 1.into());
+    assert_eq!((*line, *word_text), ("In the ", "beginning"));
+    assert_eq!((key, value), ("strong", "H7225"));
+}
+
+#[test]
+fn borrowed_names_point_into_input() {
+    use borrowed::{BookContents as A, FootnoteStyle, ParagraphContents as B};
+    let input = "\\id GEN\n\\p\n\\v 1\u{200F} In\\zf + \\ft Or, At\\zf* the beginning.\n";
+    let book = parse_borrowed(input).unwrap();
+    let A::Paragraph(paragraph) = &book.contents[1] else {
+        panic!("expected paragraph, got {:?}", book.contents[1]);
+    };
+    let [B::Verse(verse), _, B::Footnote(footnote), _] = paragraph.contents.as_slice() else {
+        panic!("unexpected contents {:?}", paragraph.contents);
+    };
+    let FootnoteStyle::Custom(marker) = footnote.style else {
+        panic!("expected custom footnote, got {:?}", footnote.style);
+    };
+    let spelling = verse.spelling.unwrap();
+    for s in [marker, spelling] {
+        let offset = s.as_ptr() as usize - input.as_ptr() as usize;
+        assert_eq!(&input[offset..offset + s.len()], s);
+    }
+    assert_eq!((marker, spelling), ("zf", "1\u{200F}"));
+}
@@ -0,0 +1,72 @@
+extern crate usfm;
+use std::{fs::read_to_string, path::PathBuf};
+use usfm::*;
+
+static MANIFEST_DIR: &str = env!("CARGO_MANIFEST_DIR");
+
+#[test]
+fn builds_web_genesis_opening() {
+    use FootnoteElementStyle::{Reference, Text};
+    let file = PathBuf::from(MANIFEST_DIR).join("usfm/02-GENeng-web.usfm");
+    let genesis = parse(&read_to_string(file).unwrap()).unwrap();
+
+    let built = BookBuilder::new(BookIdentifier::Genesis)
+        .chapter(1)
+        .p()
+        .verse(1)
+        .text("In the beginning, God")
+        .footnote(
+            Footnote::new(Caller::Auto)
+                .element(Reference, "1:1  ")
+                .element(Text, "The Hebrew word rendered \u{201c}God\u{201d} is \u{201c}\u{05d0}\u{05b1}\u{05dc}\u{05b9}\u{05d4}\u{05b4}\u{0591}\u{05d9}\u{05dd}\u{201d}\n(Elohim)."),
+        )
+        .text(" created the heavens and the earth.")
+        .verse(2)
+        .text("The earth was formless and empty. Darkness was on the surface of the deep and God\u{2019}s Spirit was hovering over the surface\nof the waters.")
+        .build();
+    assert_eq!(built.contents[1..], genesis.contents[9..11]);
+}
+
+#[test]
+fn places_content_in_the_last_block() {
+    let book = BookBuilder::new(BookIdentifier::Psalms)
+        .chapter(23)
+        .s("A Psalm of ")
+        .character(CharacterType::Proper, "David")
+        .verse(1)
+        .character(CharacterType::Deity, "Yahweh")
+        .text(" is my shepherd;")
+        .q(2)
+        .text("I shall lack nothing.")
+        .cross_ref(CrossRef::new(Caller::None).element(CrossRefElementStyle::Target, "John 10:11"))
+        .build();
+    let BookContents::Element(heading) = &book.contents[2] else {
+        panic!("expected a heading");
+    };
+    assert_eq!(heading.contents.len(), 2);
+    assert_eq!(
+        book.verses().next().unwrap().text,
+        "Yahweh is my shepherd; I shall lack nothing."
+    );
+    assert_eq!(parse(&format(&book)).unwrap(), book);
+}
+
+#[test]
+fn builds_verses_from_strings() {
+    let book = BookBuilder::new(BookIdentifier::Genesis)
+        .chapter(1)
+        .p()
+        .verse_str("1-2")
+        .unwrap()
+        .text("In the beginning.")
+        .build();
+    assert_eq!(
+        book.verses().next().unwrap().verse,
+        VerseNumber::try_from("1-2").unwrap()
+    );
+    assert!(
+        BookBuilder::new(BookIdentifier::Genesis)
+            .verse_str("one")
+            .is_err()
+    );
+}
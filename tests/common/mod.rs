@@ -0,0 +1,33 @@
+use std::{fs::read_to_string, path::PathBuf};
+
+pub static MANIFEST_DIR: &str = env!("CARGO_MANIFEST_DIR");
+
+/// Reads the sample `name` from `usfm/`.
+pub fn read(name: &str) -> String {
+    read_to_string(PathBuf::from(MANIFEST_DIR).join("usfm").join(name)).unwrap()
+}
+
+/// Checks that every `name` milestone with a `start` attribute is ended by one with a matching
+/// `end` before the next one starts, and gives how many there are.
+pub fn assert_milestones_pair(
+    doc: &roxmltree::Document,
+    name: &str,
+    start: &str,
+    end: &str,
+) -> usize {
+    let mut open: Option<&str> = None;
+    let mut count = 0;
+    for node in doc.descendants().filter(|n| n.has_tag_name(name)) {
+        match (node.attribute(start), node.attribute(end)) {
+            (Some(sid), None) => {
+                assert_eq!(open, None, "{name} {sid} starts before the last one ends");
+                open = Some(sid);
+                count += 1;
+            }
+            (None, Some(eid)) => assert_eq!(open.take(), Some(eid)),
+            _ => panic!("{name} without exactly one of {start} and {end}"),
+        }
+    }
+    assert_eq!(open, None);
+    count
+}
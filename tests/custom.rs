@@ -0,0 +1,104 @@
+use usfm::{
+    BookContents, CharacterContents, Custom, MilestoneBound, ParagraphContents, ParseErrorKind,
+    ParserConfig, UnknownMarkers, format, parse, parse_with_recovery,
+};
+
+const INPUT: &str = "\\id GEN\n\\c 1\n\\zpara Custom paragraph\n\\p\n\\v 1 \\zaln-s |x-strong=\"H7225\"\\*\\w In|strong=\"H7225\"\\w*\\zaln-e\\* the \\zfoo beginning \\+zbar nested\\+zbar*\\zfoo*.\n";
+
+#[test]
+fn preserves_unknown_markers() {
+    let book = parse(INPUT).unwrap();
+
+    let BookContents::Custom(paragraph) = &book.contents[2] else {
+        panic!("expected a custom paragraph, got {:?}", book.contents[2]);
+    };
+    assert_eq!(paragraph.marker, "zpara");
+    assert_eq!(
+        paragraph.contents,
+        vec![ParagraphContents::Line("Custom paragraph".into())]
+    );
+
+    let BookContents::Paragraph(p) = &book.contents[3] else {
+        panic!("expected a paragraph, got {:?}", book.contents[3]);
+    };
+    assert_eq!(
+        p.contents[1],
+        ParagraphContents::Custom(Custom {
+            marker: "zaln".into(),
+            milestone: Some(MilestoneBound::Start),
+            contents: vec![],
+            attributes: vec![("x-strong".into(), "H7225".into())],
+        })
+    );
+    assert!(matches!(
+        &p.contents[3],
+        ParagraphContents::Custom(Custom { marker, milestone: Some(MilestoneBound::End), .. })
+            if marker == "zaln"
+    ));
+    let ParagraphContents::Custom(span) = &p.contents[5] else {
+        panic!("expected a custom span, got {:?}", p.contents[5]);
+    };
+    assert_eq!(span.marker, "zfoo");
+    assert_eq!(span.milestone, None);
+    assert!(matches!(
+        &span.contents[1],
+        CharacterContents::Custom(Custom { marker, .. }) if marker == "zbar"
+    ));
+}
+
+#[test]
+fn unknown_markers_round_trip() {
+    let book = parse(INPUT).unwrap();
+    assert_eq!(parse(&format(&book)).unwrap(), book);
+}
+
+#[test]
+fn known_markers_are_not_custom() {
+    // A known marker in the wrong place is still an error rather than a custom marker.
+    assert!(parse("\\id GEN\n\\p Text \\h x\\h*\n").is_err());
+    assert!(parse("\\id GEN\n\\p Text \\fq x\\fq*\n").is_err());
+}
+
+#[test]
+fn strips_unknown_markers() {
+    let config = ParserConfig {
+        unknown_markers: UnknownMarkers::Strip,
+        ..Default::default()
+    };
+    let book = config.parse(INPUT).unwrap();
+    assert_eq!(book.contents.len(), 3);
+    let BookContents::Paragraph(p) = &book.contents[2] else {
+        panic!("expected a paragraph, got {:?}", book.contents[2]);
+    };
+    assert_eq!(p.contents.len(), 4);
+    assert!(matches!(p.contents[1], ParagraphContents::Character(_)));
+    assert_eq!(p.contents[2], ParagraphContents::Line(" the ".into()));
+    assert_eq!(p.contents[3], ParagraphContents::Line(".".into()));
+
+    let (_, source_map) = config.parse_with_spans(INPUT).unwrap();
+    assert_eq!(source_map.nodes().len(), book.contents.len());
+    assert_eq!(source_map.node(&[2]).unwrap().children.len(), 4);
+}
+
+#[test]
+fn rejects_unknown_markers() {
+    let config = ParserConfig {
+        unknown_markers: UnknownMarkers::Error,
+        ..Default::default()
+    };
+    let err = config.parse(INPUT).unwrap_err();
+    assert_eq!(
+        *err.kind,
+        ParseErrorKind::Unrecognized {
+            what: "marker",
+            value: "zpara".into()
+        }
+    );
+    assert_eq!((err.line, err.column), (3, 2));
+
+    let (book, diagnostics) = config.parse_with_recovery(INPUT);
+    assert_eq!(book.contents.len(), 2);
+    assert_eq!(diagnostics.len(), 2);
+    assert_eq!(diagnostics[1].error.line, 5);
+    assert_eq!(parse_with_recovery(INPUT).0, parse(INPUT).unwrap());
+}
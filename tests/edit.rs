@@ -0,0 +1,81 @@
+use usfm::{
+    BookIdentifier, Caller, CharacterType, Footnote, FootnoteElementStyle, Reference, format, parse,
+};
+
+const INPUT: &str = "\\id PSA
+\\c 23
+\\q1
+\\v 1 Yahweh is my \\nd shepherd\\nd*;
+\\q2 I shall lack nothing.
+\\q1
+\\v 2 He makes me lie down in green pastures.
+\\q2 He leads me beside still waters.
+\\q1
+\\v 3 He restores my soul.
+";
+
+fn verse(v: u16) -> Reference {
+    Reference::verse(BookIdentifier::Psalms, 23, v)
+}
+
+fn texts(book: &usfm::Book) -> Vec<String> {
+    book.verses().map(|v| v.text).collect()
+}
+
+#[test]
+fn replaces_and_deletes_verses_across_poetry_lines() {
+    let mut book = parse(INPUT).unwrap();
+    assert!(book.set_verse_text(&verse(2), "He lets me rest in green pastures."));
+    assert!(book.delete_verse(&verse(1)));
+    assert!(!book.delete_verse(&verse(4)));
+    assert_eq!(
+        texts(&book),
+        ["He lets me rest in green pastures.", "He restores my soul."]
+    );
+    assert_eq!(book.contents.len(), 4);
+    assert_eq!(parse(&format(&book)).unwrap(), book);
+}
+
+#[test]
+fn leaves_verses_of_other_books_alone() {
+    let mut book = parse(INPUT).unwrap();
+    let genesis = Reference::verse(BookIdentifier::Genesis, 23, 1);
+    let footnote = Footnote::new(Caller::Auto).element(FootnoteElementStyle::Text, "Or, LORD");
+    assert!(!book.set_verse_text(&genesis, "In the beginning"));
+    assert!(!book.delete_verse(&genesis));
+    assert!(!book.insert_footnote_at(&genesis, 0, footnote));
+    assert!(!book.wrap_in_character(&genesis, 0..6, CharacterType::Jesus));
+    assert_eq!(book, parse(INPUT).unwrap());
+
+    let mut unnamed = Reference::verse(BookIdentifier::Genesis, 23, 3);
+    unnamed.book = None;
+    assert!(book.delete_verse(&unnamed));
+}
+
+#[test]
+fn inserts_footnotes_and_wraps_text() {
+    let mut book = parse(INPUT).unwrap();
+    let footnote = Footnote::new(Caller::Auto).element(FootnoteElementStyle::Text, "Or, LORD");
+    assert!(book.insert_footnote_at(&verse(1), 6, footnote.clone()));
+    assert!(book.insert_footnote_at(&verse(1), 17, footnote.clone()));
+    assert!(!book.insert_footnote_at(&verse(1), 100, footnote));
+    assert!(
+        format(&book).contains("Yahweh\\f + \\ft Or, LORD\\f* is my \\nd shep\\nd*\\f + \\ft Or, LORD\\f*\\nd herd\\nd*;"),
+        "{}",
+        format(&book)
+    );
+
+    assert!(book.wrap_in_character(&verse(2), 12..47, CharacterType::Jesus));
+    assert!(!book.wrap_in_character(&verse(2), 11..200, CharacterType::Jesus));
+    let formatted = format(&book);
+    assert!(
+        formatted.contains("He makes me \\wj lie down in green pastures.\\wj*"),
+        "{formatted}"
+    );
+    assert!(formatted.contains("\\wj He leads\\wj* me"), "{formatted}");
+    assert_eq!(
+        texts(&book)[0],
+        "Yahweh is my shepherd; I shall lack nothing."
+    );
+    assert_eq!(parse(&formatted).unwrap().verses().count(), 3);
+}
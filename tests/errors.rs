@@ -0,0 +1,164 @@
+use std::error::Error;
+use usfm::{ParseErrorKind, parse};
+
+#[test]
+fn syntax_error_position() {
+    let input = "\\id GEN\n\\c 1\n\\p\n\\v 1 In the \\h beginning\\h*\n";
+    let err = parse(input).unwrap_err();
+    assert_eq!(*err.kind, ParseErrorKind::Syntax);
+    assert_eq!((err.line, err.column, err.offset), (4, 14, 29));
+    assert_eq!(err.marker.as_deref(), Some("h"));
+    assert_eq!(err.snippet, "\\v 1 In the \\h beginning\\h*");
+    assert!(err.expected.contains(&"character style name".to_string()));
+    assert!(
+        err.expected
+            .contains(&"footnote style (\\f, \\fe, \\ef)".to_string())
+    );
+}
+
+#[test]
+fn syntax_error_column_counts_characters() {
+    let input = "\\id GEN\n\\p \u{05d0}\u{05b1}\u{05dc} \\h x\\h*\n";
+    let err = parse(input).unwrap_err();
+    assert_eq!((err.line, err.column), (2, 9));
+    assert_eq!(&input[err.offset..err.offset + 1], "h");
+}
+
+#[test]
+fn syntax_error_render() {
+    let input = "\\id GEN\n\\p Text \\nd LORD\\nd* and \\h x\\h*\n";
+    let err = parse(input).unwrap_err();
+    let rendered = err.render("GEN.usfm");
+    let lines: Vec<_> = rendered.lines().collect();
+    assert!(lines[0].starts_with("error: expected "));
+    assert_eq!(lines[1], " --> GEN.usfm:2:27");
+    assert_eq!(lines[2], "  |");
+    assert_eq!(lines[3], "2 | \\p Text \\nd LORD\\nd* and \\h x\\h*");
+    assert_eq!(lines[4], format!("  | {}^ at `\\h`", " ".repeat(26)));
+}
+
+#[test]
+fn syntax_error_is_std_error() {
+    let err: Box<dyn Error> = Box::new(parse("\\id GEN\n\\v 1\n").unwrap_err());
+    assert!(err.to_string().starts_with("2:2: expected "));
+}
+
+fn assert_out_of_range(input: &str, value: &str, ty: &'static str, line: usize, column: usize) {
+    let err = parse(input).unwrap_err();
+    assert_eq!(
+        *err.kind,
+        ParseErrorKind::NumberOutOfRange {
+            value: value.into(),
+            ty
+        }
+    );
+    assert_eq!((err.line, err.column), (line, column));
+}
+
+#[test]
+fn chapter_out_of_range() {
+    assert_out_of_range("\\id GEN\n\\c 70000\n\\p text\n", "70000", "u16", 2, 4);
+}
+
+#[test]
+fn alt_chapter_out_of_range() {
+    assert_out_of_range(
+        "\\id GEN\n\\c 1 \\ca 70000\\ca*\n\\p text\n",
+        "70000",
+        "u16",
+        2,
+        10,
+    );
+}
+
+#[test]
+fn status_out_of_range() {
+    assert_out_of_range("\\id GEN\n\\sts 65536\n", "65536", "u16", 2, 6);
+}
+
+#[test]
+fn poetry_level_out_of_range() {
+    assert_out_of_range("\\id GEN\n\\q300 text\n", "300", "u8", 2, 3);
+}
+
+#[test]
+fn paragraph_level_out_of_range() {
+    assert_out_of_range("\\id GEN\n\\pi256 text\n", "256", "u8", 2, 4);
+}
+
+#[test]
+fn element_level_out_of_range() {
+    assert_out_of_range("\\id GEN\n\\s999 Heading\n", "999", "u8", 2, 3);
+}
+
+#[test]
+fn character_level_out_of_range() {
+    assert_out_of_range("\\id GEN\n\\li1 \\liv300 x\\liv300*\n", "300", "u8", 2, 10);
+}
+
+#[test]
+fn milestone_level_out_of_range() {
+    assert_out_of_range("\\id GEN\n\\p text\\qt300-s\\*\n", "300", "u8", 2, 11);
+}
+
+#[test]
+fn table_column_out_of_range() {
+    assert_out_of_range("\\id GEN\n\\tr \\tc300 cell\n", "300", "u8", 2, 8);
+}
+
+#[test]
+fn unknown_book_identifier() {
+    let err = parse("\\id XYZ\n\\p text\n").unwrap_err();
+    assert_eq!(
+        *err.kind,
+        ParseErrorKind::Unrecognized {
+            what: "book identifier",
+            value: "XYZ".into()
+        }
+    );
+    assert_eq!((err.line, err.column), (1, 5));
+    assert_eq!(err.to_string(), "1:5: unrecognized book identifier `XYZ`");
+}
+
+#[test]
+fn unknown_styles_are_rejected() {
+    use usfm::parser::*;
+    assert_eq!(to_paragraph_style("zz"), None);
+    assert_eq!(to_numbered_paragraph_style("zz", 1), None);
+    assert_eq!(to_poetry_style("zz"), None);
+    assert_eq!(to_numbered_poetry_style("zz", 1), None);
+    assert_eq!(to_element_type("zz"), None);
+    assert_eq!(to_numbered_element_type("zz", 1), None);
+    assert_eq!(to_empty_type("zz"), None);
+    assert_eq!(to_character_type("zz"), None);
+    assert_eq!(to_numbered_character_type("zz", 1), None);
+    assert_eq!(to_footnote_style("zz"), None);
+    assert_eq!(to_cross_ref_style("zz"), None);
+    assert_eq!(to_footnote_element_style("zz"), None);
+    assert_eq!(to_cross_ref_element_style("zz"), None);
+    assert_eq!(to_milestone_style("zz", None), None);
+    assert_eq!(to_milestone_style("qt", Some("x")), None);
+    assert_eq!(to_milestone_style("vid", Some("s")), None);
+    assert_eq!(to_numbered_milestone_style("zz", 1, Some("s")), None);
+    assert_eq!(to_milestone_bound(Some("x")), None);
+    assert_eq!(to_cell_prefix("zz"), None);
+    assert_eq!(to_book_identifier("XYZ"), None);
+}
+
+#[test]
+fn expected_custom_markers_have_names() {
+    let err = parse("\\id GEN\n\\c 1\n\\p\n\\v 1 \\bd x").unwrap_err();
+    assert!(
+        err.expected
+            .contains(&"custom footnote (\\zstyle + ...\\zstyle*)".to_string()),
+        "{:?}",
+        err.expected
+    );
+    assert!(
+        !err.expected
+            .iter()
+            .any(|name| ["zf", "zx", "zp"].contains(&name.as_str())),
+        "{:?}",
+        err.expected
+    );
+}
@@ -0,0 +1,83 @@
+use std::fs::read_to_string;
+
+use usfm::{BookContents, ElementType, ParagraphContents, ParagraphStyle, RefRange, format, parse};
+
+const INPUT: &str = "\\id MRK
+\\h Mark
+\\mt1 Mark
+\\c 1
+\\s1 John the Baptizer
+\\p
+\\v 1 The beginning of the Good News.
+\\v 2 As it is written,
+\\q1 \\qt Behold, I send my messenger\\qt*\\f + \\fr 1:2 \\ft Malachi 3:1\\f*
+\\q2 who will prepare your way.
+\\p
+\\v 3 The voice of one crying.
+\\s1 The Baptism of Jesus
+\\p
+\\v 9 In those days, Jesus came.
+";
+
+fn range(s: &str) -> RefRange {
+    s.parse().unwrap()
+}
+
+#[test]
+fn extracts_passages() {
+    let book = parse(INPUT).unwrap();
+    let passage = book.extract(&range("MRK 1:2-3"));
+    assert!(matches!(passage.contents[0], BookContents::Id { .. }));
+    assert!(
+        matches!(&passage.contents[1], BookContents::Element(e) if e.ty == ElementType::Header)
+    );
+    assert_eq!(passage.contents[2], BookContents::Chapter(1));
+    // The section heading leads into verse 1, which is left out, and the paragraph is split.
+    let BookContents::Paragraph(p) = &passage.contents[3] else {
+        panic!("expected a paragraph, got {:?}", passage.contents[3]);
+    };
+    assert_eq!(p.style, ParagraphStyle::Normal);
+    assert_eq!(p.contents[0], ParagraphContents::Verse(2.into()));
+    assert!(matches!(passage.contents[4], BookContents::Poetry(_)));
+    assert!(matches!(passage.contents[5], BookContents::Poetry(_)));
+    assert!(matches!(passage.contents[6], BookContents::Paragraph(_)));
+    // The heading after verse 3 leads into verse 9 and is left out too.
+    assert_eq!(passage.contents.len(), 7);
+    assert!(format(&passage).contains("\\f + \\fr 1:2 \\ft Malachi 3:1\\f*"));
+
+    let whole = book.extract(&range("MRK 1"));
+    assert_eq!(parse(&format(&whole)).unwrap().verses().count(), 4);
+    let last = book.extract(&range("MRK 1:9"));
+    assert!(
+        last.contents
+            .iter()
+            .any(|c| matches!(c, BookContents::Element(e) if e.ty == ElementType::Section(1)))
+    );
+    assert_eq!(book.extract(&range("MRK 2:1")).contents.len(), 2);
+    assert_eq!(book.extract(&range("GEN 1:2-3")).contents.len(), 2);
+    assert_eq!(
+        book.extract(&range("MAT 28:20-MRK 1:2")),
+        book.extract(&range("MRK 1:1-2"))
+    );
+}
+
+#[test]
+fn extracts_across_chapters() {
+    let input = read_to_string("usfm/02-GENeng-web.usfm").unwrap();
+    let book = parse(&input).unwrap();
+    let passage = book.extract(&range("GEN 1:3-2:4"));
+    let reparsed = parse(&format(&passage)).unwrap();
+    assert_eq!(reparsed, passage);
+
+    let verses: Vec<_> = passage
+        .verses()
+        .map(|v| (v.chapter, v.verse.start))
+        .collect();
+    assert_eq!(verses.first(), Some(&(1, 3)));
+    assert_eq!(verses.last(), Some(&(2, 4)));
+    assert_eq!(verses.len(), 29 + 4);
+    let original: Vec<_> = book.verses().filter(|v| v.chapter <= 2).collect();
+    for verse in passage.verses() {
+        assert!(original.contains(&verse), "{:?}", verse);
+    }
+}
@@ -238,6 +238,17 @@ fn test_format_milestone() {
     assert_eq!(book, reparsed);
 }
 
+#[test]
+fn test_format_milestone_attributes() {
+    let input = r#"\id GEN
+\p text\qt-s |sid="GEN.1.1" who="God"\*more text\qt-e\*
+"#;
+    let book = parse(input).expect("Failed to parse");
+    let formatted = format(&book);
+    let reparsed = parse(&formatted).expect("Failed to reparse");
+    assert_eq!(book, reparsed);
+}
+
 #[test]
 fn test_format_optional_break() {
     let input = r#"\id GEN
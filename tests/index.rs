@@ -0,0 +1,106 @@
+use usfm::{BookIndex, RefRange, Reference, VersePart, parse, parse_with_spans};
+
+const INPUT: &str = "\\id JHN
+\\c 1
+\\p
+\\v 1 In the beginning
+\\v 2 He was with God.
+\\c 3
+\\s1 Jesus and Nicodemus
+\\p
+\\v 1 Now there was a man
+\\q1 who came by night.
+\\q2
+\\v 2-3 And Jesus answered
+\\p
+\\v 16 For God so loved the world
+";
+
+#[test]
+fn indexes_chapters_and_verses() {
+    let book = parse(INPUT).unwrap();
+    let index = book.index();
+    assert_eq!(index, BookIndex::new(&book));
+
+    let chapters: Vec<_> = index.chapters().iter().map(|c| c.chapter).collect();
+    assert_eq!(chapters, vec![1, 3]);
+    assert_eq!(index.chapter(1).unwrap().contents, 1..3);
+    assert_eq!(index.chapter(3).unwrap().contents, 3..book.contents.len());
+    assert!(index.chapter(2).is_none());
+    assert_eq!(index.verses().len(), 5);
+
+    // Verse 3:1 continues across the poetry break.
+    let verse = index.verse(3, 1).unwrap();
+    assert_eq!(
+        verse.parts,
+        vec![
+            VersePart {
+                path: vec![5],
+                contents: 0..2
+            },
+            VersePart {
+                path: vec![6],
+                contents: 0..1
+            },
+        ]
+    );
+    assert_eq!(index.verse(3, 3).unwrap().verse, "2-3".parse().unwrap());
+    assert!(index.verse(3, 4).is_none());
+    assert!(index.verse(2, 1).is_none());
+    assert_eq!(
+        index
+            .get(&"JHN 3:16".parse::<Reference>().unwrap())
+            .unwrap()
+            .parts[0]
+            .path,
+        vec![8]
+    );
+}
+
+#[test]
+fn looks_up_ranges() {
+    let index = parse(INPUT).unwrap().index();
+    let verses = |s: &str| {
+        index
+            .range(&s.parse::<RefRange>().unwrap())
+            .iter()
+            .map(|v| format!("{}:{}", v.chapter, v.verse))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(verses("JHN 1:2-3:2"), vec!["1:2", "3:1", "3:2-3"]);
+    assert_eq!(verses("JHN 3"), vec!["3:1", "3:2-3", "3:16"]);
+    assert_eq!(verses("JHN 3:4-15"), Vec::<String>::new());
+    assert_eq!(verses("MAT 1:2-3:2"), Vec::<String>::new());
+    assert_eq!(verses("LUK 24:53-JHN 1:2"), vec!["1:1", "1:2"]);
+}
+
+#[test]
+fn finds_verse_source() {
+    let (book, source_map) = parse_with_spans(INPUT).unwrap();
+    let index = book.index();
+    let range = index
+        .verse(3, 1)
+        .unwrap()
+        .source_range(&source_map)
+        .unwrap();
+    assert_eq!(
+        &INPUT[range],
+        "\\v 1 Now there was a man\n\\q1 who came by night."
+    );
+    let range = index
+        .verse(1, 2)
+        .unwrap()
+        .source_range(&source_map)
+        .unwrap();
+    assert_eq!(&INPUT[range], "\\v 2 He was with God.");
+}
+
+#[test]
+fn looks_up_chapters_out_of_order() {
+    let book = parse("\\id JHN\n\\c 3\n\\p\n\\v 1 Nicodemus\n\\c 1\n\\p\n\\v 1 In the beginning\n\\c 2\n\\p\n\\v 1 Cana\n").unwrap();
+    let index = book.index();
+    assert_eq!(index.chapter(3).unwrap().contents, 1..3);
+    assert_eq!(index.chapter(1).unwrap().contents, 3..5);
+    assert_eq!(index.chapter(2).unwrap().contents, 5..7);
+    assert_eq!(index.verse(1, 1).unwrap().parts[0].path, vec![4]);
+}
@@ -0,0 +1,88 @@
+use std::{fs::read_to_string, path::PathBuf};
+use usfm::{BookEncoding, BookIdentifier, BookMetadata, format, parse};
+
+const INPUT: &str = "\\id GEN World English Bible
+\\usfm 3.0
+\\ide UTF-8
+\\sts 2
+\\rem Checked in 2024
+\\h Genesis
+\\toc1 The First Book of Moses, Commonly Called Genesis
+\\toc2 Genesis
+\\toc3 Gen
+\\toca2 Bereshit
+\\mt2 The First Book of Moses,
+\\mt1 \\w Genesis\\w*
+\\c 1
+\\p
+\\v 1 In the beginning.
+\\s1 Not a title
+\\h Not the header
+\\toc3 Not a name
+";
+
+#[test]
+fn reads_metadata() {
+    let metadata = parse(INPUT).unwrap().metadata();
+    assert_eq!(metadata.code, Some(BookIdentifier::Genesis));
+    assert_eq!(metadata.description.as_deref(), Some("World English Bible"));
+    assert_eq!(metadata.usfm.as_deref(), Some("3.0"));
+    assert_eq!(metadata.encoding, Some(BookEncoding::UTF8));
+    assert_eq!(metadata.status, Some(2));
+    assert_eq!(metadata.header.as_deref(), Some("Genesis"));
+    assert_eq!(metadata.names.abbreviation.as_deref(), Some("Gen"));
+    assert_eq!(metadata.names.alternates, vec![(2, "Bereshit".to_string())]);
+    assert_eq!(
+        metadata.titles,
+        vec![
+            (2, "The First Book of Moses,".to_string()),
+            (1, "Genesis".to_string())
+        ]
+    );
+    assert_eq!(parse("\\id GEN\n").unwrap().metadata().header, None);
+}
+
+#[test]
+fn writes_metadata_back() {
+    let mut book = parse(INPUT).unwrap();
+    let mut metadata = book.metadata();
+    metadata.status = Some(3);
+    metadata.header = Some("Bereshit".into());
+    metadata.names.long = None;
+    metadata.titles.truncate(1);
+    book.set_metadata(&metadata);
+
+    let reparsed = parse(&format(&book)).unwrap();
+    assert_eq!(reparsed.metadata(), metadata);
+    let output = format(&book);
+    assert!(!output.contains("\\toc1"));
+    assert!(output.contains("\\rem Checked in 2024"));
+    assert_eq!(reparsed.verses().count(), 1);
+
+    let mut empty = parse("\\id EXO\n\\c 1\n\\p\n\\v 1 Now.\n").unwrap();
+    empty.set_metadata(&BookMetadata {
+        code: Some(BookIdentifier::Exodus),
+        header: Some("Exodus".into()),
+        ..Default::default()
+    });
+    assert!(format(&empty).starts_with("\\id EXO\n\\h Exodus\n\\c"));
+}
+
+#[test]
+fn writing_unchanged_metadata_keeps_the_book() {
+    let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("usfm");
+    for name in ["sample.usfm", "02-GENeng-web.usfm", "02-GENengwebpb.usfm"] {
+        let mut book = parse(&read_to_string(dir.join(name)).unwrap()).unwrap();
+        let before = format(&book);
+        book.set_metadata(&book.metadata());
+        assert_eq!(format(&book), before, "{name}");
+    }
+
+    let mut book =
+        parse("\\id GEN\n\\toca3 Gn\n\\mt1 The Book of \\bk Genesis\\bk*\n\\c 1\n\\p\n\\v 1 In\n")
+            .unwrap();
+    let before = format(&book);
+    book.set_metadata(&book.metadata());
+    assert_eq!(format(&book), before);
+    assert!(before.contains("\\toca3 Gn"), "{before}");
+}
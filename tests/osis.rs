@@ -0,0 +1,133 @@
+mod common;
+
+use common::{assert_milestones_pair, read};
+use usfm::{BookIdentifier, osis::to_osis, parse};
+
+#[test]
+fn web_genesis_to_osis() {
+    let genesis = parse(&read("02-GENeng-web.usfm")).unwrap();
+    let osis = to_osis(&genesis);
+    let doc = roxmltree::Document::parse(&osis).unwrap();
+    let book = doc
+        .descendants()
+        .find(|n| n.attribute("type") == Some("book"))
+        .unwrap();
+    assert_eq!(book.attribute("osisID"), Some("Gen"));
+
+    assert_eq!(assert_milestones_pair(&doc, "chapter", "sID", "eID"), 50);
+    assert_eq!(
+        assert_milestones_pair(&doc, "verse", "sID", "eID"),
+        genesis.verses().len()
+    );
+    let first = doc.descendants().find(|n| n.has_tag_name("verse")).unwrap();
+    assert_eq!(first.attribute("osisID"), Some("Gen.1.1"));
+    assert_eq!(first.parent().unwrap().tag_name().name(), "p");
+
+    let note = doc.descendants().find(|n| n.has_tag_name("note")).unwrap();
+    assert_eq!(note.attribute("placement"), Some("foot"));
+    assert_eq!(note.attribute("osisRef"), Some("Gen.1.1"));
+    let reference = note.first_element_child().unwrap();
+    assert_eq!(reference.attribute("type"), Some("annotateRef"));
+}
+
+#[test]
+fn sections_poetry_and_words_of_jesus() {
+    let book = parse(concat!(
+        "\\id MAT\n\\c 5\n\\s1 The Beatitudes\n\\p\n",
+        "\\v 1-2 He said, \\wj Blessed are the poor,\\wj*\\x - \\xo 5:1 \\xt Luke 6:20\\x*\n",
+        "\\s2 Salt\n\\q1\n\\v 3 You are\n\\q2 the salt.\n\\b\n\\p\n\\v 4 Light\n",
+        "\\s1 Law\n\\p\n\\v 5 & the prophets\n",
+    ))
+    .unwrap();
+    let osis = to_osis(&book);
+    let doc = roxmltree::Document::parse(&osis).unwrap();
+    assert_eq!(assert_milestones_pair(&doc, "verse", "sID", "eID"), 4);
+
+    let sections: Vec<_> = doc
+        .descendants()
+        .filter(|n| n.has_tag_name("div") && n.attribute("type") != Some("book"))
+        .map(|n| {
+            let title = n.first_element_child().unwrap().text().unwrap();
+            (
+                n.attribute("type").unwrap(),
+                title,
+                n.parent().unwrap().attribute("type"),
+            )
+        })
+        .collect();
+    assert_eq!(
+        sections,
+        [
+            ("section", "The Beatitudes", Some("book")),
+            ("subSection", "Salt", Some("section")),
+            ("section", "Law", Some("book")),
+        ]
+    );
+
+    let range = doc.descendants().find(|n| n.has_tag_name("verse")).unwrap();
+    assert_eq!(range.attribute("osisID"), Some("Matt.5.1 Matt.5.2"));
+    assert_eq!(range.attribute("n"), Some("1-2"));
+
+    let q = doc.descendants().find(|n| n.has_tag_name("q")).unwrap();
+    assert_eq!(q.attribute("who"), Some("Jesus"));
+    assert_eq!(q.text(), Some("Blessed are the poor,"));
+
+    let note = doc.descendants().find(|n| n.has_tag_name("note")).unwrap();
+    assert_eq!(note.attribute("type"), Some("crossReference"));
+    assert_eq!(note.attribute("osisRef"), Some("Matt.5.1"));
+
+    let lg = doc.descendants().find(|n| n.has_tag_name("lg")).unwrap();
+    let levels: Vec<_> = lg
+        .children()
+        .filter(|n| n.has_tag_name("l"))
+        .map(|n| n.attribute("level").unwrap())
+        .collect();
+    assert_eq!(levels, ["1", "2"]);
+    assert!(
+        osis.contains("the salt.<verse eID=\"Matt.5.3\"/></l>\n</lg>\n<p>"),
+        "{osis}"
+    );
+}
+
+#[test]
+fn sample_to_osis() {
+    let osis = to_osis(&parse(&read("sample.usfm")).unwrap());
+    let doc = roxmltree::Document::parse(&osis).unwrap();
+    assert_milestones_pair(&doc, "chapter", "sID", "eID");
+    assert_milestones_pair(&doc, "verse", "sID", "eID");
+    let quotes = doc
+        .descendants()
+        .filter(|n| n.has_tag_name("q") && n.attribute("sID").is_some())
+        .count();
+    assert!(quotes >= 1);
+    assert_eq!(
+        quotes,
+        doc.descendants()
+            .filter(|n| n.has_tag_name("q") && n.attribute("eID").is_some())
+            .count()
+    );
+    assert_eq!(
+        doc.descendants()
+            .filter(|n| n.has_tag_name("table"))
+            .count(),
+        1
+    );
+}
+
+#[test]
+fn osis_book_abbreviations() {
+    assert_eq!(BookIdentifier::Genesis.to_osis(), "Gen");
+    assert_eq!(BookIdentifier::OneSamuel.to_osis(), "1Sam");
+    assert_eq!(BookIdentifier::SongOfSongs.to_osis(), "Song");
+    assert_eq!(BookIdentifier::Philemon.to_osis(), "Phlm");
+    assert_eq!(BookIdentifier::ThreeJohn.to_osis(), "3John");
+    assert_eq!(BookIdentifier::Sirach.to_osis(), "Sir");
+}
+
+#[test]
+fn remarks_are_well_formed_comments() {
+    let book = parse("\\id GEN\n\\rem A --- B -- \\bd C--\\bd*\n\\c 1\n\\p\n\\v 1 Text\n").unwrap();
+    let osis = to_osis(&book);
+    assert!(osis.contains("<!-- A - - - B - - C- - -->"), "{osis}");
+    roxmltree::Document::parse(&osis).unwrap();
+}
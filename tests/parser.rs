@@ -18,7 +18,7 @@ fn assert_web_genesis(genesis: &Book) {
             A::Paragraph(Paragraph{
                 style: Normal,
                 contents: vec![
-                    B::Verse("1".into()),
+                    B::Verse(1.into()),
                     B::Line("In the beginning, God".into()),
                     B::Footnote(Footnote {
                         style: FootnoteStyle::Footnote,
@@ -37,7 +37,7 @@ fn assert_web_genesis(genesis: &Book) {
                         ]
                     }),
                     B::Line(" created the heavens and the earth.".into()),
-                    B::Verse("2".into()),
+                    B::Verse(2.into()),
                     B::Line("The earth was formless and empty. Darkness was on the surface of the deep and God\u{2019}s Spirit was hovering over the surface\nof the waters.".into())
                 ]
             })
@@ -79,3 +79,26 @@ fn parse_sample() {
     let input = read_to_string(file).unwrap();
     parse(&input).inspect_err(|e| println!("{}", e)).unwrap();
 }
+
+#[test]
+fn rkyv_custom_styles() {
+    let book = |marker: &str| Book {
+        contents: vec![BookContents::Paragraph(Paragraph {
+            style: ParagraphStyle::Custom(marker.to_string()),
+            contents: vec![ParagraphContents::Line("text".to_string())],
+        })],
+    };
+    // Custom markers are not limited in length, so one well past any standard marker is kept.
+    for marker in ["zpara", &"z".repeat(40)] {
+        let bytes = rkyv::to_bytes::<Error>(&book(marker)).unwrap();
+        let archived = rkyv::access::<ArchivedBook, Error>(&bytes).unwrap();
+        let ArchivedBookContents::Paragraph(paragraph) = &archived.contents[0] else {
+            panic!("expected a paragraph, got {:?}", archived.contents[0]);
+        };
+        let ArchivedParagraphStyle::Custom(name) = &paragraph.style else {
+            panic!("expected a custom style, got {:?}", paragraph.style);
+        };
+        assert_eq!(name.as_str(), marker);
+        assert_eq!(deserialize::<Book, Error>(archived).unwrap(), book(marker));
+    }
+}
@@ -0,0 +1,111 @@
+use usfm::{BookIdentifier, Node, ParseErrorKind, Query, Reference, parse};
+
+const INPUT: &str = "\\id MAT
+\\c 5
+\\s1 The Sermon on the Mount
+\\p
+\\v 1 Seeing the multitudes, he went up onto the mountain.\\f + \\fr 5:1 \\ft Or, hill\\f*
+\\v 2 He opened his mouth and taught them, saying,
+\\q1
+\\v 3 \\wj \\+w Blessed|strong=\"G3107\"\\+w* are the poor in spirit,\\wj*
+\\q2 \\wj for theirs is the Kingdom of Heaven.\\wj*
+\\c 6
+\\s2 Giving to the needy
+\\p
+\\v 1 \\wj Be careful that you don't do your charitable giving before men.\\wj*\\f + \\fr 6:1 \\ft NU reads acts of righteousness\\f*
+";
+
+#[test]
+fn finds_nodes_by_marker_and_range() {
+    let book = parse(INPUT).unwrap();
+
+    let jesus =
+        book.query(&Query::marker("wj").within(Reference::chapter(BookIdentifier::Matthew, 5)));
+    let text: Vec<_> = jesus.iter().map(|m| m.node.text()).collect();
+    assert_eq!(
+        text,
+        [
+            "Blessed are the poor in spirit,",
+            "for theirs is the Kingdom of Heaven."
+        ]
+    );
+    assert_eq!(
+        jesus[0].reference,
+        Reference::verse(BookIdentifier::Matthew, 5, 3)
+    );
+
+    let mark = Query::marker("wj").within(Reference::chapter(BookIdentifier::Mark, 5));
+    assert!(book.query(&mark).is_empty());
+    let across_books = |start, end| {
+        book.query(&Query::marker("wj").within(usfm::RefRange { start, end }))
+            .len()
+    };
+    assert_eq!(
+        across_books(
+            Reference::chapter(BookIdentifier::Genesis, 1),
+            Reference::verse(BookIdentifier::Matthew, 5, 2)
+        ),
+        0
+    );
+    assert_eq!(
+        across_books(
+            Reference::verse(BookIdentifier::Matthew, 5, 3),
+            Reference::chapter(BookIdentifier::Revelation, 1)
+        ),
+        3
+    );
+
+    let headings = book.query(&"s".parse().unwrap());
+    assert_eq!(headings.len(), 2);
+    assert_eq!(headings[1].reference.to_string(), "MAT 6");
+    assert!(matches!(headings[1].node, Node::Element(_)));
+
+    let footnotes = book.query(
+        &"\\f"
+            .parse::<Query>()
+            .unwrap()
+            .within("6:1".parse::<usfm::RefRange>().unwrap()),
+    );
+    assert_eq!(footnotes.len(), 1);
+    assert_eq!(
+        footnotes[0].node.text(),
+        "6:1 NU reads acts of righteousness"
+    );
+
+    let poetry = book.query(&"q".parse().unwrap());
+    assert_eq!(poetry.len(), 2);
+    assert_eq!(book.query(&"q2, ft".parse().unwrap()).len(), 3);
+}
+
+#[test]
+fn finds_nodes_by_attribute() {
+    let book = parse(INPUT).unwrap();
+    for selector in ["w[strong=G*]", "w[strong]", "w[strong=\"*310*\"]"] {
+        let words = book.query(&selector.parse().unwrap());
+        assert_eq!(words.len(), 1, "{selector}");
+        assert_eq!(words[0].node.marker(), "w");
+        assert_eq!(words[0].node.text(), "Blessed");
+    }
+    assert!(book.query(&"w[strong=H*]".parse().unwrap()).is_empty());
+    assert!(book.query(&"w[lemma]".parse().unwrap()).is_empty());
+    let query = Query::marker("w").attribute("strong", "G3107");
+    assert_eq!(book.query(&query).len(), 1);
+}
+
+#[test]
+fn rejects_malformed_selectors() {
+    for (selector, at) in [("", 0), ("w[strong", 1), ("f, ", 2), ("w x", 0)] {
+        let err = selector.parse::<Query>().unwrap_err();
+        assert!(
+            matches!(
+                *err.kind,
+                ParseErrorKind::Unrecognized {
+                    what: "selector",
+                    ..
+                }
+            ),
+            "{selector}"
+        );
+        assert_eq!(err.offset, at, "{selector}");
+    }
+}
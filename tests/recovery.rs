@@ -0,0 +1,66 @@
+use std::fs::read_to_string;
+use usfm::{BookContents, ParagraphContents, ParseErrorKind, parse, parse_with_recovery};
+
+#[test]
+fn recovers_after_bad_paragraph() {
+    let input = "\\id GEN\n\\c 1\n\\p\n\\v 1 Good.\n\\p\n\\v 2 Bad \\h x\\h*\n\\v 3 Lost.\n\\q1\n\\v 4 Good again.\n";
+    let (book, diagnostics) = parse_with_recovery(input);
+
+    assert_eq!(book.contents.len(), 4);
+    assert!(matches!(book.contents[1], BookContents::Chapter(1)));
+    let BookContents::Poetry(poetry) = &book.contents[3] else {
+        panic!("expected poetry, got {:?}", book.contents[3]);
+    };
+    assert_eq!(poetry.contents[0], ParagraphContents::Verse(4.into()));
+
+    assert_eq!(diagnostics.len(), 1);
+    let diagnostic = &diagnostics[0];
+    assert_eq!(*diagnostic.error.kind, ParseErrorKind::Syntax);
+    assert_eq!((diagnostic.error.line, diagnostic.error.column), (6, 11));
+    assert_eq!(diagnostic.error.marker.as_deref(), Some("h"));
+    assert_eq!(
+        &input[diagnostic.skipped.clone()],
+        "\\p\n\\v 2 Bad \\h x\\h*\n\\v 3 Lost."
+    );
+}
+
+#[test]
+fn recovers_from_multiple_regions() {
+    let input = "\\id GEN\n\\v 9 stray\n\\c 1\n\\p One \\bd two\n\\s1 Heading\n\\p Three\n";
+    let (book, diagnostics) = parse_with_recovery(input);
+
+    assert_eq!(book.contents.len(), 4);
+    assert_eq!(diagnostics.len(), 2);
+    assert_eq!(&input[diagnostics[0].skipped.clone()], "\\v 9 stray");
+    assert_eq!(diagnostics[0].error.line, 2);
+    assert_eq!(&input[diagnostics[1].skipped.clone()], "\\p One \\bd two");
+    assert_eq!(diagnostics[1].error.line, 4);
+}
+
+#[test]
+fn recovers_from_conversion_errors() {
+    let input = "\\id GEN\n\\c 70000\n\\p text\n";
+    let (book, diagnostics) = parse_with_recovery(input);
+
+    assert_eq!(book.contents.len(), 2);
+    assert_eq!(diagnostics.len(), 1);
+    assert!(matches!(
+        *diagnostics[0].error.kind,
+        ParseErrorKind::NumberOutOfRange { .. }
+    ));
+    assert_eq!(&input[diagnostics[0].skipped.clone()], "\\c 70000");
+}
+
+#[test]
+fn recovery_matches_strict_parse_on_valid_input() {
+    for file in [
+        "usfm/sample.usfm",
+        "usfm/02-GENeng-web.usfm",
+        "usfm/02-GENengwebpb.usfm",
+    ] {
+        let input = read_to_string(file).unwrap();
+        let (book, diagnostics) = parse_with_recovery(&input);
+        assert!(diagnostics.is_empty(), "{file}: {diagnostics:?}");
+        assert_eq!(book, parse(&input).unwrap(), "{file}");
+    }
+}
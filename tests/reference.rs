@@ -0,0 +1,104 @@
+use usfm::{BookIdentifier::*, ParseErrorKind, RefList, RefRange, Reference};
+
+fn list(s: &str) -> RefList {
+    s.parse().unwrap()
+}
+
+#[test]
+fn parses_references_and_ranges() {
+    assert_eq!(
+        "GEN 1:1".parse::<Reference>().unwrap(),
+        Reference::verse(Genesis, 1, 1)
+    );
+    assert_eq!(
+        "gen 1.4a".parse::<Reference>().unwrap(),
+        Reference::verse(Genesis, 1, "4a".parse::<usfm::VerseNumber>().unwrap())
+    );
+    assert_eq!(
+        "1SA 3".parse::<Reference>().unwrap(),
+        Reference::chapter(OneSamuel, 3)
+    );
+    assert_eq!(
+        "REV".parse::<Reference>().unwrap(),
+        Reference::book(Revelation)
+    );
+
+    assert_eq!(
+        "GEN 1:1-2:3".parse::<RefRange>().unwrap(),
+        RefRange::new(
+            Reference::verse(Genesis, 1, 1),
+            Reference::verse(Genesis, 2, 3)
+        )
+    );
+    assert_eq!(
+        "GEN 1:1\u{2013}3".parse::<RefRange>().unwrap(),
+        RefRange::new(
+            Reference::verse(Genesis, 1, 1),
+            Reference::verse(Genesis, 1, 3)
+        )
+    );
+    assert_eq!(
+        "PSA 1-2".parse::<RefRange>().unwrap(),
+        RefRange::new(Reference::chapter(Psalms, 1), Reference::chapter(Psalms, 2))
+    );
+}
+
+#[test]
+fn carries_book_and_chapter_through_lists() {
+    assert_eq!(
+        list("Gen 1:1,5; 2:4; EXO 3:14"),
+        RefList(vec![
+            Reference::verse(Genesis, 1, 1).into(),
+            Reference::verse(Genesis, 1, 5).into(),
+            Reference::verse(Genesis, 2, 4).into(),
+            Reference::verse(Exodus, 3, 14).into(),
+        ])
+    );
+    assert_eq!(
+        list("(JHN 3:16-18, 20)"),
+        RefList(vec![
+            RefRange::new(Reference::verse(John, 3, 16), Reference::verse(John, 3, 18)),
+            Reference::verse(John, 3, 20).into(),
+        ])
+    );
+
+    // A footnote's `\fr` leaves out the book it is in.
+    let fr = RefList::parse_relative("3:16: ", &Reference::book(John)).unwrap();
+    assert_eq!(fr, RefList(vec![Reference::verse(John, 3, 16).into()]));
+    let no_book = list("3:16");
+    assert_eq!(no_book.0[0].start.book, None);
+}
+
+#[test]
+fn formats_canonically() {
+    for (input, expected) in [
+        ("gen 1.1", "GEN 1:1"),
+        ("GEN 1:1-2:3", "GEN 1:1-2:3"),
+        ("GEN 1:1 - 3", "GEN 1:1-3"),
+        ("GEN 50:26-EXO 1:1", "GEN 50:26-EXO 1:1"),
+        ("Gen 1:1, 5;2:4", "GEN 1:1,5; 2:4"),
+        ("PSA 1; 3-4; MAT 5:3b", "PSA 1; 3-4; MAT 5:3b"),
+        ("GEN 1; 1:5", "GEN 1; 1:5"),
+    ] {
+        let parsed = list(input);
+        assert_eq!(parsed.to_string(), expected, "{input}");
+        assert_eq!(list(expected), parsed, "{input}");
+    }
+}
+
+#[test]
+fn rejects_malformed_references() {
+    let err = "GEN 1:1; XYZ 2:3".parse::<RefList>().unwrap_err();
+    assert_eq!(
+        *err.kind,
+        ParseErrorKind::Unrecognized {
+            what: "book",
+            value: "XYZ".into()
+        }
+    );
+    assert_eq!(err.column, 10);
+    for s in ["", "GEN 1:x", "GEN 2:5-3", "GEN 1:1-2:3; 4"] {
+        assert!(s.parse::<RefRange>().is_err(), "{s}");
+    }
+    assert!("GEN 1:1-3".parse::<Reference>().is_err());
+}
@@ -0,0 +1,63 @@
+#![cfg(feature = "serde")]
+use std::{fs::read_to_string, path::PathBuf};
+use usfm::{Book, parse};
+
+static MANIFEST_DIR: &str = env!("CARGO_MANIFEST_DIR");
+
+#[test]
+fn round_trips_samples_through_json() {
+    for name in ["sample.usfm", "02-GENeng-web.usfm"] {
+        let file = PathBuf::from(MANIFEST_DIR).join("usfm").join(name);
+        let book = parse(&read_to_string(file).unwrap()).unwrap();
+        let json = serde_json::to_string(&book).unwrap();
+        assert_eq!(serde_json::from_str::<Book>(&json).unwrap(), book, "{name}");
+    }
+}
+
+#[test]
+fn writes_tagged_enums() {
+    let book = parse(
+        "\\id GEN\n\\c 1\n\\p\n\\v 1 In the \\bd beginning\\bd*\\f + \\ft Or, first\\f*\n\\b\n",
+    )
+    .unwrap();
+    assert_eq!(
+        serde_json::to_value(&book).unwrap(),
+        serde_json::json!({"contents": [
+            {"type": "id", "value": {"code": "GEN", "text": null}},
+            {"type": "chapter", "value": 1},
+            {"type": "paragraph", "value": {"style": {"type": "normal"}, "contents": [
+                {"type": "verse", "value": {"start": 1, "start_segment": null, "end": 1, "end_segment": null, "spelling": null}},
+                {"type": "line", "value": "In the "},
+                {"type": "character", "value": {
+                    "type": {"type": "bold"},
+                    "contents": [{"type": "line", "value": "beginning"}],
+                    "attributes": []
+                }},
+                {"type": "footnote", "value": {
+                    "style": {"type": "footnote"},
+                    "caller": {"type": "auto"},
+                    "elements": [{"style": "text", "contents": [{"type": "line", "value": "Or, first"}]}]
+                }}
+            ]}},
+            {"type": "empty", "value": "blank"}
+        ]})
+    );
+    assert!(
+        serde_json::from_str::<Book>(
+            r#"{"contents": [{"type": "id", "value": {"code": "XYZ", "text": null}}]}"#
+        )
+        .unwrap_err()
+        .to_string()
+        .contains("unrecognized book identifier `XYZ`")
+    );
+
+    let word = parse("\\id GEN\n\\c 1\n\\p\n\\v 1 \\w beginning|strong=\"H7225\"\\w*\n").unwrap();
+    assert_eq!(
+        serde_json::to_value(&word).unwrap()["contents"][2]["value"]["contents"][1],
+        serde_json::json!({"type": "character", "value": {
+            "type": {"type": "word"},
+            "contents": [{"type": "line", "value": "beginning"}],
+            "attributes": [["strong", "H7225"]]
+        }})
+    );
+}
@@ -0,0 +1,92 @@
+extern crate usfm;
+use std::fs::read_to_string;
+use usfm::parser::SpanNode;
+use usfm::*;
+
+fn paragraph_children(contents: &[ParagraphContents]) -> Vec<usize> {
+    contents
+        .iter()
+        .map(|c| match c {
+            ParagraphContents::Character(c) => c.contents.len(),
+            ParagraphContents::Footnote(f) => f.elements.len(),
+            ParagraphContents::CrossRef(x) => x.elements.len(),
+            ParagraphContents::Figure(f) => f.contents.len(),
+            _ => 0,
+        })
+        .collect()
+}
+
+fn shape(node: &SpanNode) -> Vec<usize> {
+    node.children.iter().map(|c| c.children.len()).collect()
+}
+
+fn assert_same_shape(book: &Book, source_map: &SourceMap) {
+    use BookContents as C;
+    assert_eq!(book.contents.len(), source_map.nodes().len());
+    for (content, node) in book.contents.iter().zip(source_map.nodes()) {
+        let children = node.children.len();
+        match content {
+            C::Paragraph(p) => assert_eq!(shape(node), paragraph_children(&p.contents)),
+            C::Poetry(p) => assert_eq!(shape(node), paragraph_children(&p.contents)),
+            C::TableRow(tr) => assert_eq!(
+                shape(node),
+                tr.cells
+                    .iter()
+                    .map(|c| c.contents.len())
+                    .collect::<Vec<_>>()
+            ),
+            C::Element(e) => assert_eq!(children, e.contents.len()),
+            C::Sidebar(sb) => assert_eq!(children, sb.contents.len()),
+            C::Figure(f) => assert_eq!(children, f.contents.len()),
+            _ => assert_eq!(children, 0),
+        }
+    }
+}
+
+#[test]
+fn spans_match_book_shape() {
+    for file in [
+        "usfm/sample.usfm",
+        "usfm/02-GENeng-web.usfm",
+        "usfm/02-GENengwebpb.usfm",
+    ] {
+        let input = read_to_string(file).unwrap();
+        let (book, source_map) = parse_with_spans(&input).unwrap();
+        assert_eq!(book, parse(&input).unwrap());
+        assert_same_shape(&book, &source_map);
+    }
+}
+
+#[test]
+fn spans_point_into_source() {
+    let input = "\\id GEN\n\\c 1\n\\p\n\\v 1 In the \\nd Lord\\nd*\\f + \\ft note\\f* here.\n\\tr \\tc1 A \\tc2 B\n";
+    let (book, source_map) = parse_with_spans(input).unwrap();
+    let text = |path: &[usize]| &input[source_map.get(path).unwrap().range()];
+
+    assert_eq!(text(&[1]), "\\c 1");
+    assert_eq!(
+        text(&[2]),
+        "\\p\n\\v 1 In the \\nd Lord\\nd*\\f + \\ft note\\f* here."
+    );
+    assert_eq!(text(&[2, 0]), "\\v 1");
+    assert_eq!(text(&[2, 1]), "In the ");
+    assert_eq!(text(&[2, 2]), "\\nd Lord\\nd*");
+    assert_eq!(text(&[2, 2, 0]), "Lord");
+    assert_eq!(text(&[2, 3]), "\\f + \\ft note\\f*");
+    assert_eq!(text(&[2, 3, 0]), "\\ft note");
+    assert_eq!(text(&[2, 3, 0, 0]), "note");
+    assert_eq!(text(&[3, 1]), "\\tc2 B");
+    assert!(source_map.get(&[2, 9]).is_none());
+
+    let BookContents::Paragraph(p) = &book.contents[2] else {
+        panic!()
+    };
+    assert_eq!(
+        p.contents.len(),
+        source_map.node(&[2]).unwrap().children.len()
+    );
+
+    let span = source_map.get(&[2, 2]).unwrap();
+    assert_eq!((span.start.line, span.start.column), (4, 13));
+    assert_eq!((span.end.line, span.end.column), (4, 25));
+}
@@ -0,0 +1,210 @@
+use usfm::stylesheet::{Style, StyleType, TextType};
+use usfm::{
+    BookContents, CharacterContents, CharacterType, Custom, Element, ElementContents, ElementKind,
+    ElementType, FootnoteStyle, MilestoneBound, MilestoneStyle, ParagraphContents, ParagraphStyle,
+    ParseErrorKind, ParserConfig, Stylesheet, UnknownMarkers, format,
+};
+
+const CUSTOM_STY: &str = r#"# Project additions
+\Marker zpara
+\Name zpara - Custom Paragraph
+\StyleType Paragraph
+\TextType VerseText
+\OccursUnder c
+
+\Marker zfoo
+\Name zfoo - Custom Character
+\StyleType Character
+\TextType VerseText
+\Endmarker zfoo*
+\FontSize 12
+
+\Marker zn
+\StyleType Note
+\TextType NoteText
+\Endmarker zn*
+
+\Marker zaln-s
+\StyleType Milestone
+\Endmarker zaln-e
+"#;
+
+const INPUT: &str = "\\id GEN\n\\c 1\n\\zpara\n\\v 1 \\zaln-s |x-strong=\"H7225\"\\*In\\zaln-e\\* the \\zfoo beginning\\zfoo*\\zn + \\fr 1:1 \\ft Or \\fq when\\zn*.\n";
+
+fn config() -> ParserConfig {
+    ParserConfig {
+        stylesheet: CUSTOM_STY.parse().unwrap(),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn parses_stylesheet() {
+    let stylesheet = Stylesheet::parse(CUSTOM_STY).unwrap();
+    assert_eq!(stylesheet.styles().count(), 4);
+    assert_eq!(
+        stylesheet.get("zfoo"),
+        Some(&Style {
+            marker: "zfoo".into(),
+            name: Some("zfoo - Custom Character".into()),
+            description: None,
+            style_type: Some(StyleType::Character),
+            text_type: Some(TextType::VerseText),
+            occurs_under: vec![],
+            endmarker: Some("zfoo*".into()),
+        })
+    );
+    assert_eq!(stylesheet.get("zpara").unwrap().occurs_under, vec!["c"]);
+    assert!(stylesheet.declares("zaln", StyleType::Milestone));
+    assert!(!stylesheet.declares("zfoo", StyleType::Paragraph));
+}
+
+#[test]
+fn custom_stylesheet_extends_base() {
+    let mut stylesheet = Stylesheet::parse(CUSTOM_STY).unwrap();
+    stylesheet
+        .extend(Stylesheet::parse("\\Marker zfoo\n\\TextType Other\n\\Marker zbar\n").unwrap());
+    let zfoo = stylesheet.get("zfoo").unwrap();
+    assert_eq!(zfoo.style_type, Some(StyleType::Character));
+    assert_eq!(zfoo.text_type, Some(TextType::Other));
+    assert!(stylesheet.get("zbar").is_some());
+}
+
+#[test]
+fn rejects_unknown_style_type() {
+    let err = Stylesheet::parse("\\Marker zfoo\n\\StyleType Word\n").unwrap_err();
+    assert_eq!(
+        *err.kind,
+        ParseErrorKind::Unrecognized {
+            what: "style type",
+            value: "Word".into()
+        }
+    );
+    assert_eq!((err.line, err.column), (2, 12));
+}
+
+#[test]
+fn classifies_declared_markers() {
+    let book = config().parse(INPUT).unwrap();
+
+    let BookContents::Paragraph(p) = &book.contents[2] else {
+        panic!("expected a paragraph, got {:?}", book.contents[2]);
+    };
+    assert_eq!(p.style, ParagraphStyle::Custom("zpara".into()));
+    let ParagraphContents::Milestone(ms) = &p.contents[1] else {
+        panic!("expected a milestone, got {:?}", p.contents[1]);
+    };
+    assert_eq!(
+        ms.style,
+        MilestoneStyle::Custom("zaln".into(), MilestoneBound::Start)
+    );
+    assert_eq!(ms.attributes, vec![("x-strong".into(), "H7225".into())]);
+    let ParagraphContents::Character(c) = &p.contents[5] else {
+        panic!("expected a character, got {:?}", p.contents[5]);
+    };
+    assert_eq!(c.ty, CharacterType::Custom("zfoo".into()));
+    assert_eq!(
+        c.contents,
+        vec![CharacterContents::Line("beginning".into())]
+    );
+    let ParagraphContents::Footnote(f) = &p.contents[6] else {
+        panic!("expected a footnote, got {:?}", p.contents[6]);
+    };
+    assert_eq!(f.style, FootnoteStyle::Custom("zn".into()));
+    assert_eq!(f.elements.len(), 3);
+
+    assert_eq!(config().parse(&format(&book)).unwrap(), book);
+}
+
+#[test]
+fn undeclared_markers_stay_custom() {
+    let book = usfm::parse(INPUT).unwrap();
+    let BookContents::Custom(p) = &book.contents[2] else {
+        panic!("expected a custom paragraph, got {:?}", book.contents[2]);
+    };
+    assert!(matches!(
+        &p.contents[5],
+        ParagraphContents::Custom(Custom {
+            milestone: None,
+            ..
+        })
+    ));
+}
+
+#[test]
+fn declared_markers_are_not_unknown() {
+    let config = ParserConfig {
+        unknown_markers: UnknownMarkers::Error,
+        ..config()
+    };
+    assert!(config.parse(INPUT).is_ok());
+    let err = config.parse("\\id GEN\n\\p \\zbar x\\zbar*\n").unwrap_err();
+    assert_eq!(err.marker.as_deref(), Some("zbar"));
+
+    let config = ParserConfig {
+        unknown_markers: UnknownMarkers::Strip,
+        ..config
+    };
+    assert_eq!(
+        config.parse(INPUT).unwrap(),
+        self::config().parse(INPUT).unwrap()
+    );
+}
+
+#[test]
+fn text_type_makes_custom_elements() {
+    let config = ParserConfig {
+        stylesheet: "\\Marker zs\n\\StyleType Paragraph\n\\TextType Section\n\n\\Marker zmt\n\\StyleType Paragraph\n\\TextType Title\n"
+            .parse()
+            .unwrap(),
+        ..Default::default()
+    };
+    let input = "\\id GEN\n\\zmt Genesis\n\\c 1\n\\zs The Creation\n\\p\n\\v 1 In the beginning\n";
+    let book = config.parse(input).unwrap();
+    assert_eq!(
+        book.contents[1],
+        BookContents::Element(Element {
+            ty: ElementType::Custom("zmt".into(), ElementKind::Title),
+            contents: vec![ElementContents::Line("Genesis".into())],
+        })
+    );
+    assert_eq!(
+        book.contents[3],
+        BookContents::Element(Element {
+            ty: ElementType::Custom("zs".into(), ElementKind::Section),
+            contents: vec![ElementContents::Line("The Creation".into())],
+        })
+    );
+    let view = book.view();
+    assert_eq!(view.titles().len(), 1);
+    assert_eq!(view.chapters()[0].sections[0].headings.len(), 1);
+    assert_eq!(config.parse(&format(&book)).unwrap(), book);
+}
+
+#[test]
+fn endmarker_decides_whether_markers_close() {
+    let config = ParserConfig {
+        stylesheet: "\\Marker zw\n\\StyleType Character\n\n\\Marker zfoo\n\\StyleType Character\n\\Endmarker zfoo*\n"
+            .parse()
+            .unwrap(),
+        ..Default::default()
+    };
+    let book = config
+        .parse("\\id GEN\n\\p\n\\v 1 \\zw word \\bd text\\bd*\n")
+        .unwrap();
+    let BookContents::Paragraph(p) = &book.contents[1] else {
+        panic!("expected a paragraph, got {:?}", book.contents[1]);
+    };
+    let ParagraphContents::Character(c) = &p.contents[1] else {
+        panic!("expected a character, got {:?}", p.contents[1]);
+    };
+    assert_eq!(c.ty, CharacterType::Custom("zw".into()));
+    assert_eq!(c.contents, vec![CharacterContents::Line("word ".into())]);
+    assert_eq!(config.parse(&format(&book)).unwrap(), book);
+
+    let err = config
+        .parse("\\id GEN\n\\p\n\\v 1 \\zfoo word \\bd text\\bd*\n")
+        .unwrap_err();
+    assert_eq!(err.marker.as_deref(), Some("zfoo"));
+    assert!(usfm::parse("\\id GEN\n\\p\n\\v 1 \\zw word \\bd text\\bd*\n").is_err());
+}
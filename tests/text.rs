@@ -0,0 +1,51 @@
+use usfm::{TextOptions, VerseText, parse};
+
+const INPUT: &str = "\\id PSA
+\\c 23
+\\d A Psalm of David.
+\\q1
+\\v 1 \\vp 1a\\vp* Yahweh is my \\nd shepherd\\nd*;\\f + \\fr 23:1 \\ft Or, LORD\\f*
+\\q2 I shall lack nothing.
+\\q1
+\\v 2 He makes me lie down\\x - \\xo 23:2 \\xt Ezek 34:15\\x* in green pastures.
+\\fig Sheep|src=\"sheep.jpg\" size=\"col\"\\fig*
+\\v 3-4 He restores my soul. \\va 3\\va*
+";
+
+#[test]
+fn collects_verse_text() {
+    let book = parse(INPUT).unwrap();
+    let verses: Vec<_> = book.verses().collect();
+    assert_eq!(
+        verses[0],
+        VerseText {
+            chapter: 23,
+            verse: 1.into(),
+            text: "Yahweh is my shepherd; I shall lack nothing.".into(),
+        }
+    );
+    assert_eq!(verses[1].text, "He makes me lie down in green pastures.");
+    assert_eq!(verses[2].verse, "3-4".parse().unwrap());
+    assert_eq!(verses[2].text, "He restores my soul.");
+    assert_eq!(book.verses().len(), 3);
+}
+
+#[test]
+fn includes_notes_and_numbers_on_request() {
+    let book = parse(INPUT).unwrap();
+    let options = TextOptions {
+        footnotes: true,
+        cross_refs: true,
+        published_verses: true,
+        figures: true,
+    };
+    let verses: Vec<_> = book.verses_with(options).map(|v| v.text).collect();
+    assert_eq!(
+        verses,
+        vec![
+            "1a Yahweh is my shepherd; Or, LORD I shall lack nothing.",
+            "He makes me lie down Ezek 34:15 in green pastures. Sheep",
+            "He restores my soul.",
+        ]
+    );
+}
@@ -79,10 +79,7 @@ fn parse_sample_translations() {
 
     // Print report
     eprintln!();
-    eprintln!(
-        "{:<20} {:<12} {:<40} {}",
-        "Script", "Code", "File", "Result"
-    );
+    eprintln!("{:<20} {:<12} {:<40} Result", "Script", "Code", "File");
     eprintln!("{}", "-".repeat(90));
 
     let mut pass_count = 0;
@@ -95,13 +92,7 @@ fn parse_sample_translations() {
         } else {
             fail_count += 1;
         }
-        eprintln!(
-            "{:<20} {:<12} {:<40} {}",
-            r.script,
-            r.code,
-            r.file,
-            status
-        );
+        eprintln!("{:<20} {:<12} {:<40} {}", r.script, r.code, r.file, status);
         if let Some(err) = &r.error {
             eprintln!("    Error: {}", err);
         }
@@ -177,7 +168,7 @@ fn process_translation(translation: &Translation) -> TestResult {
 
         let content = match String::from_utf8(raw.clone()) {
             Ok(s) => s,
-            Err(_) => return make_error("(read)", format!("Not valid UTF-8.")),
+            Err(_) => return make_error("(read)", "Not valid UTF-8.".into()),
         };
 
         (name, content)
@@ -191,7 +182,10 @@ fn process_translation(translation: &Translation) -> TestResult {
             passed: true,
             error: None,
         },
-        Err(msg) => make_error(&file_name, msg),
+        Err(e) => {
+            let (_, diagnostics) = usfm::parse_with_recovery(&content);
+            let summary = format!("{} region(s) skipped in recovery mode", diagnostics.len());
+            make_error(&file_name, format!("{}{}", e.render(&file_name), summary))
+        }
     }
 }
-
@@ -0,0 +1,142 @@
+#![cfg(feature = "serde")]
+use std::{fs::read_to_string, path::PathBuf};
+use usfm::{
+    BookContents, ParseErrorKind, format, parse,
+    usj::{Usj, from_usj, to_usj},
+};
+
+static MANIFEST_DIR: &str = env!("CARGO_MANIFEST_DIR");
+
+fn read(path: &str) -> String {
+    read_to_string(PathBuf::from(MANIFEST_DIR).join(path)).unwrap()
+}
+
+#[test]
+fn round_trips_samples_through_usj() {
+    let schema = serde_json::from_str(&read("tests/usj.schema.json")).unwrap();
+    let validator = jsonschema::validator_for(&schema).unwrap();
+    for name in ["sample.usfm", "02-GENeng-web.usfm", "02-GENengwebpb.usfm"] {
+        let mut book = parse(&read(&format!("usfm/{name}"))).unwrap();
+        let json = serde_json::to_string(&to_usj(&book)).unwrap();
+        let value = serde_json::from_str(&json).unwrap();
+        if let Err(error) = validator.validate(&value) {
+            panic!("{name}: {error} at {}", error.instance_path());
+        }
+
+        // Read from the string, as a `serde_json::Value` sorts the attributes of a node.
+        let usj: Usj = serde_json::from_str(&json).unwrap();
+        let read_back = from_usj(&usj).unwrap();
+        book.contents
+            .retain(|content| !matches!(content, BookContents::Usfm(_)));
+        assert_eq!(read_back, book, "{name}");
+        assert_eq!(format(&read_back), format(&book), "{name}");
+    }
+}
+
+#[test]
+fn writes_attributes_of_words_milestones_and_figures() {
+    let book = parse(
+        "\\id MAT\n\\c 5\n\\p\n\\v 3 \\qt-s |who=\"Jesus\"\\*\\w Blessed|lemma=\"makarios\" strong=\"G3107\"\\w* are the poor\\qt-e\\*\n\\fig The Mount|src=\"mount.jpg\" size=\"span\"\\fig*\n",
+    )
+    .unwrap();
+    let json = serde_json::to_value(to_usj(&book)).unwrap();
+    assert_eq!(
+        json["content"][1],
+        serde_json::json!({"type": "chapter", "marker": "c", "number": "5", "sid": "MAT 5"})
+    );
+    assert_eq!(
+        json["content"][2]["content"],
+        serde_json::json!([
+            {"type": "verse", "marker": "v", "number": "3", "sid": "MAT 5:3"},
+            {"type": "ms", "marker": "qt1-s", "who": "Jesus"},
+            {"type": "char", "marker": "w", "lemma": "makarios", "strong": "G3107", "content": ["Blessed"]},
+            " are the poor",
+            {"type": "ms", "marker": "qt1-e"},
+            {"type": "figure", "marker": "fig", "file": "mount.jpg", "size": "span", "content": ["The Mount"]},
+        ])
+    );
+}
+
+#[test]
+fn reads_usj_from_other_tools() {
+    let usj: Usj = serde_json::from_str(
+        r#"{"type": "USJ", "version": "3.1", "content": [
+            {"type": "book", "marker": "id", "code": "JHN", "content": []},
+            {"type": "chapter", "marker": "c", "number": "3", "pubnumber": "III", "sid": "JHN 3"},
+            {"type": "para", "marker": "p", "content": [
+                {"type": "verse", "marker": "v", "number": "16", "sid": "JHN 3:16"},
+                "For God so loved ",
+                {"type": "ref", "loc": "JHN 1:10", "content": ["the world"]},
+                {"type": "note", "marker": "f", "caller": "+", "content": [
+                    "Or, ",
+                    {"type": "char", "marker": "fq", "content": ["cosmos"]}
+                ]}
+            ]}
+        ]}"#,
+    )
+    .unwrap();
+    assert_eq!(
+        format(&from_usj(&usj).unwrap()),
+        format(&parse("\\id JHN\n\\c 3\n\\cp III\n\\p\n\\v 16 For God so loved \\ref the world|loc=\"JHN 1:10\"\\ref*\\f + \\ft Or, \\fq cosmos\\f*\n").unwrap())
+    );
+
+    let mut usj = usj;
+    usj.content.truncate(1);
+    let serde_json::Value::Object(mut book) = serde_json::to_value(&usj.content[0]).unwrap() else {
+        unreachable!()
+    };
+    book.insert("code".to_string(), "XYZ".into());
+    usj.content[0] = serde_json::from_value(book.into()).unwrap();
+    let err = from_usj(&usj).unwrap_err();
+    assert_eq!(
+        err.kind,
+        ParseErrorKind::Unrecognized {
+            what: "book identifier",
+            value: "XYZ".to_string()
+        }
+    );
+    assert_eq!(err.path, "/content/0/code");
+}
+
+#[test]
+fn locates_errors_by_json_pointer() {
+    let usj: Usj = serde_json::from_str(
+        r#"{"type": "USJ", "version": "3.1", "content": [
+            {"type": "book", "marker": "id", "code": "JHN", "content": []},
+            {"type": "para", "marker": "p", "content": [
+                "Text ",
+                {"type": "char", "marker": "bd", "content": [
+                    {"type": "verse", "marker": "v", "number": "x"}
+                ]}
+            ]}
+        ]}"#,
+    )
+    .unwrap();
+    let err = from_usj(&usj).unwrap_err();
+    assert_eq!(err.path, "/content/1/content/1/content/0/number");
+    assert_eq!(
+        err.to_string(),
+        "/content/1/content/1/content/0/number: unrecognized verse number `x`"
+    );
+}
+
+#[test]
+fn writes_references_and_paragraph_styles_as_usx_does() {
+    let book = parse(
+        "\\id JHN\n\\c 3\n\\mi\n\\v 16 See \\ref John 1:10|loc=\"JHN 1:10\"\\ref* and \\ref JHN 1:1\\ref*\n",
+    )
+    .unwrap();
+    let json = serde_json::to_value(to_usj(&book)).unwrap();
+    assert_eq!(
+        json["content"][2],
+        serde_json::json!({"type": "para", "marker": "mi", "content": [
+            {"type": "verse", "marker": "v", "number": "16", "sid": "JHN 3:16"},
+            "See ",
+            {"type": "ref", "loc": "JHN 1:10", "content": ["John 1:10"]},
+            " and ",
+            {"type": "ref", "loc": "JHN 1:1", "content": ["JHN 1:1"]},
+        ]})
+    );
+    let usj: Usj = serde_json::from_value(json).unwrap();
+    assert_eq!(from_usj(&usj).unwrap(), book);
+}
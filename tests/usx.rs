@@ -0,0 +1,232 @@
+mod common;
+
+use common::{MANIFEST_DIR, assert_milestones_pair, read};
+use std::{
+    fs::{remove_file, write},
+    path::PathBuf,
+    process::Command,
+};
+use usfm::{
+    Book, BookContents, ElementType, ParseErrorKind, format, parse,
+    usx::{from_usx, to_usx},
+};
+
+#[test]
+fn web_genesis_to_usx() {
+    let genesis = parse(&read("02-GENeng-web.usfm")).unwrap();
+    let usx = to_usx(&genesis);
+    let doc = roxmltree::Document::parse(&usx).unwrap();
+    let root = doc.root_element();
+    assert!(root.has_tag_name("usx"));
+    assert_eq!(root.attribute("version"), Some("3.0"));
+    let book = root.first_element_child().unwrap();
+    assert_eq!(book.attribute("code"), Some("GEN"));
+
+    assert_eq!(assert_milestones_pair(&doc, "chapter", "sid", "eid"), 50);
+    assert_eq!(
+        assert_milestones_pair(&doc, "verse", "sid", "eid"),
+        genesis.verses().len()
+    );
+    let first = doc.descendants().find(|n| n.has_tag_name("verse")).unwrap();
+    assert_eq!(first.attribute("sid"), Some("GEN 1:1"));
+    assert_eq!(first.parent().unwrap().attribute("style"), Some("p"));
+
+    let note = doc.descendants().find(|n| n.has_tag_name("note")).unwrap();
+    assert_eq!(note.attribute("caller"), Some("+"));
+    assert_eq!(note.attribute("style"), Some("f"));
+    let fr = note.first_element_child().unwrap();
+    assert_eq!(fr.attribute("style"), Some("fr"));
+    assert_eq!(fr.text(), Some("1:1  "));
+}
+
+#[test]
+fn sample_to_usx() {
+    let book = parse(&read("sample.usfm")).unwrap();
+    let usx = to_usx(&book);
+    let doc = roxmltree::Document::parse(&usx).unwrap();
+    assert_milestones_pair(&doc, "chapter", "sid", "eid");
+    assert_milestones_pair(&doc, "verse", "sid", "eid");
+
+    let count = |name: &str| doc.descendants().filter(|n| n.has_tag_name(name)).count();
+    assert_eq!(count("table"), 1);
+    assert_eq!(count("row"), 4);
+    assert_eq!(count("sidebar"), 1);
+    assert_eq!(count("periph"), 1);
+    let sidebar = doc
+        .descendants()
+        .find(|n| n.has_tag_name("sidebar"))
+        .unwrap();
+    assert_eq!(sidebar.attribute("category"), Some("History"));
+    assert!(
+        doc.descendants()
+            .any(|n| n.has_tag_name("char") && n.attribute("style") == Some("cat"))
+    );
+    let chapter = doc
+        .descendants()
+        .find(|n| n.has_tag_name("chapter"))
+        .unwrap();
+    assert_eq!(chapter.attribute("pubnumber"), Some("I"));
+    assert!(count("ms") >= 2);
+
+    let figure = doc
+        .descendants()
+        .find(|n| n.has_tag_name("figure") && n.text().is_some())
+        .unwrap();
+    assert_eq!(figure.attribute("file"), Some("creation.jpg"));
+    assert_eq!(figure.text(), Some("A dove over the waters"));
+
+    let cell = doc.descendants().find(|n| n.has_tag_name("cell")).unwrap();
+    assert!(cell.attribute("style").unwrap().starts_with("th"));
+    assert!(cell.attribute("align").is_some());
+}
+
+/// Checks the USX of every sample against the schema in `tests/usx.rng` with `xmllint`.
+#[test]
+fn samples_are_valid_usx() {
+    let schema = PathBuf::from(MANIFEST_DIR).join("tests").join("usx.rng");
+    for name in ["sample.usfm", "02-GENeng-web.usfm", "02-GENengwebpb.usfm"] {
+        let path = std::env::temp_dir().join(format!("usfm-{}-{name}.usx", std::process::id()));
+        write(&path, to_usx(&parse(&read(name)).unwrap())).unwrap();
+        let output = Command::new("xmllint")
+            .arg("--noout")
+            .arg("--relaxng")
+            .arg(&schema)
+            .arg(&path)
+            .output();
+        remove_file(&path).unwrap();
+        let output = output.expect("xmllint (libxml2-utils) is needed to validate USX");
+        assert!(
+            output.status.success(),
+            "{name}: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+}
+
+#[test]
+fn ends_verses_in_their_last_paragraph() {
+    let book = parse(
+        "\\id PSA\n\\c 23\n\\q1\n\\v 1 Yahweh is my shepherd;\n\\q2 I shall lack nothing.\n\\s1 Heading\n\\p\n\\v 2 He & I\n",
+    )
+    .unwrap();
+    let usx = to_usx(&book);
+    assert!(usx.contains(
+        "<para style=\"q2\">I shall lack nothing.<verse eid=\"PSA 23:1\"/></para>\n<para style=\"s1\">Heading</para>"
+    ), "{usx}");
+    assert!(
+        usx.contains(
+            "He &amp; I<verse eid=\"PSA 23:2\"/></para>\n<chapter eid=\"PSA 23\"/>\n</usx>"
+        ),
+        "{usx}"
+    );
+}
+
+#[test]
+fn round_trips_samples_through_usx() {
+    for name in ["sample.usfm", "02-GENeng-web.usfm", "02-GENengwebpb.usfm"] {
+        let mut book = parse(&read(name)).unwrap();
+        let read_back = from_usx(&to_usx(&book)).unwrap();
+        book.contents
+            .retain(|content| !matches!(content, BookContents::Usfm(_)));
+        move_cp_after_its_chapter(&mut book);
+        assert_eq!(read_back, book, "{name}");
+        assert_eq!(format(&read_back), format(&book), "{name}");
+    }
+}
+
+/// Moves the first `\cp` of each chapter, or one before the first chapter, to just after the
+/// chapter's `\c` and `\ca`, where it is read back from the chapter's `pubnumber`.
+fn move_cp_after_its_chapter(book: &mut Book) {
+    fn after_header(contents: &[BookContents], chapter: usize) -> usize {
+        match contents.get(chapter + 1) {
+            Some(BookContents::AltChapter(_)) => chapter + 2,
+            _ => chapter + 1,
+        }
+    }
+    let mut carried = None;
+    let mut first_chapter = None;
+    let mut chapter = None;
+    let mut contents = Vec::new();
+    for content in std::mem::take(&mut book.contents) {
+        match content {
+            BookContents::Element(e) if e.ty == ElementType::ChapterPublishedLabel => match chapter
+                .take()
+            {
+                Some(at) => contents.insert(after_header(&contents, at), BookContents::Element(e)),
+                None if first_chapter.is_none() => {
+                    carried = carried.or(Some(BookContents::Element(e)))
+                }
+                None => {}
+            },
+            BookContents::Chapter(_) => {
+                first_chapter = first_chapter.or(Some(contents.len()));
+                chapter = Some(contents.len());
+                contents.push(content);
+            }
+            _ => contents.push(content),
+        }
+    }
+    if let (Some(cp), Some(at)) = (carried, first_chapter) {
+        let at = after_header(&contents, at);
+        if !matches!(contents.get(at), Some(BookContents::Element(e)) if e.ty == ElementType::ChapterPublishedLabel)
+        {
+            contents.insert(at, cp);
+        }
+    }
+    book.contents = contents;
+}
+
+#[test]
+fn writes_a_cp_on_the_chapter_it_is_in() {
+    let input = "\\id GEN\n\\c 1\n\\p\n\\v 1 In the beginning.\n\\c 2\n\\cp B\n\\p\n\\v 1 Thus.\n";
+    let book = parse(input).unwrap();
+    assert_eq!(from_usx(&to_usx(&book)).unwrap(), book);
+
+    let later =
+        parse(&input.replace("\\cp B\n\\p\n\\v 1 Thus.\n", "\\p\n\\v 1 Thus.\n\\cp B\n")).unwrap();
+    let usx = to_usx(&later);
+    assert!(
+        usx.contains(r#"<chapter number="2" style="c" pubnumber="B" sid="GEN 2"/>"#),
+        "{usx}"
+    );
+    assert_eq!(from_usx(&usx).unwrap(), book);
+}
+
+#[test]
+fn reads_usx_from_other_tools() {
+    let book = from_usx(
+        r#"<?xml version="1.0" encoding="utf-8"?>
+<usx version="3.0">
+  <book code="JHN" style="id">World English Bible</book>
+  <chapter number="3" style="c" pubnumber="III" sid="JHN 3"/>
+  <para style="p">
+    <verse number="16" style="v" altnumber="15" sid="JHN 3:16"/>For God so loved <ref loc="JHN 1:1">the world</ref><note caller="+" style="f">Or, <char style="fq">cosmos</char></note><verse eid="JHN 3:16"/></para>
+  <chapter eid="JHN 3"/>
+</usx>"#,
+    )
+    .unwrap();
+    assert_eq!(
+        format(&book),
+        parse("\\id JHN World English Bible\n\\c 3\n\\cp III\n\\p\n\\v 16 \\va 15\\va*For God so loved \\ref the world|loc=\"JHN 1:1\"\\ref*\\f + \\ft Or, \\fq cosmos\\f*\n")
+            .map(|book| format(&book))
+            .unwrap()
+    );
+}
+
+#[test]
+fn reports_usx_errors_at_their_position() {
+    let err =
+        from_usx("<usx version=\"3.0\">\n<book code=\"GEN\" style=\"id\">\n</usx>").unwrap_err();
+    assert_eq!(*err.kind, ParseErrorKind::Syntax);
+    assert_eq!(err.line, 3);
+
+    let err = from_usx("<usx>\n<chapter number=\"x\" style=\"c\"/></usx>").unwrap_err();
+    assert_eq!(
+        *err.kind,
+        ParseErrorKind::Unrecognized {
+            what: "chapter number",
+            value: "x".to_string()
+        }
+    );
+    assert_eq!((err.line, err.column), (2, 1));
+}
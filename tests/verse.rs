@@ -0,0 +1,97 @@
+use usfm::{BookContents, ParagraphContents, VerseNumber, format, parse};
+
+fn verse(s: &str) -> VerseNumber {
+    s.parse().unwrap()
+}
+
+#[test]
+fn parses_verse_numbers() {
+    assert_eq!(verse("4"), VerseNumber::new(4));
+    assert_eq!(verse("1-3"), VerseNumber::range(1, 3));
+    let segmented = verse("4b-5a");
+    assert_eq!((segmented.start, segmented.start_segment), (4, Some('b')));
+    assert_eq!((segmented.end, segmented.end_segment), (5, Some('a')));
+    assert!(segmented.is_range());
+    assert!(!verse("4a").is_range());
+    assert_eq!(verse("4a").to_string(), "4a");
+}
+
+#[test]
+fn strips_and_remembers_directional_marks() {
+    let rtl = verse("\u{200F}1-2\u{200F}");
+    assert_eq!(
+        rtl,
+        VerseNumber {
+            spelling: Some("\u{200F}1-2\u{200F}".into()),
+            ..VerseNumber::range(1, 2)
+        }
+    );
+    assert_eq!(rtl.to_string(), "1-2");
+    assert_eq!(rtl.spelling(), "\u{200F}1-2\u{200F}");
+    assert_eq!(verse("12").spelling, None);
+}
+
+#[test]
+fn rejects_malformed_verse_numbers() {
+    for s in ["a", "1-", "1ab", "3-1", "1-2-3", "70000"] {
+        assert!(s.parse::<VerseNumber>().is_err(), "{s}");
+    }
+}
+
+#[test]
+fn keeps_verse_numbers_the_grammar_accepts_as_written() {
+    for (s, start, end) in [("1-2-3", 1, 3), ("1ab", 1, 1), ("a", 0, 0), ("3-1", 3, 3)] {
+        let input = format!("\\id GEN\n\\p\n\\v {s} text\n");
+        let book = parse(&input).unwrap();
+        let BookContents::Paragraph(p) = &book.contents[1] else {
+            panic!("expected a paragraph, got {:?}", book.contents[1]);
+        };
+        let ParagraphContents::Verse(verse) = &p.contents[0] else {
+            panic!("expected a verse, got {:?}", p.contents[0]);
+        };
+        assert_eq!((verse.start, verse.end), (start, end), "{s}");
+        assert_eq!(verse.spelling(), s);
+        assert_eq!(parse(&format(&book)).unwrap(), book);
+    }
+}
+
+#[test]
+fn orders_and_contains() {
+    let mut verses = vec![
+        verse("10"),
+        verse("4b"),
+        verse("4"),
+        verse("1-3"),
+        verse("4a"),
+    ];
+    verses.sort();
+    assert_eq!(
+        verses,
+        vec![
+            verse("1-3"),
+            verse("4"),
+            verse("4a"),
+            verse("4b"),
+            verse("10")
+        ]
+    );
+
+    assert!(verse("1-3").contains(2));
+    assert!(!verse("1-3").contains(4));
+    assert!(verse("4-5").contains_range(&verse("4b")));
+    assert!(verse("4a-5").contains_range(&verse("4b-5a")));
+    assert!(!verse("4a").contains_range(&verse("4")));
+    assert!(!verse("4b-5a").contains_range(&verse("4a")));
+}
+
+#[test]
+fn verse_spelling_round_trips() {
+    let input = "\\id GEN\n\\p\n\\v \u{200F}3-4\u{200F} text\n\\v 5a more\n";
+    let book = parse(input).unwrap();
+    let BookContents::Paragraph(p) = &book.contents[1] else {
+        panic!("expected a paragraph, got {:?}", book.contents[1]);
+    };
+    assert_eq!(p.contents[2], ParagraphContents::Verse(verse("5a")));
+    assert!(format(&book).contains("\\v \u{200F}3-4\u{200F} text"));
+    assert_eq!(parse(&format(&book)).unwrap(), book);
+}
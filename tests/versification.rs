@@ -0,0 +1,156 @@
+use std::{fs::read_to_string, path::PathBuf};
+use usfm::{BookIdentifier::*, ParseErrorKind, RefRange, Reference, Versification};
+
+const ORG: &str = "\
+# Versification  \"Original\"
+GEN 1:31 2:25 3:24 31:54 32:33
+PSA 1:6 2:12 3:9 51:21
+MAL 1:14 2:17 3:24
+";
+
+const ENG: &str = "\
+# Versification  \"English\"
+# Lines are the last verse of each chapter.
+GEN 1:31 2:25 3:24 31:55 32:32  # trailing comment
+PSA 1:6 2:12 3:8 51:19
+MAL 1:14 2:17 3:18 4:6
+-PSA 3:9
+*PSA 3:1,-,a,b
+# Mappings to the original versification
+GEN 31:55 = GEN 32:1
+GEN 32:1-32 = GEN 32:2-33
+PSA 3:1-8 = PSA 3:2-9
+#! PSA 51:1-2 = PSA 51:3
+&MAL 4:1-6 = MAL 3:19-24
+";
+
+#[test]
+fn reads_chapter_and_verse_counts() {
+    let eng: Versification = ENG.parse().unwrap();
+    assert_eq!(eng.name(), Some("English"));
+    assert_eq!(eng.last_chapter(Malachi), Some(4));
+    assert_eq!(eng.last_verse(Genesis, 31), Some(55));
+    assert_eq!(eng.last_verse(Genesis, 4), None);
+    assert_eq!(eng.last_verse(Genesis, 33), None);
+    assert_eq!(eng.last_verse(Exodus, 1), None);
+    assert!(eng.is_excluded(Psalms, 3, 9));
+    assert!(!eng.is_excluded(Psalms, 3, 8));
+}
+
+#[test]
+fn maps_between_versifications() {
+    let org: Versification = ORG.parse().unwrap();
+    let eng: Versification = ENG.parse().unwrap();
+
+    assert_eq!(
+        eng.map(&Reference::verse(Genesis, 31, 55), &org),
+        Reference::verse(Genesis, 32, 1)
+    );
+    assert_eq!(
+        org.map(&Reference::verse(Genesis, 32, 33), &eng),
+        Reference::verse(Genesis, 32, 32)
+    );
+    assert_eq!(
+        eng.map(&Reference::verse(Malachi, 4, 6), &org),
+        Reference::verse(Malachi, 3, 24)
+    );
+    // Both verses of the `#!` mapping land on the same original verse.
+    assert_eq!(
+        eng.to_original(&Reference::verse(Psalms, 51, 2)),
+        Reference::verse(Psalms, 51, 3)
+    );
+    assert_eq!(
+        eng.from_original(&Reference::verse(Psalms, 51, 3)),
+        Reference::verse(Psalms, 51, 1)
+    );
+    // Verses without a mapping are the same in both.
+    let unmapped = Reference::verse(Genesis, 1, 1);
+    assert_eq!(eng.map(&unmapped, &org), unmapped);
+    let chapter = Reference::chapter(Psalms, 3);
+    assert_eq!(eng.map(&chapter, &org), chapter);
+
+    let psalm: RefRange = "PSA 3:1-8".parse().unwrap();
+    assert_eq!(eng.map_range(&psalm, &org), "PSA 3:2-9".parse().unwrap());
+    let verses: Reference = "PSA 3:1-8".parse::<RefRange>().unwrap().start;
+    assert_eq!(eng.map(&verses, &org), Reference::verse(Psalms, 3, 2));
+    let verse_range = Reference::verse(Psalms, 3, usfm::VerseNumber::range(1, 8));
+    assert_eq!(
+        eng.map(&verse_range, &org),
+        Reference::verse(Psalms, 3, usfm::VerseNumber::range(2, 9))
+    );
+}
+
+#[test]
+fn rejects_malformed_lines() {
+    let err = "GEN 1:31\nXYZ 1:2\n".parse::<Versification>().unwrap_err();
+    assert_eq!(
+        *err.kind,
+        ParseErrorKind::Unrecognized {
+            what: "versification line",
+            value: "XYZ 1:2".into()
+        }
+    );
+    assert_eq!((err.line, err.column), (2, 1));
+    assert!("GEN 1:31 2\n".parse::<Versification>().is_err());
+    assert!("GEN 1:1 = EXO\n".parse::<Versification>().is_err());
+}
+
+/// Reads one of the `.vrs` files that Paratext ships, copied unchanged from
+/// `SIL.Scripture/Resources` in libpalaso into `tests/vrs`.
+fn paratext_vrs(name: &str) -> Versification {
+    let file = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(format!("tests/vrs/{name}.vrs"));
+    read_to_string(file).unwrap().parse().unwrap()
+}
+
+#[test]
+#[ignore = "needs org, eng, lxx, vul, rso and rsc .vrs from libpalaso in tests/vrs"]
+fn reads_paratext_versifications() {
+    let org = paratext_vrs("org");
+    let eng = paratext_vrs("eng");
+    let others = ["lxx", "vul", "rso", "rsc"].map(|name| (name, paratext_vrs(name)));
+
+    assert_eq!(org.last_chapter(Psalms), Some(150));
+    assert_eq!(org.last_chapter(Malachi), Some(3));
+    assert_eq!(eng.last_chapter(Malachi), Some(4));
+    assert_eq!(org.last_verse(Psalms, 51), Some(21));
+    assert_eq!(eng.last_verse(Psalms, 51), Some(19));
+
+    // English leaves the two verses of the Hebrew superscription of Psalm 51 unnumbered.
+    assert_eq!(
+        eng.map(&Reference::verse(Psalms, 51, 1), &org),
+        Reference::verse(Psalms, 51, 3)
+    );
+    assert_eq!(
+        eng.map(&Reference::verse(Psalms, 51, 19), &org),
+        Reference::verse(Psalms, 51, 21)
+    );
+    assert_eq!(
+        eng.map(&Reference::verse(Genesis, 31, 55), &org),
+        Reference::verse(Genesis, 32, 1)
+    );
+    assert_eq!(
+        eng.map(&Reference::verse(Malachi, 4, 1), &org),
+        Reference::verse(Malachi, 3, 19)
+    );
+
+    // The Septuagint, the Vulgate and both Russian Bibles join Psalms 9 and 10 as the Greek does.
+    for (name, vrs) in &others {
+        assert_eq!(vrs.last_chapter(Genesis), Some(50), "{name}");
+        assert_eq!(
+            vrs.map(&Reference::verse(Psalms, 9, 22), &org),
+            Reference::verse(Psalms, 10, 1),
+            "{name}"
+        );
+        assert_eq!(
+            org.map(&Reference::verse(Psalms, 23, 1), vrs),
+            Reference::verse(Psalms, 22, 1),
+            "{name}"
+        );
+    }
+    // The Russian Synodal puts the closing doxology of Romans at the end of chapter 14.
+    let (_, rsc) = &others[3];
+    assert_eq!(
+        rsc.map(&Reference::verse(Romans, 14, 24), &org),
+        Reference::verse(Romans, 16, 25)
+    );
+}
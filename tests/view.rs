@@ -0,0 +1,84 @@
+use std::fs::read_to_string;
+
+use usfm::{BookContents, ElementType, parse};
+
+const INPUT: &str = "\\id MRK
+\\usfm 3.0
+\\h Mark
+\\toc1 The Good News According to Mark
+\\mt1 Mark
+\\imt Introduction
+\\ip Mark tells the story of Jesus.
+\\ie
+\\c 1
+\\cd The ministry of John.
+\\ms Part One
+\\mr (1:1-8:26)
+\\s1 John the Baptizer
+\\r (Matthew 3:1-12)
+\\p
+\\v 1 The beginning of the Good News.
+\\s1 The Baptism of Jesus
+\\p
+\\v 9 In those days, Jesus came.
+\\c 2
+\\p
+\\v 1 When he entered again into Capernaum.
+";
+
+#[test]
+fn groups_book_into_hierarchy() {
+    let book = parse(INPUT).unwrap();
+    let view = book.view();
+
+    assert_eq!(view.header().len(), 4);
+    assert!(matches!(view.header()[0], BookContents::Id { .. }));
+    assert_eq!(view.titles().len(), 1);
+    assert_eq!(view.titles()[0].ty, ElementType::MajorTitle(1));
+    assert_eq!(view.intro().len(), 3);
+
+    let chapters: Vec<_> = view.chapters().iter().map(|c| c.number).collect();
+    assert_eq!(chapters, vec![1, 2]);
+    let chapter = view.chapter(1).unwrap();
+    assert_eq!(chapter.sections.len(), 3);
+    // The chapter description comes before any heading.
+    assert!(chapter.sections[0].headings.is_empty());
+    assert_eq!(chapter.sections[0].contents.len(), 1);
+    let types: Vec<_> = chapter.sections[1].headings.iter().map(|h| &h.ty).collect();
+    assert_eq!(
+        types,
+        vec![
+            &ElementType::MajorSection(1),
+            &ElementType::MajorReference,
+            &ElementType::Section(1),
+            &ElementType::Parallel,
+        ]
+    );
+    assert_eq!(chapter.sections[1].contents.len(), 1);
+    assert_eq!(
+        chapter.sections[2].heading().unwrap().ty,
+        ElementType::Section(1)
+    );
+    assert_eq!(view.sections().count(), 4);
+    assert!(view.chapter(3).is_none());
+
+    // Nodes are borrowed from the book rather than copied.
+    assert!(std::ptr::eq(view.header()[0], &book.contents[0]));
+}
+
+#[test]
+fn views_whole_books() {
+    let input = read_to_string("usfm/02-GENeng-web.usfm").unwrap();
+    let book = parse(&input).unwrap();
+    let view = book.view();
+    assert_eq!(view.chapters().len(), 50);
+    let nodes = view.header().len()
+        + view.titles().len()
+        + view.intro().len()
+        + view
+            .sections()
+            .map(|s| s.headings.len() + s.contents.len())
+            .sum::<usize>()
+        + view.chapters().len();
+    assert_eq!(nodes, book.contents.len());
+}
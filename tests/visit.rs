@@ -0,0 +1,103 @@
+use rkyv::rancor::Error;
+use std::{fs::read_to_string, path::PathBuf};
+use usfm::fold::Fold;
+use usfm::visit::{self, Visit};
+use usfm::visit_mut::VisitMut;
+use usfm::{
+    ArchivedBook, ArchivedFootnote, ArchivedVerseNumber, Book, Footnote, VerseNumber, parse,
+};
+
+const MANIFEST_DIR: &str = env!("CARGO_MANIFEST_DIR");
+
+fn parse_web_genesis() -> Book {
+    let file = PathBuf::from(MANIFEST_DIR).join("usfm/02-GENeng-web.usfm");
+    let input = read_to_string(file).unwrap();
+    parse(&input).unwrap()
+}
+
+#[derive(Default)]
+struct Counts {
+    chapters: usize,
+    verses: usize,
+    footnotes: usize,
+}
+
+impl<'ast> Visit<'ast> for Counts {
+    fn visit_chapter(&mut self, _number: u16) {
+        self.chapters += 1;
+    }
+
+    fn visit_verse(&mut self, _node: &'ast VerseNumber) {
+        self.verses += 1;
+    }
+
+    fn visit_footnote(&mut self, node: &'ast Footnote) {
+        self.footnotes += 1;
+        visit::visit_footnote(self, node);
+    }
+}
+
+impl<'ast> visit::archived::Visit<'ast> for Counts {
+    fn visit_chapter(&mut self, _number: u16) {
+        self.chapters += 1;
+    }
+
+    fn visit_verse(&mut self, _node: &'ast ArchivedVerseNumber) {
+        self.verses += 1;
+    }
+
+    fn visit_footnote(&mut self, node: &'ast ArchivedFootnote) {
+        self.footnotes += 1;
+        visit::archived::visit_footnote(self, node);
+    }
+}
+
+#[test]
+fn visits_owned_and_archived_books() {
+    let genesis = parse_web_genesis();
+    let mut counts = Counts::default();
+    counts.visit_book(&genesis);
+    assert_eq!(counts.chapters, 50);
+    assert_eq!(counts.verses, genesis.verses().len());
+    assert!(counts.footnotes > 0);
+
+    let bytes = rkyv::to_bytes::<Error>(&genesis).unwrap();
+    let archived = rkyv::access::<ArchivedBook, Error>(&bytes).unwrap();
+    let mut archived_counts = Counts::default();
+    visit::archived::Visit::visit_book(&mut archived_counts, archived);
+    assert_eq!(archived_counts.chapters, counts.chapters);
+    assert_eq!(archived_counts.verses, counts.verses);
+    assert_eq!(archived_counts.footnotes, counts.footnotes);
+}
+
+struct Upper;
+
+impl VisitMut for Upper {
+    fn visit_text_mut(&mut self, text: &mut String) {
+        *text = text.to_uppercase();
+    }
+}
+
+impl Fold for Upper {
+    fn fold_text(&mut self, text: String) -> String {
+        text.to_uppercase()
+    }
+}
+
+struct Identity;
+
+impl Fold for Identity {}
+
+#[test]
+fn rewrites_text() {
+    let genesis = parse_web_genesis();
+    assert_eq!(Identity.fold_book(genesis.clone()), genesis);
+
+    let mut visited = genesis.clone();
+    Upper.visit_book_mut(&mut visited);
+    let folded = Upper.fold_book(genesis.clone());
+    assert_eq!(visited, folded);
+    assert_ne!(folded, genesis);
+    let text = folded.verses().next().unwrap().text;
+    assert_eq!(text, text.to_uppercase());
+}